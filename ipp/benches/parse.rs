@@ -0,0 +1,103 @@
+//!
+//! Parser allocation and throughput benchmark.
+//!
+//! Run with `cargo bench -p ipp --bench parse`. For every attribute shape it reports the marginal number of
+//! allocations per attribute and the parse time of a large Get-Jobs style response. Single-valued attributes
+//! skip the per-attribute value list, multi-valued attributes still build one and show its cost.
+//! To compare with another revision, run the same benchmark file in a checkout of that revision.
+//!
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use ipp::{
+    model::{DelimiterTag, ValueTag},
+    parser::IppParser,
+    reader::IppReader,
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const JOBS: usize = 10;
+const ITERATIONS: u32 = 200;
+
+// Get-Jobs style response with the given number of integer attributes, each with the given number of values
+fn get_jobs_response(attributes: usize, values: usize) -> Vec<u8> {
+    let mut job = vec![u8::from(DelimiterTag::JobAttributes)];
+    for i in 0..attributes {
+        let name = format!("attr-{i:04}");
+        for v in 0..values {
+            let name = if v == 0 { name.as_str() } else { "" };
+            job.push(ValueTag::Integer as u8);
+            job.extend((name.len() as u16).to_be_bytes());
+            job.extend(name.as_bytes());
+            job.extend(4u16.to_be_bytes());
+            job.extend((i as i32).to_be_bytes());
+        }
+    }
+
+    let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+    data.extend(job.repeat(JOBS));
+    data.push(u8::from(DelimiterTag::EndOfAttributes));
+    data
+}
+
+fn parse(data: Vec<u8>) {
+    let response = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+    black_box(response);
+}
+
+fn count_allocations(data: Vec<u8>) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    parse(data);
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn main() {
+    println!(
+        "{:<16} {:>24} {:>20}",
+        "values/attribute", "allocations/attribute", "parse time"
+    );
+
+    for values in [1, 2, 4] {
+        let small = count_allocations(get_jobs_response(20, values));
+        let large = count_allocations(get_jobs_response(40, values));
+        let per_attribute = (large - small) as f64 / (20 * JOBS) as f64;
+
+        let data = get_jobs_response(500, values);
+        let mut elapsed = Duration::ZERO;
+        for _ in 0..ITERATIONS {
+            let data = data.clone();
+            let start = Instant::now();
+            parse(data);
+            elapsed += start.elapsed();
+        }
+
+        println!("{values:<16} {per_attribute:>24.2} {:>17.2?}", elapsed / ITERATIONS);
+    }
+}
@@ -1,14 +1,22 @@
 //!
 //! Attribute-related structs
 //!
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    time::Duration,
+};
 
 use crate::parser::IppParseError;
 use crate::{
-    model::DelimiterTag,
+    model::{DelimiterTag, Operation, UriAuthentication, UriSecurity},
+    printer::{DeviceId, PrinterAlert, PrinterSupply, PrinterUri},
     value::{IppName, IppValue},
 };
 use bytes::{BufMut, Bytes, BytesMut};
+#[cfg(feature = "async")]
+use futures_util::io::{AsyncWrite, AsyncWriteExt};
+use http::Uri;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -38,27 +46,50 @@ impl IppAttribute {
         ATTRIBUTES_NATURAL_LANGUAGE => "attributes-natural-language",
         CHARSET_CONFIGURED => "charset-configured",
         CHARSET_SUPPORTED => "charset-supported",
+        CLIENT_INFO => "client-info",
         COLOR_MODE_SUPPORTED => "color-mode-supported",
         COLOR_SUPPORTED => "color-supported",
         COMPRESSION_SUPPORTED => "compression-supported",
         COPIES => "copies",
         COPIES_DEFAULT => "copies-default",
         COPIES_SUPPORTED => "copies-supported",
+        DETAILED_STATUS_MESSAGE => "detailed-status-message",
         DOCUMENT_FORMAT => "document-format",
         DOCUMENT_FORMAT_DEFAULT => "document-format-default",
+        DOCUMENT_FORMAT_DETAILS => "document-format-details",
         DOCUMENT_FORMAT_PREFERRED => "document-format-preferred",
         DOCUMENT_FORMAT_SUPPORTED => "document-format-supported",
+        DOCUMENT_NUMBER => "document-number",
+        DOCUMENT_URI => "document-uri",
         FINISHINGS => "finishings",
+        FINISHINGS_COL => "finishings-col",
+        FINISHINGS_COL_DATABASE => "finishings-col-database",
+        FINISHINGS_COL_DEFAULT => "finishings-col-default",
+        FINISHINGS_COL_READY => "finishings-col-ready",
         FINISHINGS_DEFAULT => "finishings-default",
         FINISHINGS_SUPPORTED => "finishings-supported",
         GENERATED_NATURAL_LANGUAGE_SUPPORTED => "generated-natural-language-supported",
+        IPP_ATTRIBUTE_FIDELITY => "ipp-attribute-fidelity",
         IPP_VERSIONS_SUPPORTED => "ipp-versions-supported",
+        JOB_ACCOUNT_ID => "job-account-id",
+        JOB_ACCOUNTING_USER_ID => "job-accounting-user-id",
+        JOB_CONSTRAINTS_SUPPORTED => "job-constraints-supported",
         JOB_ID => "job-id",
+        JOB_IDS => "job-ids",
         JOB_NAME => "job-name",
+        JOB_ORIGINATING_USER_NAME => "job-originating-user-name",
+        JOB_PASSWORD => "job-password",
+        JOB_PASSWORD_ENCRYPTION => "job-password-encryption",
+        JOB_PRINTER_UP_TIME => "job-printer-up-time",
+        JOB_PRINTER_URI => "job-printer-uri",
+        JOB_RESOLVERS_SUPPORTED => "job-resolvers-supported",
+        JOB_SHEETS => "job-sheets",
+        JOB_SHEETS_COL => "job-sheets-col",
         JOB_STATE => "job-state",
         JOB_STATE_REASONS => "job-state-reasons",
         JOB_URI => "job-uri",
         LAST_DOCUMENT => "last-document",
+        MEDIA => "media",
         MEDIA_COL => "media-col",
         MEDIA_COL_DATABASE => "media-col-database",
         MEDIA_COL_DEFAULT => "media-col-default",
@@ -74,6 +105,9 @@ impl IppAttribute {
         MULTIPLE_DOCUMENT_HANDLING_DEFAULT => "multiple-document-handling-default",
         MULTIPLE_DOCUMENT_HANDLING_SUPPORTED => "multiple-document-handling-supported",
         NATURAL_LANGUAGE_CONFIGURED => "natural-language-configured",
+        NUMBER_UP => "number-up",
+        NUMBER_UP_DEFAULT => "number-up-default",
+        NUMBER_UP_SUPPORTED => "number-up-supported",
         OPERATIONS_SUPPORTED => "operations-supported",
         ORIENTATION_REQUESTED => "orientation-requested",
         ORIENTATION_REQUESTED_DEFAULT => "orientation-requested-default",
@@ -82,11 +116,15 @@ impl IppAttribute {
         OUTPUT_BIN_DEFAULT => "output-bin-default",
         OUTPUT_BIN_SUPPORTED => "output-bin-supported",
         OUTPUT_MODE_SUPPORTED => "output-mode-supported",
+        OVERRIDES => "overrides",
         PAGES_PER_MINUTE => "pages-per-minute",
         PDL_OVERRIDE_SUPPORTED => "pdl-override-supported",
+        PRINTER_ALERT => "printer-alert",
+        PRINTER_ALERT_DESCRIPTION => "printer-alert-description",
         PRINTER_DEVICE_ID => "printer-device-id",
         PRINTER_FIRMWARE_NAME => "printer-firmware-name",
         PRINTER_FIRMWARE_STRING_VERSION => "printer-firmware-string-version",
+        PRINTER_ICONS => "printer-icons",
         PRINTER_INFO => "printer-info",
         PRINTER_IS_ACCEPTING_JOBS => "printer-is-accepting-jobs",
         PRINTER_LOCATION => "printer-location",
@@ -99,16 +137,22 @@ impl IppAttribute {
         PRINTER_STATE => "printer-state",
         PRINTER_STATE_MESSAGE => "printer-state-message",
         PRINTER_STATE_REASONS => "printer-state-reasons",
+        PRINTER_SUPPLY => "printer-supply",
+        PRINTER_SUPPLY_DESCRIPTION => "printer-supply-description",
         PRINTER_UP_TIME => "printer-up-time",
         PRINTER_URI => "printer-uri",
         PRINTER_URI_SUPPORTED => "printer-uri-supported",
         PRINTER_UUID => "printer-uuid",
+        PRINTER_XRI_SUPPORTED => "printer-xri-supported",
         PRINT_COLOR_MODE => "print-color-mode",
         PRINT_COLOR_MODE_DEFAULT => "print-color-mode-default",
         PRINT_COLOR_MODE_SUPPORTED => "print-color-mode-supported",
         PRINT_QUALITY => "print-quality",
         PRINT_QUALITY_DEFAULT => "print-quality-default",
         PRINT_QUALITY_SUPPORTED => "print-quality-supported",
+        PRINT_SCALING => "print-scaling",
+        PRINT_SCALING_DEFAULT => "print-scaling-default",
+        PRINT_SCALING_SUPPORTED => "print-scaling-supported",
         QUEUED_JOB_COUNT => "queued-job-count",
         REQUESTED_ATTRIBUTES => "requested-attributes",
         REQUESTING_USER_NAME => "requesting-user-name",
@@ -116,6 +160,9 @@ impl IppAttribute {
         SIDES_DEFAULT => "sides-default",
         SIDES_SUPPORTED => "sides-supported",
         STATUS_MESSAGE => "status-message",
+        TIME_AT_COMPLETED => "time-at-completed",
+        TIME_AT_CREATION => "time-at-creation",
+        TIME_AT_PROCESSING => "time-at-processing",
         URI_AUTHENTICATION_SUPPORTED => "uri-authentication-supported",
         URI_SECURITY_SUPPORTED => "uri-security-supported",
     }
@@ -160,6 +207,40 @@ impl IppAttribute {
         })
     }
 
+    /// Create a keyword attribute
+    pub fn keyword<N, V>(name: N, value: V) -> Result<IppAttribute, IppParseError>
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        IppAttribute::with_name(name, IppValue::Keyword(value.as_ref().try_into()?))
+    }
+
+    /// Create an integer attribute
+    pub fn integer<N>(name: N, value: i32) -> Result<IppAttribute, IppParseError>
+    where
+        N: AsRef<str>,
+    {
+        IppAttribute::with_name(name, IppValue::Integer(value))
+    }
+
+    /// Create a boolean attribute
+    pub fn boolean<N>(name: N, value: bool) -> Result<IppAttribute, IppParseError>
+    where
+        N: AsRef<str>,
+    {
+        IppAttribute::with_name(name, IppValue::Boolean(value))
+    }
+
+    /// Create a text attribute without language
+    pub fn text<N, V>(name: N, value: V) -> Result<IppAttribute, IppParseError>
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        IppAttribute::with_name(name, IppValue::TextWithoutLanguage(value.as_ref().try_into()?))
+    }
+
     /// Return attribute name
     pub fn name(&self) -> &IppName {
         &self.name
@@ -175,16 +256,71 @@ impl IppAttribute {
         self.value
     }
 
+    /// Return the number of bytes produced by [`IppAttribute::to_bytes`] without serializing the attribute
+    pub fn encoded_len(&self) -> usize {
+        1 + 2 + self.name.len() + self.value.encoded_len()
+    }
+
     /// Write attribute to byte array
     pub fn to_bytes(&self) -> Bytes {
         let mut buffer = BytesMut::new();
 
-        buffer.put_u8(self.value.to_tag());
+        buffer.put_u8(self.value.wire_tag());
         buffer.put_u16(self.name.len() as u16);
         buffer.put_slice(self.name.as_bytes());
         buffer.put(self.value.to_bytes());
         buffer.freeze()
     }
+
+    /// Write attribute to byte array after checking the value with [`IppValue::validate`]
+    pub fn try_to_bytes(&self) -> Result<Bytes, IppParseError> {
+        self.value.validate()?;
+        Ok(self.to_bytes())
+    }
+}
+
+/// Policy for resolving conflicts in [`IppAttributes::merge`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Attributes from the other list replace the existing attributes with the same name
+    Overwrite,
+    /// Attributes from the other list are only added if the group does not have an attribute with the same name
+    KeepExisting,
+}
+
+/// Difference between two attribute lists, see [`IppAttributes::diff`].
+/// Each entry carries the tag of the group the attribute belongs to, entries are sorted by group and name.
+#[derive(Clone, Debug, Default)]
+pub struct AttributeDiff {
+    /// Attributes which only exist in the other list
+    pub added: Vec<(DelimiterTag, IppAttribute)>,
+    /// Attributes which only exist in this list
+    pub removed: Vec<(DelimiterTag, IppAttribute)>,
+    /// Attributes with a different value, as the old and the new attribute
+    pub changed: Vec<(DelimiterTag, IppAttribute, IppAttribute)>,
+}
+
+impl AttributeDiff {
+    /// Check if both attribute lists are equal
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+// number of an enum or integer value
+pub(crate) fn enum_or_int(value: &IppValue) -> Option<i32> {
+    match *value {
+        IppValue::Enum(n) | IppValue::Integer(n) => Some(n),
+        _ => None,
+    }
+}
+
+// string contents of an octetString value, long values are kept as raw data by the parser
+fn octet_string(value: &IppValue) -> String {
+    match value {
+        IppValue::Other { data, .. } => String::from_utf8_lossy(data).into_owned(),
+        value => value.to_string(),
+    }
 }
 
 /// Attribute group
@@ -193,6 +329,8 @@ impl IppAttribute {
 pub struct IppAttributeGroup {
     tag: DelimiterTag,
     attributes: HashMap<IppName, IppAttribute>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    lowercase_names: bool,
 }
 
 impl IppAttributeGroup {
@@ -201,6 +339,33 @@ impl IppAttributeGroup {
         IppAttributeGroup {
             tag,
             attributes: HashMap::new(),
+            lowercase_names: false,
+        }
+    }
+
+    /// Lowercase attribute names in [`IppAttributeGroup::insert`] and [`IppAttributeGroup::get`].
+    /// IPP attribute names are case-insensitive, with this option enabled mixed-case names sent by
+    /// non-conforming peers can be looked up directly. Parsed groups have it enabled when
+    /// [`IppParser::lowercase_names`](crate::parser::IppParser::lowercase_names) is set. Default is false.
+    pub fn lowercase_names(mut self, enabled: bool) -> Self {
+        self.lowercase_names = enabled;
+        self
+    }
+
+    /// Insert an attribute, replacing and returning an existing attribute with the same name
+    pub fn insert(&mut self, mut attribute: IppAttribute) -> Option<IppAttribute> {
+        if self.lowercase_names {
+            attribute.name.make_ascii_lowercase();
+        }
+        self.attributes.insert(attribute.name.clone(), attribute)
+    }
+
+    /// Get an attribute by name. If lowercase names are enabled the name is matched case-insensitively.
+    pub fn get(&self, name: &str) -> Option<&IppAttribute> {
+        if self.lowercase_names && name.bytes().any(|b| b.is_ascii_uppercase()) {
+            self.attributes.get(name.to_ascii_lowercase().as_str())
+        } else {
+            self.attributes.get(name)
         }
     }
 
@@ -214,15 +379,133 @@ impl IppAttributeGroup {
         &self.attributes
     }
 
-    /// Return mutable attributes
+    /// Return mutable attributes.
+    /// Inserting through the map bypasses the name normalisation of [`IppAttributeGroup::lowercase_names`], so
+    /// [`IppAttributeGroup::get`] may not find such attributes. Use [`IppAttributeGroup::insert`] to add attributes.
     pub fn attributes_mut(&mut self) -> &mut HashMap<IppName, IppAttribute> {
         &mut self.attributes
     }
 
+    /// Iterate over the attributes in a reproducible order: `attributes-charset`, `attributes-natural-language`
+    /// and `printer-uri` first as required by RFC 8011 section 4.1.4, then the other attributes sorted by name
+    pub fn iter_ordered(&self) -> impl Iterator<Item = &IppAttribute> {
+        let header = IppAttribute::HEADER_ATTRS
+            .iter()
+            .filter_map(|name| self.attributes.get(*name));
+        let mut other = self
+            .attributes
+            .values()
+            .filter(|attr| !is_header_attr(attr.name()))
+            .collect::<Vec<_>>();
+        other.sort_by(|a, b| a.name().cmp(b.name()));
+        header.chain(other)
+    }
+
     /// Consume this group and return mutable attributes
     pub fn into_attributes(self) -> HashMap<IppName, IppAttribute> {
         self.attributes
     }
+
+    /// Decode the `printer-device-id` attribute of this group
+    pub fn device_id(&self) -> Option<DeviceId> {
+        self.first_value(IppAttribute::PRINTER_DEVICE_ID)
+            .map(|v| DeviceId::parse(&v.to_string()))
+    }
+
+    /// Return the `printer-up-time` (or `job-printer-up-time` for job groups) of this group as a duration.
+    /// Negative values are rejected.
+    pub fn uptime(&self) -> Option<Duration> {
+        [IppAttribute::PRINTER_UP_TIME, IppAttribute::JOB_PRINTER_UP_TIME]
+            .into_iter()
+            .find_map(|name| match self.first_value(name) {
+                Some(IppValue::Integer(secs)) => Some(u64::try_from(*secs).ok().map(Duration::from_secs)),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// Decode the `printer-uri-supported` attribute of this group together with the positionally aligned
+    /// `uri-security-supported` and `uri-authentication-supported` values. Malformed URIs are skipped.
+    pub fn printer_uris(&self) -> Vec<PrinterUri> {
+        let mut security = self.values(IppAttribute::URI_SECURITY_SUPPORTED);
+        let mut authentication = self.values(IppAttribute::URI_AUTHENTICATION_SUPPORTED);
+        self.values(IppAttribute::PRINTER_URI_SUPPORTED)
+            .map(|uri| {
+                let security = security.next().and_then(|v| UriSecurity::from_keyword(&v.to_string()));
+                let authentication = authentication
+                    .next()
+                    .and_then(|v| UriAuthentication::from_keyword(&v.to_string()));
+                (uri, security, authentication)
+            })
+            .filter_map(|(uri, security, authentication)| {
+                Some(PrinterUri {
+                    uri: uri.as_http_uri().ok()?,
+                    security,
+                    authentication,
+                })
+            })
+            .collect()
+    }
+
+    /// Decode the `printer-alert` attribute of this group, each alert with the description
+    /// from the `printer-alert-description` value at the same position
+    pub fn printer_alerts(&self) -> Vec<PrinterAlert> {
+        let mut descriptions = self.values(IppAttribute::PRINTER_ALERT_DESCRIPTION);
+        self.values(IppAttribute::PRINTER_ALERT)
+            .map(|alert| {
+                let alert = PrinterAlert::parse(&octet_string(alert));
+                match descriptions.next() {
+                    Some(description) => alert.with_description(description.to_string()),
+                    None => alert,
+                }
+            })
+            .collect()
+    }
+
+    /// Decode the `printer-supply` attribute of this group, each supply with the description
+    /// from the `printer-supply-description` value at the same position
+    pub fn printer_supplies(&self) -> Vec<PrinterSupply> {
+        let mut descriptions = self.values(IppAttribute::PRINTER_SUPPLY_DESCRIPTION);
+        self.values(IppAttribute::PRINTER_SUPPLY)
+            .map(|supply| {
+                let supply = PrinterSupply::parse(&octet_string(supply));
+                match descriptions.next() {
+                    Some(description) => supply.with_description(description.to_string()),
+                    None => supply,
+                }
+            })
+            .collect()
+    }
+
+    // iterate over all values of a given attribute, multi-valued attributes are flattened
+    pub(crate) fn values<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a IppValue> {
+        self.attributes.get(name).into_iter().flat_map(|a| a.value())
+    }
+
+    // first value of a given attribute
+    pub(crate) fn first_value(&self, name: &str) -> Option<&IppValue> {
+        self.values(name).next()
+    }
+
+    // string representation of all values of a given attribute
+    pub(crate) fn strings(&self, name: &str) -> Vec<String> {
+        self.values(name).map(|v| v.to_string()).collect()
+    }
+
+    /// Return the first value of an enum attribute, accepting an integer encoding as well.
+    /// Some attributes such as `print-quality` are sent as integers by printers implementing other versions of
+    /// the specification.
+    pub fn enum_or_int(&self, name: &str) -> Option<i32> {
+        self.first_value(name).and_then(enum_or_int)
+    }
+
+    // boolean attribute, false if missing
+    pub(crate) fn boolean(&self, name: &str) -> bool {
+        self.first_value(name)
+            .and_then(|v| v.as_boolean())
+            .copied()
+            .unwrap_or(false)
+    }
 }
 
 /// Attribute list
@@ -258,56 +541,914 @@ impl IppAttributes {
         self.groups.iter().filter(move |g| g.tag == tag)
     }
 
+    /// Get the number of attribute groups matching a given delimiter tag, e.g. the number of jobs in a Get-Jobs response
+    pub fn group_count(&self, tag: DelimiterTag) -> usize {
+        self.groups_of(tag).count()
+    }
+
+    /// Get the n-th attribute group (counting from zero) matching a given delimiter tag
+    pub fn nth_group(&self, tag: DelimiterTag, n: usize) -> Option<&IppAttributeGroup> {
+        self.groups_of(tag).nth(n)
+    }
+
     /// Add attribute to a given group
     pub fn add(&mut self, tag: DelimiterTag, attribute: IppAttribute) {
         let group = self.groups_mut().iter_mut().find(|g| g.tag() == tag);
         if let Some(group) = group {
-            group.attributes_mut().insert(attribute.name().to_owned(), attribute);
+            group.insert(attribute);
         } else {
             let mut new_group = IppAttributeGroup::new(tag);
-            new_group
-                .attributes_mut()
-                .insert(attribute.name().to_owned(), attribute);
+            new_group.insert(attribute);
             self.groups_mut().push(new_group);
         }
     }
 
-    /// Write attribute list to byte array
-    pub fn to_bytes(&self) -> Bytes {
-        let mut buffer = BytesMut::new();
-
-        // put the required attributes first as described in section 4.1.4 of RFC8011
-        buffer.put_u8(DelimiterTag::OperationAttributes as u8);
+    /// Add attribute to a given group, rejecting duplicates. Unlike [`IppAttributes::add`], which replaces an existing
+    /// attribute with the same name, this returns [`IppParseError::DuplicateAttribute`] and leaves the group unchanged.
+    pub fn add_strict(&mut self, tag: DelimiterTag, attribute: IppAttribute) -> Result<(), IppParseError> {
+        if self
+            .groups_of(tag)
+            .next()
+            .is_some_and(|group| group.get(attribute.name()).is_some())
+        {
+            return Err(IppParseError::DuplicateAttribute {
+                name: attribute.name().to_string(),
+            });
+        }
+        self.add(tag, attribute);
+        Ok(())
+    }
 
-        if let Some(group) = self.groups_of(DelimiterTag::OperationAttributes).next() {
-            for hdr in &IppAttribute::HEADER_ATTRS {
-                if let Some(attr) = group.attributes().get(*hdr) {
-                    buffer.put(attr.to_bytes());
+    /// Merge attributes from another list, e.g. apply job overrides to the printer defaults.
+    /// Attributes are merged into the group with the same tag, groups which do not exist yet are appended.
+    pub fn merge(&mut self, other: &IppAttributes, policy: MergePolicy) {
+        for group in other.groups() {
+            for attribute in group.attributes().values() {
+                let exists = self
+                    .groups_of(group.tag())
+                    .next()
+                    .is_some_and(|g| g.get(attribute.name()).is_some());
+                if policy == MergePolicy::Overwrite || !exists {
+                    self.add(group.tag(), attribute.clone());
                 }
             }
+        }
+    }
 
-            // now the other operation attributes
-            for attr in group.attributes().values() {
-                if !is_header_attr(attr.name()) {
-                    buffer.put(attr.to_bytes());
+    /// Compare this attribute list with another one, e.g. printer attributes before and after a configuration change.
+    /// Groups are matched by tag and position among the groups with the same tag, so the n-th job group is compared
+    /// with the n-th job group of the other list. Values are compared with [`IppValue`] equality.
+    pub fn diff(&self, other: &IppAttributes) -> AttributeDiff {
+        fn sorted(group: Option<&IppAttributeGroup>) -> Vec<&IppAttribute> {
+            let mut attributes = group
+                .map(|g| g.attributes().values().collect::<Vec<_>>())
+                .unwrap_or_default();
+            attributes.sort_by(|a, b| a.name().cmp(b.name()));
+            attributes
+        }
+        fn get<'a>(group: Option<&'a IppAttributeGroup>, name: &str) -> Option<&'a IppAttribute> {
+            group.and_then(|g| g.attributes().get(name))
+        }
+
+        let mut tags = self
+            .groups
+            .iter()
+            .chain(&other.groups)
+            .map(|g| g.tag())
+            .collect::<Vec<_>>();
+        tags.sort_by_key(|tag| u8::from(*tag));
+        tags.dedup();
+
+        let mut diff = AttributeDiff::default();
+        for tag in tags {
+            for n in 0..self.group_count(tag).max(other.group_count(tag)) {
+                let old = self.nth_group(tag, n);
+                let new = other.nth_group(tag, n);
+
+                for attr in sorted(old) {
+                    match get(new, attr.name()) {
+                        None => diff.removed.push((tag, attr.clone())),
+                        Some(new_attr) if new_attr.value() != attr.value() => {
+                            diff.changed.push((tag, attr.clone(), new_attr.clone()))
+                        }
+                        Some(_) => {}
+                    }
+                }
+                for attr in sorted(new) {
+                    if get(old, attr.name()).is_none() {
+                        diff.added.push((tag, attr.clone()));
+                    }
                 }
             }
         }
+        diff
+    }
+
+    /// Check that all operation attributes required for a given operation are present.
+    /// Returns [`IppParseError::MissingAttribute`] naming the first missing attribute.
+    pub fn validate_required(&self, op: Operation) -> Result<(), IppParseError> {
+        let group = self.groups_of(DelimiterTag::OperationAttributes).next();
+        let has = |name: &str| group.is_some_and(|g| g.get(name).is_some());
+
+        let required = op.required_operation_attributes();
+        let job_target = has(IppAttribute::JOB_URI) && required.contains(&IppAttribute::JOB_ID);
+
+        let missing = [
+            IppAttribute::ATTRIBUTES_CHARSET,
+            IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+        ]
+        .iter()
+        .chain(required)
+        .filter(|name| !(job_target && [IppAttribute::PRINTER_URI, IppAttribute::JOB_ID].contains(name)))
+        .find(|name| !has(name));
+
+        match missing {
+            Some(name) => Err(IppParseError::MissingAttribute((*name).to_owned())),
+            None => Ok(()),
+        }
+    }
+
+    /// Return a copy of the attribute list containing only the named attributes, e.g. those
+    /// specified in the `requested-attributes` of the request.
+    /// Group tags and order are preserved, groups without any of the named attributes are dropped.
+    pub fn select(&self, names: &[&str]) -> IppAttributes {
+        let groups = self
+            .groups
+            .iter()
+            .filter_map(|group| {
+                let attributes = group
+                    .attributes()
+                    .iter()
+                    .filter(|(name, _)| names.contains(&name.as_str()))
+                    .map(|(name, attr)| (name.clone(), attr.clone()))
+                    .collect::<HashMap<_, _>>();
+                (!attributes.is_empty()).then_some(IppAttributeGroup {
+                    tag: group.tag,
+                    attributes,
+                    lowercase_names: group.lowercase_names,
+                })
+            })
+            .collect();
+        IppAttributes { groups }
+    }
+
+    /// Keep only the groups with one of the given tags, e.g. when forwarding part of a message.
+    /// The order of the remaining groups is preserved. This complements [`IppAttributes::select`],
+    /// which filters by attribute name.
+    pub fn retain_groups(&mut self, tags: &[DelimiterTag]) {
+        self.groups.retain(|group| tags.contains(&group.tag));
+    }
+
+    /// Collapse all groups into a single map of attribute names to values.
+    ///
+    /// Groups are visited in order, so when the same attribute name appears in several groups
+    /// the value from the later group wins.
+    pub fn flatten(&self) -> HashMap<String, IppValue> {
+        self.groups
+            .iter()
+            .flat_map(|g| g.attributes().values())
+            .map(|a| (a.name().to_string(), a.value().clone()))
+            .collect()
+    }
+
+    /// Collect all `uri` values from all groups, including the elements of multi-valued attributes,
+    /// e.g. `printer-uri-supported`, `printer-more-info` and `printer-icons`.
+    /// Returns (attribute name, URI) pairs sorted by attribute name, values which cannot be parsed are skipped.
+    pub fn all_uris(&self) -> Vec<(String, Uri)> {
+        let mut uris = self
+            .groups
+            .iter()
+            .flat_map(|g| g.attributes().values())
+            .flat_map(|attr| {
+                attr.value()
+                    .into_iter()
+                    .filter_map(|v| v.as_http_uri().ok())
+                    .map(|uri| (attr.name().to_string(), uri))
+            })
+            .collect::<Vec<_>>();
+        uris.sort_by(|a, b| a.0.cmp(&b.0));
+        uris
+    }
+
+    /// Return the number of bytes produced by [`IppAttributes::to_bytes`] without serializing the attributes
+    pub fn encoded_len(&self) -> usize {
+        let operation_len = self
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .map(|g| g.attributes().values().map(|a| a.encoded_len()).sum::<usize>())
+            .unwrap_or_default();
+
+        let other_len = self
+            .groups()
+            .iter()
+            .filter(|group| group.tag() != DelimiterTag::OperationAttributes)
+            .map(|g| 1 + g.attributes().values().map(|a| a.encoded_len()).sum::<usize>())
+            .sum::<usize>();
+
+        // operation attributes tag, attributes of all groups, end of attributes tag
+        1 + operation_len + other_len + 1
+    }
+
+    // Walk the attribute list in wire order: group tags and attributes.
+    // The required operation attributes go first as described in section 4.1.4 of RFC8011.
+    fn wire_parts(&self) -> impl Iterator<Item = WirePart<'_>> {
+        let operation_group = self.groups_of(DelimiterTag::OperationAttributes).next();
+
+        let header_attrs = IppAttribute::HEADER_ATTRS
+            .iter()
+            .filter_map(move |hdr| operation_group.and_then(|g| g.attributes().get(*hdr)));
+
+        // now the other operation attributes
+        let operation_attrs = operation_group
+            .into_iter()
+            .flat_map(|g| g.attributes().values())
+            .filter(|attr| !is_header_attr(attr.name()));
 
         // now the rest
-        for group in self
+        let other_groups = self
             .groups()
             .iter()
             .filter(|group| group.tag() != DelimiterTag::OperationAttributes)
-        {
-            buffer.put_u8(group.tag() as u8);
+            .flat_map(|group| {
+                std::iter::once(WirePart::Tag(group.tag())).chain(group.attributes().values().map(WirePart::Attribute))
+            });
+
+        std::iter::once(WirePart::Tag(DelimiterTag::OperationAttributes))
+            .chain(header_attrs.chain(operation_attrs).map(WirePart::Attribute))
+            .chain(other_groups)
+            .chain(std::iter::once(WirePart::Tag(DelimiterTag::EndOfAttributes)))
+    }
+
+    /// Write attribute list to byte array
+    pub fn to_bytes(&self) -> Bytes {
+        let mut buffer = BytesMut::new();
 
-            for attr in group.attributes().values() {
-                buffer.put(attr.to_bytes());
+        for part in self.wire_parts() {
+            match part {
+                WirePart::Tag(tag) => buffer.put_u8(tag.into()),
+                WirePart::Attribute(attr) => buffer.put(attr.to_bytes()),
             }
         }
-        buffer.put_u8(DelimiterTag::EndOfAttributes as u8);
 
         buffer.freeze()
     }
+
+    /// Write attribute list directly to the writer without building the whole message in memory
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        for part in self.wire_parts() {
+            match part {
+                WirePart::Tag(tag) => w.write_all(&[tag.into()])?,
+                WirePart::Attribute(attr) => w.write_all(&attr.to_bytes())?,
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    /// Write attribute list directly to the asynchronous writer without building the whole message in memory
+    pub async fn async_write_to<W: AsyncWrite + Unpin>(&self, w: &mut W) -> io::Result<()> {
+        for part in self.wire_parts() {
+            match part {
+                WirePart::Tag(tag) => w.write_all(&[tag.into()]).await?,
+                WirePart::Attribute(attr) => w.write_all(&attr.to_bytes()).await?,
+            }
+        }
+        Ok(())
+    }
+}
+
+enum WirePart<'a> {
+    Tag(DelimiterTag),
+    Attribute(&'a IppAttribute),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::ValueTag;
+
+    use super::*;
+
+    fn merge_fixture() -> (IppAttributes, IppAttributes) {
+        let mut defaults = IppAttributes::new();
+        defaults.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::with_name(IppAttribute::COPIES, IppValue::Integer(1)).unwrap(),
+        );
+        defaults.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::with_name(IppAttribute::SIDES, IppValue::Keyword("one-sided".try_into().unwrap())).unwrap(),
+        );
+
+        let mut overrides = IppAttributes::new();
+        overrides.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::with_name(IppAttribute::COPIES, IppValue::Integer(3)).unwrap(),
+        );
+        overrides.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::with_name(IppAttribute::PRINT_QUALITY, IppValue::Enum(5)).unwrap(),
+        );
+        overrides.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(IppAttribute::COPIES, IppValue::Integer(7)).unwrap(),
+        );
+        (defaults, overrides)
+    }
+
+    fn job_value(attrs: &IppAttributes, name: &str) -> Option<IppValue> {
+        attrs
+            .groups_of(DelimiterTag::JobAttributes)
+            .next()
+            .and_then(|g| g.attributes().get(name))
+            .map(|a| a.value().clone())
+    }
+
+    #[test]
+    fn test_keyword_attribute() {
+        let attr = IppAttribute::keyword(IppAttribute::SIDES, "two-sided-long-edge").unwrap();
+        assert_eq!(attr.name().as_str(), IppAttribute::SIDES);
+        assert_eq!(
+            attr.value(),
+            &IppValue::Keyword("two-sided-long-edge".try_into().unwrap())
+        );
+
+        let result = IppAttribute::keyword(IppAttribute::SIDES, "x".repeat(256));
+        assert!(matches!(result, Err(IppParseError::InvalidStringLength { .. })));
+    }
+
+    #[test]
+    fn test_integer_attribute() {
+        let attr = IppAttribute::integer(IppAttribute::COPIES, 2).unwrap();
+        assert_eq!(attr.name().as_str(), IppAttribute::COPIES);
+        assert_eq!(attr.value(), &IppValue::Integer(2));
+
+        let result = IppAttribute::integer("x".repeat(256), 2);
+        assert!(matches!(result, Err(IppParseError::InvalidStringLength { .. })));
+    }
+
+    #[test]
+    fn test_boolean_attribute() {
+        let attr = IppAttribute::boolean(IppAttribute::COLOR_SUPPORTED, true).unwrap();
+        assert_eq!(attr.name().as_str(), IppAttribute::COLOR_SUPPORTED);
+        assert_eq!(attr.value(), &IppValue::Boolean(true));
+    }
+
+    #[test]
+    fn test_text_attribute() {
+        let attr = IppAttribute::text(IppAttribute::PRINTER_INFO, "Office printer").unwrap();
+        assert_eq!(attr.name().as_str(), IppAttribute::PRINTER_INFO);
+        assert_eq!(
+            attr.value(),
+            &IppValue::TextWithoutLanguage("Office printer".try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_uptime() {
+        let mut group = IppAttributeGroup::new(DelimiterTag::PrinterAttributes);
+        assert_eq!(group.uptime(), None);
+
+        let attr = IppAttribute::integer(IppAttribute::PRINTER_UP_TIME, 3600).unwrap();
+        group.insert(attr);
+        assert_eq!(group.uptime(), Some(Duration::from_secs(3600)));
+
+        let attr = IppAttribute::integer(IppAttribute::PRINTER_UP_TIME, -1).unwrap();
+        group.insert(attr);
+        assert_eq!(group.uptime(), None);
+
+        let mut group = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+        let attr = IppAttribute::integer(IppAttribute::JOB_PRINTER_UP_TIME, 42).unwrap();
+        group.insert(attr);
+        assert_eq!(group.uptime(), Some(Duration::from_secs(42)));
+    }
+
+    #[test]
+    fn test_nth_group() {
+        let mut attrs = IppAttributes::new();
+        attrs.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(
+                IppAttribute::ATTRIBUTES_CHARSET,
+                IppValue::Charset("utf-8".try_into().unwrap()),
+            )
+            .unwrap(),
+        );
+        for job_id in [10, 11, 12] {
+            let mut group = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+            let attr = IppAttribute::integer(IppAttribute::JOB_ID, job_id).unwrap();
+            group.insert(attr);
+            attrs.groups_mut().push(group);
+        }
+
+        assert_eq!(attrs.group_count(DelimiterTag::JobAttributes), 3);
+        assert_eq!(attrs.group_count(DelimiterTag::OperationAttributes), 1);
+        assert_eq!(attrs.group_count(DelimiterTag::PrinterAttributes), 0);
+
+        let job_id = |n| {
+            attrs
+                .nth_group(DelimiterTag::JobAttributes, n)
+                .map(|g| g.attributes()[IppAttribute::JOB_ID].value().clone())
+        };
+        assert_eq!(job_id(0), Some(IppValue::Integer(10)));
+        assert_eq!(job_id(1), Some(IppValue::Integer(11)));
+        assert_eq!(job_id(2), Some(IppValue::Integer(12)));
+        assert_eq!(job_id(3), None);
+    }
+
+    #[test]
+    fn test_add_strict_duplicate() {
+        let mut attrs = IppAttributes::new();
+        attrs
+            .add_strict(
+                DelimiterTag::OperationAttributes,
+                IppAttribute::integer(IppAttribute::COPIES, 1).unwrap(),
+            )
+            .unwrap();
+
+        let result = attrs.add_strict(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::integer(IppAttribute::COPIES, 2).unwrap(),
+        );
+        assert!(matches!(result, Err(IppParseError::DuplicateAttribute { ref name }) if name == "copies"));
+
+        let group = attrs.groups_of(DelimiterTag::OperationAttributes).next().unwrap();
+        assert_eq!(group.attributes()[IppAttribute::COPIES].value(), &IppValue::Integer(1));
+
+        // the same name in another group is not a duplicate
+        attrs
+            .add_strict(
+                DelimiterTag::JobAttributes,
+                IppAttribute::integer(IppAttribute::COPIES, 2).unwrap(),
+            )
+            .unwrap();
+
+        // the default mode keeps overwriting
+        attrs.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::integer(IppAttribute::COPIES, 3).unwrap(),
+        );
+        let group = attrs.groups_of(DelimiterTag::OperationAttributes).next().unwrap();
+        assert_eq!(group.attributes()[IppAttribute::COPIES].value(), &IppValue::Integer(3));
+    }
+
+    #[test]
+    fn test_all_uris() {
+        let uri = |s: &str| IppValue::Uri(s.try_into().unwrap());
+        let mut attrs = IppAttributes::new();
+        attrs.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(IppAttribute::PRINTER_URI, uri("ipp://printer.local/ipp/print")).unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name(
+                IppAttribute::PRINTER_URI_SUPPORTED,
+                IppValue::Array(vec![
+                    uri("ipp://printer.local/ipp/print"),
+                    uri("ipps://printer.local/ipp/print"),
+                ]),
+            )
+            .unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name(IppAttribute::PRINTER_MORE_INFO, uri("http://printer.local/")).unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name(IppAttribute::PRINTER_ICONS, uri("http://printer.local/icon.png")).unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::text(IppAttribute::PRINTER_INFO, "http://not-a-uri-value/").unwrap(),
+        );
+
+        let uris = attrs
+            .all_uris()
+            .into_iter()
+            .map(|(name, uri)| (name, uri.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            uris,
+            vec![
+                ("printer-icons".to_owned(), "http://printer.local/icon.png".to_owned()),
+                ("printer-more-info".to_owned(), "http://printer.local/".to_owned()),
+                ("printer-uri".to_owned(), "ipp://printer.local/ipp/print".to_owned()),
+                (
+                    "printer-uri-supported".to_owned(),
+                    "ipp://printer.local/ipp/print".to_owned()
+                ),
+                (
+                    "printer-uri-supported".to_owned(),
+                    "ipps://printer.local/ipp/print".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_overwrite() {
+        let (mut attrs, overrides) = merge_fixture();
+        attrs.merge(&overrides, MergePolicy::Overwrite);
+
+        assert_eq!(attrs.groups().len(), 2);
+        assert_eq!(job_value(&attrs, IppAttribute::COPIES), Some(IppValue::Integer(3)));
+        assert_eq!(
+            job_value(&attrs, IppAttribute::SIDES),
+            Some(IppValue::Keyword("one-sided".try_into().unwrap()))
+        );
+        assert_eq!(job_value(&attrs, IppAttribute::PRINT_QUALITY), Some(IppValue::Enum(5)));
+
+        let operation = attrs.groups_of(DelimiterTag::OperationAttributes).next().unwrap();
+        assert_eq!(
+            operation.attributes().get(IppAttribute::COPIES).map(|a| a.value()),
+            Some(&IppValue::Integer(7))
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let text = |s: &str| IppValue::TextWithoutLanguage(s.try_into().unwrap());
+        let printer = |location: &str| {
+            let mut attrs = IppAttributes::new();
+            attrs.add(
+                DelimiterTag::PrinterAttributes,
+                IppAttribute::with_name(
+                    IppAttribute::PRINTER_NAME,
+                    IppValue::NameWithoutLanguage("office".try_into().unwrap()),
+                )
+                .unwrap(),
+            );
+            attrs.add(
+                DelimiterTag::PrinterAttributes,
+                IppAttribute::with_name(IppAttribute::PRINTER_LOCATION, text(location)).unwrap(),
+            );
+            attrs
+        };
+
+        let before = printer("Room 101");
+        assert!(before.diff(&before.clone()).is_empty());
+
+        let mut after = printer("Room 202");
+        after.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name(IppAttribute::PRINTER_INFO, text("Office printer")).unwrap(),
+        );
+        after.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(IppAttribute::STATUS_MESSAGE, text("successful-ok")).unwrap(),
+        );
+
+        let diff = before.diff(&after);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        let (tag, old, new) = &diff.changed[0];
+        assert_eq!(*tag, DelimiterTag::PrinterAttributes);
+        assert_eq!(old.name().as_str(), IppAttribute::PRINTER_LOCATION);
+        assert_eq!(old.value(), &text("Room 101"));
+        assert_eq!(new.value(), &text("Room 202"));
+
+        let added = diff
+            .added
+            .iter()
+            .map(|(tag, a)| (*tag, a.name().as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            added,
+            vec![
+                (DelimiterTag::OperationAttributes, IppAttribute::STATUS_MESSAGE),
+                (DelimiterTag::PrinterAttributes, IppAttribute::PRINTER_INFO),
+            ]
+        );
+
+        let reverse = after.diff(&before);
+        assert!(reverse.added.is_empty());
+        assert_eq!(reverse.removed.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_keep_existing() {
+        let (mut attrs, overrides) = merge_fixture();
+        attrs.merge(&overrides, MergePolicy::KeepExisting);
+
+        assert_eq!(attrs.groups().len(), 2);
+        assert_eq!(job_value(&attrs, IppAttribute::COPIES), Some(IppValue::Integer(1)));
+        assert_eq!(job_value(&attrs, IppAttribute::PRINT_QUALITY), Some(IppValue::Enum(5)));
+        assert_eq!(
+            attrs
+                .groups_of(DelimiterTag::JobAttributes)
+                .next()
+                .unwrap()
+                .attributes()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_flatten_later_group_wins() {
+        let mut attrs = IppAttributes::new();
+        attrs.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name("status-message", IppValue::Integer(1)).unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name("status-message", IppValue::Integer(2)).unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name("copies", IppValue::Integer(3)).unwrap(),
+        );
+
+        let map = attrs.flatten();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["status-message"], IppValue::Integer(2));
+        assert_eq!(map["copies"], IppValue::Integer(3));
+    }
+
+    fn operation_attributes(names: &[&str]) -> IppAttributes {
+        let mut attrs = IppAttributes::new();
+        for name in names {
+            attrs.add(
+                DelimiterTag::OperationAttributes,
+                IppAttribute::with_name(name, IppValue::Integer(1)).unwrap(),
+            );
+        }
+        attrs
+    }
+
+    #[test]
+    fn test_validate_required() {
+        let attrs = operation_attributes(&[
+            IppAttribute::ATTRIBUTES_CHARSET,
+            IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+            IppAttribute::PRINTER_URI,
+        ]);
+        assert!(attrs.validate_required(Operation::GetPrinterAttributes).is_ok());
+        assert!(attrs.validate_required(Operation::CupsGetPrinters).is_ok());
+
+        let result = attrs.validate_required(Operation::CancelJob);
+        assert!(matches!(result, Err(IppParseError::MissingAttribute(ref name)) if name == IppAttribute::JOB_ID));
+    }
+
+    #[test]
+    fn test_validate_required_missing_printer_uri() {
+        let attrs = operation_attributes(&[
+            IppAttribute::ATTRIBUTES_CHARSET,
+            IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+        ]);
+        let result = attrs.validate_required(Operation::PrintJob);
+        assert!(matches!(result, Err(IppParseError::MissingAttribute(ref name)) if name == IppAttribute::PRINTER_URI));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "missing required attribute: printer-uri"
+        );
+    }
+
+    #[test]
+    fn test_validate_required_job_uri() {
+        let attrs = operation_attributes(&[
+            IppAttribute::ATTRIBUTES_CHARSET,
+            IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+            IppAttribute::JOB_URI,
+        ]);
+        assert!(attrs.validate_required(Operation::CancelJob).is_ok());
+
+        let result = IppAttributes::new().validate_required(Operation::CancelJob);
+        assert!(
+            matches!(result, Err(IppParseError::MissingAttribute(ref name)) if name == IppAttribute::ATTRIBUTES_CHARSET)
+        );
+    }
+
+    fn request_attributes() -> IppAttributes {
+        let mut attrs = IppAttributes::new();
+        for (tag, name, value) in [
+            (DelimiterTag::OperationAttributes, "job-name", IppValue::Integer(1)),
+            (
+                DelimiterTag::OperationAttributes,
+                IppAttribute::PRINTER_URI,
+                IppValue::Uri("ipp://localhost/printers/test".try_into().unwrap()),
+            ),
+            (
+                DelimiterTag::OperationAttributes,
+                IppAttribute::ATTRIBUTES_CHARSET,
+                IppValue::Charset("utf-8".try_into().unwrap()),
+            ),
+            (
+                DelimiterTag::OperationAttributes,
+                IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+                IppValue::NaturalLanguage("en".try_into().unwrap()),
+            ),
+            (DelimiterTag::JobAttributes, IppAttribute::COPIES, IppValue::Integer(2)),
+            (
+                DelimiterTag::JobAttributes,
+                IppAttribute::SIDES,
+                IppValue::Keyword("two-sided-long-edge".try_into().unwrap()),
+            ),
+        ] {
+            attrs.add(tag, IppAttribute::with_name(name, value).unwrap());
+        }
+        attrs
+    }
+
+    #[test]
+    fn test_write_to() {
+        let attrs = request_attributes();
+
+        let mut buf = Vec::new();
+        attrs.write_to(&mut buf).unwrap();
+        assert_eq!(buf, attrs.to_bytes());
+        assert_eq!(buf.len(), attrs.encoded_len());
+
+        // charset, natural language and printer-uri go first
+        assert_eq!(&buf[..4], &[0x01, ValueTag::Charset as u8, 0x00, 0x12]);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_write_to() {
+        let attrs = request_attributes();
+
+        let mut cursor = futures_util::io::Cursor::new(Vec::new());
+        attrs.async_write_to(&mut cursor).await.unwrap();
+        assert_eq!(cursor.into_inner(), attrs.to_bytes());
+    }
+
+    #[test]
+    fn test_select() {
+        let mut attrs = IppAttributes::new();
+        attrs.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(
+                IppAttribute::ATTRIBUTES_CHARSET,
+                IppValue::Charset("utf-8".try_into().unwrap()),
+            )
+            .unwrap(),
+        );
+        for i in 0..100 {
+            attrs.add(
+                DelimiterTag::PrinterAttributes,
+                IppAttribute::with_name(format!("vendor-attribute-{i}"), IppValue::Integer(i)).unwrap(),
+            );
+        }
+        for (name, value) in [
+            (IppAttribute::PRINTER_STATE, IppValue::Enum(3)),
+            (IppAttribute::PRINTER_IS_ACCEPTING_JOBS, IppValue::Boolean(true)),
+            (IppAttribute::QUEUED_JOB_COUNT, IppValue::Integer(0)),
+        ] {
+            attrs.add(
+                DelimiterTag::PrinterAttributes,
+                IppAttribute::with_name(name, value).unwrap(),
+            );
+        }
+
+        let selected = attrs.select(&[
+            IppAttribute::PRINTER_STATE,
+            IppAttribute::PRINTER_IS_ACCEPTING_JOBS,
+            IppAttribute::QUEUED_JOB_COUNT,
+            IppAttribute::PRINTER_NAME,
+        ]);
+
+        assert_eq!(selected.groups().len(), 1);
+        let group = &selected.groups()[0];
+        assert_eq!(group.tag(), DelimiterTag::PrinterAttributes);
+        assert_eq!(group.attributes().len(), 3);
+        assert_eq!(
+            group.attributes()[IppAttribute::PRINTER_STATE].value(),
+            &IppValue::Enum(3)
+        );
+        assert!(!group.attributes().contains_key(IppAttribute::PRINTER_NAME));
+
+        // the original list is untouched
+        assert_eq!(attrs.groups()[1].attributes().len(), 103);
+    }
+
+    #[test]
+    fn test_retain_groups() {
+        let mut attrs = IppAttributes::new();
+        attrs.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::text(IppAttribute::STATUS_MESSAGE, "successful-ok").unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::UnsupportedAttributes,
+            IppAttribute::keyword(IppAttribute::SIDES, "two-sided-long-edge").unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::integer(IppAttribute::QUEUED_JOB_COUNT, 2).unwrap(),
+        );
+        attrs.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::integer(IppAttribute::JOB_ID, 1).unwrap(),
+        );
+
+        attrs.retain_groups(&[DelimiterTag::OperationAttributes, DelimiterTag::PrinterAttributes]);
+
+        let tags = attrs.groups().iter().map(|g| g.tag()).collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            vec![DelimiterTag::OperationAttributes, DelimiterTag::PrinterAttributes]
+        );
+        assert_eq!(
+            attrs.groups()[1].attributes()[IppAttribute::QUEUED_JOB_COUNT].value(),
+            &IppValue::Integer(2)
+        );
+
+        attrs.retain_groups(&[]);
+        assert!(attrs.groups().is_empty());
+    }
+
+    #[test]
+    fn test_iter_ordered() {
+        let attrs = [
+            IppAttribute::keyword(IppAttribute::SIDES, "one-sided").unwrap(),
+            IppAttribute::with_name(
+                IppAttribute::PRINTER_URI,
+                IppValue::Uri("ipp://localhost/printers/test".try_into().unwrap()),
+            )
+            .unwrap(),
+            IppAttribute::integer(IppAttribute::COPIES, 2).unwrap(),
+            IppAttribute::with_name(
+                IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+                IppValue::NaturalLanguage("en".try_into().unwrap()),
+            )
+            .unwrap(),
+            IppAttribute::text(IppAttribute::JOB_NAME, "report").unwrap(),
+            IppAttribute::with_name(
+                IppAttribute::ATTRIBUTES_CHARSET,
+                IppValue::Charset("utf-8".try_into().unwrap()),
+            )
+            .unwrap(),
+        ];
+        let mut group = IppAttributeGroup::new(DelimiterTag::OperationAttributes);
+        for attr in attrs.iter().cloned() {
+            group.insert(attr);
+        }
+
+        let names = group.iter_ordered().map(|a| a.name().as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                IppAttribute::ATTRIBUTES_CHARSET,
+                IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+                IppAttribute::PRINTER_URI,
+                IppAttribute::COPIES,
+                IppAttribute::JOB_NAME,
+                IppAttribute::SIDES,
+            ]
+        );
+
+        // the order does not depend on the insertion order
+        let mut reversed = IppAttributeGroup::new(DelimiterTag::OperationAttributes);
+        for attr in attrs.into_iter().rev() {
+            reversed.insert(attr);
+        }
+        assert!(
+            reversed
+                .iter_ordered()
+                .map(|a| a.name().as_str())
+                .eq(names.iter().copied())
+        );
+    }
+
+    #[test]
+    fn test_enum_or_int() {
+        let mut group = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+        assert_eq!(group.enum_or_int(IppAttribute::PRINT_QUALITY), None);
+
+        group.insert(IppAttribute::with_name(IppAttribute::PRINT_QUALITY, IppValue::Enum(5)).unwrap());
+        assert_eq!(group.enum_or_int(IppAttribute::PRINT_QUALITY), Some(5));
+
+        group.insert(IppAttribute::integer(IppAttribute::PRINT_QUALITY, 4).unwrap());
+        assert_eq!(group.enum_or_int(IppAttribute::PRINT_QUALITY), Some(4));
+
+        group.insert(IppAttribute::keyword(IppAttribute::PRINT_QUALITY, "high").unwrap());
+        assert_eq!(group.enum_or_int(IppAttribute::PRINT_QUALITY), None);
+    }
+
+    #[test]
+    fn test_lowercase_names() {
+        let attr =
+            IppAttribute::with_name("Printer-Name", IppValue::NameWithoutLanguage("foo".try_into().unwrap())).unwrap();
+
+        let mut group = IppAttributeGroup::new(DelimiterTag::PrinterAttributes);
+        group.insert(attr.clone());
+        assert!(group.get(IppAttribute::PRINTER_NAME).is_none());
+        assert!(group.get("Printer-Name").is_some());
+
+        let mut group = IppAttributeGroup::new(DelimiterTag::PrinterAttributes).lowercase_names(true);
+        assert!(group.insert(attr.clone()).is_none());
+        assert_eq!(
+            group.attributes()[IppAttribute::PRINTER_NAME].name().as_str(),
+            IppAttribute::PRINTER_NAME
+        );
+        assert_eq!(
+            group.get("PRINTER-NAME").map(|a| a.value().to_string()).as_deref(),
+            Some("foo")
+        );
+        assert!(group.insert(attr).is_some());
+        assert_eq!(group.attributes().len(), 1);
+    }
 }
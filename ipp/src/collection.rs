@@ -0,0 +1,1383 @@
+//!
+//! Typed builders for IPP collection attributes
+//!
+#[cfg(feature = "ipp-set-extensions")]
+use crate::attribute::IppAttributeGroup;
+#[cfg(feature = "ipp-system-service")]
+use {crate::model::ClientType, num_traits::FromPrimitive as _};
+
+use crate::{
+    attribute::IppAttribute,
+    parser::{IppParseError, list_or_value},
+    value::{IppCollection, IppName, IppValue},
+};
+
+fn member_name(name: &str) -> IppName {
+    // unwrap is fine because all member names used in this module are short static keywords
+    name.try_into().unwrap()
+}
+
+/// Builder for the `document-format-details` collection (PWG 5100.7)
+#[derive(Debug, Clone, Default)]
+pub struct DocumentFormatDetailsBuilder {
+    document_format: Option<String>,
+    document_format_device_id: Option<String>,
+    document_format_version: Option<String>,
+    document_natural_language: Vec<String>,
+    document_source_application_name: Option<String>,
+    document_source_application_version: Option<String>,
+    document_source_os_name: Option<String>,
+    document_source_os_version: Option<String>,
+}
+
+impl DocumentFormatDetailsBuilder {
+    /// Create an empty builder
+    pub fn new() -> DocumentFormatDetailsBuilder {
+        DocumentFormatDetailsBuilder::default()
+    }
+
+    /// Specify the mime-type of the document, e.g. "image/pwg-raster"
+    pub fn document_format<S>(mut self, document_format: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_format = Some(document_format.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the IEEE 1284 device ID the document was generated for
+    pub fn document_format_device_id<S>(mut self, device_id: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_format_device_id = Some(device_id.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the version of the document format
+    pub fn document_format_version<S>(mut self, version: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_format_version = Some(version.as_ref().to_owned());
+        self
+    }
+
+    /// Add a natural language used in the document. Can be repeated.
+    pub fn document_natural_language<S>(mut self, language: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_natural_language.push(language.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the name of the application which generated the document
+    pub fn document_source_application_name<S>(mut self, name: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_source_application_name = Some(name.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the version of the application which generated the document
+    pub fn document_source_application_version<S>(mut self, version: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_source_application_version = Some(version.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the name of the operating system the document was generated on
+    pub fn document_source_os_name<S>(mut self, name: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_source_os_name = Some(name.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the version of the operating system the document was generated on
+    pub fn document_source_os_version<S>(mut self, version: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.document_source_os_version = Some(version.as_ref().to_owned());
+        self
+    }
+
+    /// Build the collection value
+    pub fn build(self) -> Result<IppValue, IppParseError> {
+        let mut map = IppCollection::new();
+
+        if let Some(v) = self.document_format {
+            map.insert(member_name("document-format"), IppValue::MimeMediaType(v.try_into()?));
+        }
+        if let Some(v) = self.document_format_device_id {
+            map.insert(
+                member_name("document-format-device-id"),
+                IppValue::TextWithoutLanguage(v.try_into()?),
+            );
+        }
+        if let Some(v) = self.document_format_version {
+            map.insert(
+                member_name("document-format-version"),
+                IppValue::TextWithoutLanguage(v.try_into()?),
+            );
+        }
+        if !self.document_natural_language.is_empty() {
+            let languages = self
+                .document_natural_language
+                .into_iter()
+                .map(|v| Ok(IppValue::NaturalLanguage(v.try_into()?)))
+                .collect::<Result<Vec<_>, IppParseError>>()?;
+            map.insert(member_name("document-natural-language"), list_or_value(languages));
+        }
+        if let Some(v) = self.document_source_application_name {
+            map.insert(
+                member_name("document-source-application-name"),
+                IppValue::NameWithoutLanguage(v.try_into()?),
+            );
+        }
+        if let Some(v) = self.document_source_application_version {
+            map.insert(
+                member_name("document-source-application-version"),
+                IppValue::TextWithoutLanguage(v.try_into()?),
+            );
+        }
+        if let Some(v) = self.document_source_os_name {
+            map.insert(
+                member_name("document-source-os-name"),
+                IppValue::NameWithoutLanguage(v.try_into()?),
+            );
+        }
+        if let Some(v) = self.document_source_os_version {
+            map.insert(
+                member_name("document-source-os-version"),
+                IppValue::TextWithoutLanguage(v.try_into()?),
+            );
+        }
+
+        Ok(IppValue::Collection(map))
+    }
+}
+
+/// Builder for a single entry of the `overrides` attribute (PWG 5100.6).
+/// At least one of `pages`, `document-numbers` or `document-copies` ranges must be specified.
+#[derive(Debug, Clone, Default)]
+pub struct OverrideBuilder {
+    pages: Vec<(i32, i32)>,
+    document_numbers: Vec<(i32, i32)>,
+    document_copies: Vec<(i32, i32)>,
+    media: Option<String>,
+    attributes: Vec<IppAttribute>,
+}
+
+impl OverrideBuilder {
+    /// Create an empty builder
+    pub fn new() -> OverrideBuilder {
+        OverrideBuilder::default()
+    }
+
+    /// Add a range of pages this override applies to. Can be repeated.
+    /// Ranges must satisfy `1 <= first <= last`, otherwise [`OverrideBuilder::build`] fails.
+    pub fn pages(mut self, first: i32, last: i32) -> Self {
+        self.pages.push((first, last));
+        self
+    }
+
+    /// Add a range of documents this override applies to. Can be repeated.
+    pub fn document_numbers(mut self, first: i32, last: i32) -> Self {
+        self.document_numbers.push((first, last));
+        self
+    }
+
+    /// Add a range of document copies this override applies to. Can be repeated.
+    pub fn document_copies(mut self, first: i32, last: i32) -> Self {
+        self.document_copies.push((first, last));
+        self
+    }
+
+    /// Specify the media keyword for the selected pages, e.g. "na_letter_8.5x11in"
+    pub fn media<S>(mut self, media: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.media = Some(media.as_ref().to_owned());
+        self
+    }
+
+    /// Specify any other job template attribute for the selected pages
+    pub fn attribute(mut self, attribute: IppAttribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Build the collection value.
+    /// Returns [`IppParseError::InvalidRange`] for a range which does not satisfy `1 <= first <= last`.
+    pub fn build(self) -> Result<IppValue, IppParseError> {
+        if self.pages.is_empty() && self.document_numbers.is_empty() && self.document_copies.is_empty() {
+            return Err(IppParseError::MissingAttribute("pages".to_owned()));
+        }
+
+        // pages, documents and copies are numbered from 1
+        let ranges = |ranges: Vec<(i32, i32)>| match ranges.iter().find(|&&(first, _)| first < 1) {
+            Some(&(min, max)) => Err(IppParseError::InvalidRange { min, max }),
+            None => IppValue::from_ranges(&ranges),
+        };
+
+        let mut coll = IppCollection::new();
+        if !self.pages.is_empty() {
+            coll.insert(member_name("pages"), ranges(self.pages)?);
+        }
+        if !self.document_numbers.is_empty() {
+            coll.insert(member_name("document-numbers"), ranges(self.document_numbers)?);
+        }
+        if !self.document_copies.is_empty() {
+            coll.insert(member_name("document-copies"), ranges(self.document_copies)?);
+        }
+        if let Some(media) = self.media {
+            coll.insert(member_name("media"), IppValue::Keyword(media.try_into()?));
+        }
+        for attr in self.attributes {
+            coll.insert(attr.name().clone(), attr.into_value());
+        }
+
+        Ok(IppValue::Collection(coll))
+    }
+}
+
+/// Punching instructions, the `punching` member of `finishings-col` (PWG 5100.1)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Punching {
+    /// Hole locations in hundredths of millimeters from the reference edge
+    pub locations: Vec<i32>,
+    /// Offset from the reference edge in hundredths of millimeters
+    pub offset: Option<i32>,
+    /// Reference edge keyword, e.g. "left"
+    pub reference_edge: Option<String>,
+}
+
+impl Punching {
+    fn build(self) -> Result<IppValue, IppParseError> {
+        let mut coll = IppCollection::new();
+        if !self.locations.is_empty() {
+            coll.insert(member_name("punching-locations"), integers(self.locations));
+        }
+        if let Some(offset) = self.offset {
+            coll.insert(member_name("punching-offset"), IppValue::Integer(offset));
+        }
+        if let Some(edge) = self.reference_edge {
+            coll.insert(
+                member_name("punching-reference-edge"),
+                IppValue::Keyword(edge.try_into()?),
+            );
+        }
+        Ok(IppValue::Collection(coll))
+    }
+
+    fn from_collection(coll: &IppCollection) -> Punching {
+        Punching {
+            locations: coll.get("punching-locations").map(integer_list).unwrap_or_default(),
+            offset: coll.get("punching-offset").and_then(|v| v.as_integer().copied()),
+            reference_edge: coll.get("punching-reference-edge").map(|v| v.to_string()),
+        }
+    }
+}
+
+/// Stitching instructions, the `stitching` member of `finishings-col` (PWG 5100.1)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stitching {
+    /// Stitch angle in degrees
+    pub angle: Option<i32>,
+    /// Stitch locations in hundredths of millimeters from the reference edge
+    pub locations: Vec<i32>,
+    /// Stitching method keyword, e.g. "auto", "crimp", "wire"
+    pub method: Option<String>,
+    /// Offset from the reference edge in hundredths of millimeters
+    pub offset: Option<i32>,
+    /// Reference edge keyword, e.g. "left"
+    pub reference_edge: Option<String>,
+}
+
+impl Stitching {
+    fn build(self) -> Result<IppValue, IppParseError> {
+        let mut coll = IppCollection::new();
+        if let Some(angle) = self.angle {
+            coll.insert(member_name("stitching-angle"), IppValue::Integer(angle));
+        }
+        if !self.locations.is_empty() {
+            coll.insert(member_name("stitching-locations"), integers(self.locations));
+        }
+        if let Some(method) = self.method {
+            coll.insert(member_name("stitching-method"), IppValue::Keyword(method.try_into()?));
+        }
+        if let Some(offset) = self.offset {
+            coll.insert(member_name("stitching-offset"), IppValue::Integer(offset));
+        }
+        if let Some(edge) = self.reference_edge {
+            coll.insert(
+                member_name("stitching-reference-edge"),
+                IppValue::Keyword(edge.try_into()?),
+            );
+        }
+        Ok(IppValue::Collection(coll))
+    }
+
+    fn from_collection(coll: &IppCollection) -> Stitching {
+        Stitching {
+            angle: coll.get("stitching-angle").and_then(|v| v.as_integer().copied()),
+            locations: coll.get("stitching-locations").map(integer_list).unwrap_or_default(),
+            method: coll.get("stitching-method").map(|v| v.to_string()),
+            offset: coll.get("stitching-offset").and_then(|v| v.as_integer().copied()),
+            reference_edge: coll.get("stitching-reference-edge").map(|v| v.to_string()),
+        }
+    }
+}
+
+fn integers(values: Vec<i32>) -> IppValue {
+    list_or_value(values.into_iter().map(IppValue::Integer).collect())
+}
+
+fn integer_list(value: &IppValue) -> Vec<i32> {
+    value.into_iter().filter_map(|v| v.as_integer().copied()).collect()
+}
+
+/// Entry of the `finishings-col` attribute and its `-default`, `-ready` and `-database` variants (PWG 5100.1)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FinishingsCol {
+    /// Finishing template keyword, e.g. "staple-top-left", "punch-dual-left"
+    pub finishing_template: Option<String>,
+    /// Punching instructions
+    pub punching: Option<Punching>,
+    /// Stitching instructions
+    pub stitching: Option<Stitching>,
+}
+
+impl FinishingsCol {
+    /// Create an empty entry
+    pub fn new() -> FinishingsCol {
+        FinishingsCol::default()
+    }
+
+    /// Specify the finishing template keyword
+    pub fn finishing_template<S>(mut self, template: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.finishing_template = Some(template.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the punching instructions
+    pub fn punching(mut self, punching: Punching) -> Self {
+        self.punching = Some(punching);
+        self
+    }
+
+    /// Specify the stitching instructions
+    pub fn stitching(mut self, stitching: Stitching) -> Self {
+        self.stitching = Some(stitching);
+        self
+    }
+
+    /// Build the collection value
+    pub fn build(self) -> Result<IppValue, IppParseError> {
+        let mut coll = IppCollection::new();
+        if let Some(template) = self.finishing_template {
+            coll.insert(
+                member_name("finishing-template"),
+                IppValue::Keyword(template.try_into()?),
+            );
+        }
+        if let Some(punching) = self.punching {
+            coll.insert(member_name("punching"), punching.build()?);
+        }
+        if let Some(stitching) = self.stitching {
+            coll.insert(member_name("stitching"), stitching.build()?);
+        }
+        Ok(IppValue::Collection(coll))
+    }
+
+    /// Extract the entry from a `finishings-col` collection value.
+    /// Returns `None` if the value is not a collection, unknown members are ignored.
+    pub fn from_value(value: &IppValue) -> Option<FinishingsCol> {
+        let coll = value.as_collection()?;
+        Some(FinishingsCol {
+            finishing_template: coll.get("finishing-template").map(|v| v.to_string()),
+            punching: coll
+                .get("punching")
+                .and_then(|v| v.as_collection())
+                .map(Punching::from_collection),
+            stitching: coll
+                .get("stitching")
+                .and_then(|v| v.as_collection())
+                .map(Stitching::from_collection),
+        })
+    }
+
+    /// Extract all entries from a multi-valued attribute such as `finishings-col-database`,
+    /// skipping the values which are not collections
+    pub fn parse_all(value: &IppValue) -> Vec<FinishingsCol> {
+        match value {
+            IppValue::Array(values) => values.iter().filter_map(FinishingsCol::from_value).collect(),
+            value => FinishingsCol::from_value(value).into_iter().collect(),
+        }
+    }
+}
+
+/// Banner page selection for the `job-sheets` and `job-sheets-col` attributes (PWG 5100.7).
+/// CUPS accepts two `job-sheets` keywords for the start and end banners, e.g. "standard" and "none".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JobSheets {
+    sheets: Vec<String>,
+    media: Option<String>,
+}
+
+impl JobSheets {
+    /// Create banner selection from one or more `job-sheets` keywords, e.g. "standard", "none"
+    pub fn new<I, S>(sheets: I) -> JobSheets
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        JobSheets {
+            sheets: sheets.into_iter().map(|s| s.as_ref().to_owned()).collect(),
+            media: None,
+        }
+    }
+
+    /// Specify the media keyword for the banner pages, only sent with `job-sheets-col`
+    pub fn media<S>(mut self, media: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.media = Some(media.as_ref().to_owned());
+        self
+    }
+
+    /// `job-sheets` keywords
+    pub fn sheets(&self) -> &[String] {
+        &self.sheets
+    }
+
+    /// Media keyword of the banner pages
+    pub fn banner_media(&self) -> Option<&str> {
+        self.media.as_deref()
+    }
+
+    fn sheets_value(self) -> Result<IppValue, IppParseError> {
+        let values = self
+            .sheets
+            .into_iter()
+            .map(|s| Ok(IppValue::Keyword(s.try_into()?)))
+            .collect::<Result<Vec<_>, IppParseError>>()?;
+        Ok(list_or_value(values))
+    }
+
+    /// Build the `job-sheets-col` collection value
+    pub fn build(self) -> Result<IppValue, IppParseError> {
+        let media = self.media.clone();
+        let mut coll = IppCollection::new();
+        coll.insert(member_name("job-sheets"), self.sheets_value()?);
+        if let Some(media) = media {
+            coll.insert(member_name("media"), IppValue::Keyword(media.try_into()?));
+        }
+        Ok(IppValue::Collection(coll))
+    }
+
+    /// Build the job attribute: `job-sheets-col` if banner media is specified, `job-sheets` otherwise
+    pub fn into_attribute(self) -> Result<IppAttribute, IppParseError> {
+        if self.media.is_some() {
+            IppAttribute::with_name(IppAttribute::JOB_SHEETS_COL, self.build()?)
+        } else {
+            IppAttribute::with_name(IppAttribute::JOB_SHEETS, self.sheets_value()?)
+        }
+    }
+
+    /// Extract banner selection from either a `job-sheets` or a `job-sheets-col` value
+    pub fn from_value(value: &IppValue) -> Option<JobSheets> {
+        match value {
+            IppValue::Collection(coll) => Some(JobSheets {
+                sheets: coll.get("job-sheets")?.into_iter().map(|v| v.to_string()).collect(),
+                media: coll.get("media").map(|v| v.to_string()),
+            }),
+            IppValue::Keyword(_) | IppValue::NameWithoutLanguage(_) | IppValue::Array(_) => Some(JobSheets {
+                sheets: value.into_iter().map(|v| v.to_string()).collect(),
+                media: None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+// Well-known self-describing media names (PWG 5101.1) used by MediaSize::to_keyword
+const MEDIA_NAMES: &[&str] = &[
+    "iso_a3_297x420mm",
+    "iso_a4_210x297mm",
+    "iso_a5_148x210mm",
+    "iso_a6_105x148mm",
+    "iso_b5_176x250mm",
+    "iso_dl_110x220mm",
+    "na_5x7_5x7in",
+    "na_executive_7.25x10.5in",
+    "na_index-4x6_4x6in",
+    "na_ledger_11x17in",
+    "na_legal_8.5x14in",
+    "na_letter_8.5x11in",
+    "na_number-10_4.125x9.5in",
+];
+
+/// Media dimensions, the `media-size` member of `media-col` (PWG 5100.3)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MediaSize {
+    /// Width in hundredths of millimeters
+    pub x_dimension: i32,
+    /// Height in hundredths of millimeters
+    pub y_dimension: i32,
+}
+
+impl MediaSize {
+    /// Create media size from width and height in hundredths of millimeters
+    pub fn new(x_dimension: i32, y_dimension: i32) -> MediaSize {
+        MediaSize {
+            x_dimension,
+            y_dimension,
+        }
+    }
+
+    /// Extract the dimensions from a self-describing media keyword (PWG 5101.1),
+    /// e.g. `iso_a4_210x297mm` or `na_letter_8.5x11in`.
+    /// Returns `None` if the keyword does not end with a valid `WIDTHxHEIGHT` part in `mm` or `in` units.
+    pub fn from_keyword(keyword: &str) -> Option<MediaSize> {
+        let (_, size) = keyword.rsplit_once('_')?;
+        let (size, factor) = if let Some(size) = size.strip_suffix("mm") {
+            (size, 100.0)
+        } else {
+            (size.strip_suffix("in")?, 2540.0)
+        };
+        let (width, height) = size.split_once('x')?;
+        let dimension = |s: &str| {
+            let value = s.parse::<f64>().ok().filter(|v| v.is_finite() && *v > 0.0)? * factor;
+            (value <= i32::MAX as f64).then(|| value.round() as i32)
+        };
+        Some(MediaSize::new(dimension(width)?, dimension(height)?))
+    }
+
+    /// Return the self-describing media keyword for these dimensions: a well-known name such as `iso_a4_210x297mm`
+    /// if the dimensions match one, otherwise a custom name in millimeters, e.g. `custom_100x150mm_100x150mm`.
+    pub fn to_keyword(&self) -> String {
+        if let Some(name) = MEDIA_NAMES
+            .iter()
+            .find(|name| MediaSize::from_keyword(name).as_ref() == Some(self))
+        {
+            return (*name).to_owned();
+        }
+        let mm = |v: i32| {
+            if v % 100 == 0 {
+                (v / 100).to_string()
+            } else {
+                format!("{}.{:02}", v / 100, v % 100).trim_end_matches('0').to_owned()
+            }
+        };
+        let size = format!("{}x{}mm", mm(self.x_dimension), mm(self.y_dimension));
+        format!("custom_{size}_{size}")
+    }
+
+    /// Build the `media-size` collection value
+    pub fn build(self) -> IppValue {
+        let mut coll = IppCollection::new();
+        coll.insert(member_name("x-dimension"), IppValue::Integer(self.x_dimension));
+        coll.insert(member_name("y-dimension"), IppValue::Integer(self.y_dimension));
+        IppValue::Collection(coll)
+    }
+
+    /// Extract the dimensions from the `media-size` collection value.
+    /// Returns `None` if the value is not a collection or either dimension is not an integer.
+    pub fn from_value(value: &IppValue) -> Option<MediaSize> {
+        let coll = value.as_collection()?;
+        Some(MediaSize::new(
+            *coll.get("x-dimension")?.as_integer()?,
+            *coll.get("y-dimension")?.as_integer()?,
+        ))
+    }
+}
+
+/// The `media-col` collection (PWG 5100.3), as used by `media-col`, `media-col-default` and `media-col-ready`.
+/// Only the commonly used members are decoded, margins are in hundredths of millimeters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MediaCol {
+    /// media-size
+    pub media_size: Option<MediaSize>,
+    /// media-size-name, e.g. `iso_a4_210x297mm`
+    pub media_size_name: Option<String>,
+    /// media-source, e.g. `main` or `tray-1`
+    pub media_source: Option<String>,
+    /// media-type, e.g. `stationery` or `photographic-glossy`
+    pub media_type: Option<String>,
+    /// media-color, e.g. `white`
+    pub media_color: Option<String>,
+    /// media-top-margin
+    pub media_top_margin: Option<i32>,
+    /// media-bottom-margin
+    pub media_bottom_margin: Option<i32>,
+    /// media-left-margin
+    pub media_left_margin: Option<i32>,
+    /// media-right-margin
+    pub media_right_margin: Option<i32>,
+}
+
+impl MediaCol {
+    /// Create the media collection from a media keyword such as `iso_a4_210x297mm`, e.g. a `media-ready` value.
+    /// The size is taken from the keyword if it is self-describing.
+    pub fn from_keyword(keyword: &str) -> MediaCol {
+        MediaCol {
+            media_size: MediaSize::from_keyword(keyword),
+            media_size_name: Some(keyword.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// Build the `media-col` collection value from the members which are set
+    pub fn build(self) -> Result<IppValue, IppParseError> {
+        let mut coll = IppCollection::new();
+        let margins = [
+            ("media-bottom-margin", self.media_bottom_margin),
+            ("media-left-margin", self.media_left_margin),
+            ("media-right-margin", self.media_right_margin),
+            ("media-top-margin", self.media_top_margin),
+        ];
+        for (name, margin) in margins {
+            if let Some(margin) = margin {
+                coll.insert(member_name(name), IppValue::Integer(margin));
+            }
+        }
+        if let Some(color) = self.media_color {
+            coll.insert(member_name("media-color"), IppValue::Keyword(color.try_into()?));
+        }
+        if let Some(size) = self.media_size {
+            coll.insert(member_name("media-size"), size.build());
+        }
+        if let Some(name) = self.media_size_name {
+            coll.insert(member_name("media-size-name"), IppValue::Keyword(name.try_into()?));
+        }
+        if let Some(source) = self.media_source {
+            coll.insert(member_name("media-source"), IppValue::Keyword(source.try_into()?));
+        }
+        if let Some(media_type) = self.media_type {
+            coll.insert(member_name("media-type"), IppValue::Keyword(media_type.try_into()?));
+        }
+        Ok(IppValue::Collection(coll))
+    }
+
+    /// Extract the media collection from a `media-col` value. Returns `None` if the value is not a collection.
+    pub fn from_value(value: &IppValue) -> Option<MediaCol> {
+        let coll = value.as_collection()?;
+        let string = |name: &str| coll.get(name).map(|v| v.to_string());
+        let integer = |name: &str| coll.get(name).and_then(|v| v.as_integer()).copied();
+        Some(MediaCol {
+            media_size: coll.get("media-size").and_then(MediaSize::from_value),
+            media_size_name: string("media-size-name"),
+            media_source: string("media-source"),
+            media_type: string("media-type"),
+            media_color: string("media-color"),
+            media_top_margin: integer("media-top-margin"),
+            media_bottom_margin: integer("media-bottom-margin"),
+            media_left_margin: integer("media-left-margin"),
+            media_right_margin: integer("media-right-margin"),
+        })
+    }
+
+    /// Extract all entries from a `1setOf collection` value such as `media-col-ready`, skipping the malformed ones
+    pub fn parse_all(value: &IppValue) -> Vec<MediaCol> {
+        match value {
+            IppValue::Array(values) => values.iter().filter_map(MediaCol::from_value).collect(),
+            value => MediaCol::from_value(value).into_iter().collect(),
+        }
+    }
+}
+
+/// Single entry of the `printer-xri-supported` attribute (RFC 3380)
+#[cfg(feature = "ipp-job-set-operations")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xri {
+    uri: String,
+    authentication: String,
+    security: String,
+}
+
+#[cfg(feature = "ipp-job-set-operations")]
+impl Xri {
+    /// Create a new XRI entry for a given printer URI with no authentication and no security
+    pub fn new<S>(uri: S) -> Xri
+    where
+        S: AsRef<str>,
+    {
+        Xri {
+            uri: uri.as_ref().to_owned(),
+            authentication: "none".to_owned(),
+            security: "none".to_owned(),
+        }
+    }
+
+    /// Specify the authentication mechanism, e.g. "basic", "digest", "certificate"
+    pub fn authentication<S>(mut self, authentication: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.authentication = authentication.as_ref().to_owned();
+        self
+    }
+
+    /// Specify the security mechanism, e.g. "tls"
+    pub fn security<S>(mut self, security: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.security = security.as_ref().to_owned();
+        self
+    }
+
+    /// Printer URI
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Authentication mechanism
+    pub fn authentication_mechanism(&self) -> &str {
+        &self.authentication
+    }
+
+    /// Security mechanism
+    pub fn security_mechanism(&self) -> &str {
+        &self.security
+    }
+
+    /// Build the `xri` collection value
+    pub fn build(self) -> Result<IppValue, IppParseError> {
+        let mut map = IppCollection::new();
+        map.insert(
+            member_name("xri-authentication"),
+            IppValue::Keyword(self.authentication.try_into()?),
+        );
+        map.insert(
+            member_name("xri-security"),
+            IppValue::Keyword(self.security.try_into()?),
+        );
+        map.insert(member_name("xri-uri"), IppValue::Uri(self.uri.try_into()?));
+        Ok(IppValue::Collection(map))
+    }
+
+    /// Build the `printer-xri-supported` value from a list of entries
+    pub fn build_supported<I>(xris: I) -> Result<IppValue, IppParseError>
+    where
+        I: IntoIterator<Item = Xri>,
+    {
+        let values = xris.into_iter().map(Xri::build).collect::<Result<Vec<_>, _>>()?;
+        Ok(list_or_value(values))
+    }
+
+    /// Extract a single entry from the `xri` collection value.
+    /// Returns `None` if the value is not a collection or the `xri-uri` member is missing.
+    pub fn from_value(value: &IppValue) -> Option<Xri> {
+        let coll = value.as_collection()?;
+        let member = |name: &str, default: &str| {
+            coll.get(name)
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| default.to_owned())
+        };
+        Some(Xri {
+            uri: coll.get("xri-uri")?.to_string(),
+            authentication: member("xri-authentication", "none"),
+            security: member("xri-security", "none"),
+        })
+    }
+
+    /// Extract all entries from the `printer-xri-supported` value, skipping the malformed ones
+    pub fn parse_supported(value: &IppValue) -> Vec<Xri> {
+        match value {
+            IppValue::Array(values) => values.iter().filter_map(Xri::from_value).collect(),
+            value => Xri::from_value(value).into_iter().collect(),
+        }
+    }
+}
+
+/// Single entry of the `client-info` attribute (PWG 5100.7)
+#[cfg(feature = "ipp-system-service")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    name: String,
+    client_type: ClientType,
+    patches: Option<String>,
+    string_version: Option<String>,
+}
+
+#[cfg(feature = "ipp-system-service")]
+impl ClientInfo {
+    /// Create a new client info entry with a given client name and type
+    pub fn new<S>(name: S, client_type: ClientType) -> ClientInfo
+    where
+        S: AsRef<str>,
+    {
+        ClientInfo {
+            name: name.as_ref().to_owned(),
+            client_type,
+            patches: None,
+            string_version: None,
+        }
+    }
+
+    /// Specify the patches applied to the client, e.g. "patch-1234"
+    pub fn patches<S>(mut self, patches: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.patches = Some(patches.as_ref().to_owned());
+        self
+    }
+
+    /// Specify the human-readable client version, e.g. "6.0.0"
+    pub fn string_version<S>(mut self, version: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.string_version = Some(version.as_ref().to_owned());
+        self
+    }
+
+    /// Client name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Client type
+    pub fn client_type(&self) -> ClientType {
+        self.client_type
+    }
+
+    /// Client patches, if any
+    pub fn client_patches(&self) -> Option<&str> {
+        self.patches.as_deref()
+    }
+
+    /// Human-readable client version, if any
+    pub fn client_string_version(&self) -> Option<&str> {
+        self.string_version.as_deref()
+    }
+
+    /// Build the `client-info` collection value. Missing patches are encoded as no-value.
+    pub fn build(self) -> Result<IppValue, IppParseError> {
+        let mut map = IppCollection::new();
+        map.insert(
+            member_name("client-name"),
+            IppValue::NameWithoutLanguage(self.name.try_into()?),
+        );
+        let patches = match self.patches {
+            Some(patches) => IppValue::TextWithoutLanguage(patches.try_into()?),
+            None => IppValue::NoValue,
+        };
+        map.insert(member_name("client-patches"), patches);
+        if let Some(version) = self.string_version {
+            map.insert(
+                member_name("client-string-version"),
+                IppValue::TextWithoutLanguage(version.try_into()?),
+            );
+        }
+        map.insert(member_name("client-type"), IppValue::Enum(self.client_type as i32));
+        Ok(IppValue::Collection(map))
+    }
+
+    /// Build the `client-info` value from a list of entries
+    pub fn build_all<I>(infos: I) -> Result<IppValue, IppParseError>
+    where
+        I: IntoIterator<Item = ClientInfo>,
+    {
+        let values = infos
+            .into_iter()
+            .map(ClientInfo::build)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(list_or_value(values))
+    }
+
+    /// Extract a single entry from the `client-info` collection value.
+    /// Returns `None` if the value is not a collection or the `client-name` or `client-type` member is missing.
+    pub fn from_value(value: &IppValue) -> Option<ClientInfo> {
+        let coll = value.as_collection()?;
+        let text = |name: &str| match coll.get(name) {
+            Some(IppValue::NoValue) | None => None,
+            Some(value) => Some(value.to_string()),
+        };
+        Some(ClientInfo {
+            name: text("client-name")?,
+            client_type: coll
+                .get("client-type")
+                .and_then(|v| v.as_enum())
+                .and_then(|v| ClientType::from_i32(*v))?,
+            patches: text("client-patches"),
+            string_version: text("client-string-version"),
+        })
+    }
+
+    /// Extract all entries from the `client-info` value, skipping the malformed ones
+    pub fn parse_all(value: &IppValue) -> Vec<ClientInfo> {
+        match value {
+            IppValue::Array(values) => values.iter().filter_map(ClientInfo::from_value).collect(),
+            value => ClientInfo::from_value(value).into_iter().collect(),
+        }
+    }
+}
+
+/// Single entry of the `job-constraints-supported` or `job-resolvers-supported` attribute (PWG 5100.13).
+/// A constraint lists job template attribute values which cannot be used together, a resolver lists the values
+/// to apply to resolve the constraints with the same resolver name.
+#[cfg(feature = "ipp-set-extensions")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobConstraint {
+    resolver_name: String,
+    attributes: Vec<(String, IppValue)>,
+}
+
+#[cfg(feature = "ipp-set-extensions")]
+impl JobConstraint {
+    /// Name of the resolver for this constraint
+    pub fn resolver_name(&self) -> &str {
+        &self.resolver_name
+    }
+
+    /// Job template attributes and their values, in the order of the collection
+    pub fn attributes(&self) -> &[(String, IppValue)] {
+        &self.attributes
+    }
+
+    /// Check if the job attributes match all attribute values of this constraint, i.e. for every attribute
+    /// the job has at least one of the listed values
+    pub fn violated_by(&self, job: &IppAttributeGroup) -> bool {
+        !self.attributes.is_empty()
+            && self.attributes.iter().all(|(name, values)| {
+                job.attributes()
+                    .get(name.as_str())
+                    .is_some_and(|attr| attr.value().into_iter().any(|v| values.into_iter().any(|c| c == v)))
+            })
+    }
+
+    /// Extract a single entry from the constraint or resolver collection value.
+    /// Returns `None` if the value is not a collection or the `resolver-name` member is missing.
+    pub fn from_value(value: &IppValue) -> Option<JobConstraint> {
+        let coll = value.as_collection()?;
+        Some(JobConstraint {
+            resolver_name: coll.get("resolver-name")?.to_string(),
+            attributes: coll
+                .iter()
+                .filter(|(name, _)| name.as_str() != "resolver-name")
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        })
+    }
+
+    /// Extract all entries from the `job-constraints-supported` or `job-resolvers-supported` value,
+    /// skipping the malformed ones
+    pub fn parse_supported(value: &IppValue) -> Vec<JobConstraint> {
+        match value {
+            IppValue::Array(values) => values.iter().filter_map(JobConstraint::from_value).collect(),
+            value => JobConstraint::from_value(value).into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{model::DelimiterTag, parser::IppParser, reader::IppReader};
+
+    use super::*;
+
+    fn round_trip(attr: &IppAttribute) -> IppValue {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 2];
+        data.extend(attr.to_bytes());
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        res.attributes()
+            .groups_of(DelimiterTag::JobAttributes)
+            .next()
+            .and_then(|g| g.attributes().get(attr.name().as_str()))
+            .map(|a| a.value().clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_document_format_details() {
+        let value = DocumentFormatDetailsBuilder::new()
+            .document_format("image/pwg-raster")
+            .document_source_application_name("ipp.rs")
+            .document_source_application_version("6.0")
+            .document_source_os_name("Linux")
+            .build()
+            .unwrap();
+
+        let coll = value.as_collection().unwrap();
+        assert_eq!(coll.len(), 4);
+        assert_eq!(
+            coll.get("document-format"),
+            Some(&IppValue::MimeMediaType("image/pwg-raster".try_into().unwrap()))
+        );
+
+        let attr = IppAttribute::with_name(IppAttribute::DOCUMENT_FORMAT_DETAILS, value.clone()).unwrap();
+        assert_eq!(round_trip(&attr), value);
+    }
+
+    #[test]
+    fn test_document_format_details_too_long() {
+        let result = DocumentFormatDetailsBuilder::new()
+            .document_format("x".repeat(256))
+            .build();
+        assert!(matches!(result, Err(IppParseError::InvalidStringLength { .. })));
+    }
+
+    #[test]
+    fn test_media_col_round_trip() {
+        let media_col = MediaCol {
+            media_source: Some("tray-1".to_owned()),
+            media_type: Some("stationery".to_owned()),
+            media_top_margin: Some(423),
+            media_bottom_margin: Some(423),
+            ..MediaCol::from_keyword("iso_a4_210x297mm")
+        };
+        assert_eq!(media_col.media_size, Some(MediaSize::new(21000, 29700)));
+
+        let attr = IppAttribute::with_name(IppAttribute::MEDIA_COL, media_col.clone().build().unwrap()).unwrap();
+        assert_eq!(MediaCol::from_value(&round_trip(&attr)), Some(media_col));
+
+        assert_eq!(MediaCol::from_value(&IppValue::Integer(1)), None);
+        assert_eq!(MediaCol::from_keyword("photo").media_size, None);
+    }
+
+    #[cfg(feature = "ipp-job-set-operations")]
+    #[test]
+    fn test_xri_round_trip() {
+        let xris = vec![
+            Xri::new("ipps://printer.example.com/ipp/print")
+                .authentication("basic")
+                .security("tls"),
+            Xri::new("ipp://printer.example.com/ipp/print"),
+        ];
+
+        let value = Xri::build_supported(xris.clone()).unwrap();
+        let attr = IppAttribute::with_name(IppAttribute::PRINTER_XRI_SUPPORTED, value.clone()).unwrap();
+        let parsed = round_trip(&attr);
+        assert_eq!(parsed, value);
+        assert_eq!(Xri::parse_supported(&parsed), xris);
+    }
+
+    #[cfg(feature = "ipp-job-set-operations")]
+    #[test]
+    fn test_xri_typed_mechanisms() {
+        use crate::model::{UriAuthentication, UriSecurity};
+
+        let xri = Xri::new("ipps://printer.example.com/ipp/print")
+            .authentication(UriAuthentication::Certificate)
+            .security(UriSecurity::Tls);
+        assert_eq!(
+            UriAuthentication::from_keyword(xri.authentication_mechanism()),
+            Some(UriAuthentication::Certificate)
+        );
+        assert_eq!(
+            UriSecurity::from_keyword(xri.security_mechanism()),
+            Some(UriSecurity::Tls)
+        );
+        assert_eq!(UriSecurity::from_keyword("ssl3"), None);
+    }
+
+    #[cfg(feature = "ipp-job-set-operations")]
+    #[test]
+    fn test_xri_single_value() {
+        let xri = Xri::new("ipp://printer.example.com/ipp/print").authentication("digest");
+        let value = Xri::build_supported([xri.clone()]).unwrap();
+        assert!(value.is_collection());
+
+        let parsed = Xri::parse_supported(&value);
+        assert_eq!(parsed, vec![xri]);
+        assert_eq!(parsed[0].uri(), "ipp://printer.example.com/ipp/print");
+        assert_eq!(parsed[0].authentication_mechanism(), "digest");
+        assert_eq!(parsed[0].security_mechanism(), "none");
+    }
+
+    #[cfg(feature = "ipp-job-set-operations")]
+    #[test]
+    fn test_xri_missing_uri() {
+        let mut map = IppCollection::new();
+        map.insert(
+            member_name("xri-security"),
+            IppValue::Keyword("tls".try_into().unwrap()),
+        );
+        assert_eq!(Xri::from_value(&IppValue::Collection(map)), None);
+        assert!(Xri::parse_supported(&IppValue::Integer(1)).is_empty());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let value = IppValue::Array(vec![
+            OverrideBuilder::new()
+                .pages(1, 1)
+                .media("na_letter_8.5x11in")
+                .attribute(
+                    IppAttribute::with_name(IppAttribute::SIDES, IppValue::Keyword("one-sided".try_into().unwrap()))
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+            OverrideBuilder::new()
+                .pages(2, 5)
+                .pages(8, 10)
+                .media("iso_a4_210x297mm")
+                .build()
+                .unwrap(),
+        ]);
+
+        let attr = IppAttribute::with_name(IppAttribute::OVERRIDES, value.clone()).unwrap();
+        let parsed = round_trip(&attr);
+        assert_eq!(parsed, value);
+
+        let overrides = parsed.as_array().unwrap();
+        let first = overrides[0].as_collection().unwrap();
+        assert_eq!(first.get("pages"), Some(&IppValue::RangeOfInteger { min: 1, max: 1 }));
+        assert_eq!(
+            first.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+            vec!["pages", "media", "sides"]
+        );
+
+        let second = overrides[1].as_collection().unwrap();
+        assert_eq!(
+            second.get("pages"),
+            Some(&IppValue::Array(vec![
+                IppValue::RangeOfInteger { min: 2, max: 5 },
+                IppValue::RangeOfInteger { min: 8, max: 10 }
+            ]))
+        );
+        assert_eq!(
+            second.get("media"),
+            Some(&IppValue::Keyword("iso_a4_210x297mm".try_into().unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_overrides_without_range() {
+        let result = OverrideBuilder::new().media("iso_a4_210x297mm").build();
+        assert!(matches!(result, Err(IppParseError::MissingAttribute(_))));
+    }
+
+    #[test]
+    fn test_overrides_invalid_range() {
+        let result = OverrideBuilder::new().pages(5, 2).build();
+        assert!(matches!(result, Err(IppParseError::InvalidRange { min: 5, max: 2 })));
+
+        let result = OverrideBuilder::new().pages(0, 0).build();
+        assert!(matches!(result, Err(IppParseError::InvalidRange { min: 0, max: 0 })));
+
+        let result = OverrideBuilder::new().pages(1, 2).document_copies(-1, 3).build();
+        assert!(matches!(result, Err(IppParseError::InvalidRange { min: -1, max: 3 })));
+    }
+
+    #[test]
+    fn test_finishings_col_round_trip() {
+        let finishings = FinishingsCol::new()
+            .finishing_template("punch-dual-left")
+            .punching(Punching {
+                locations: vec![2540, 17780],
+                offset: Some(635),
+                reference_edge: Some("left".to_owned()),
+            });
+
+        let value = finishings.clone().build().unwrap();
+        let attr = IppAttribute::with_name(IppAttribute::FINISHINGS_COL, value.clone()).unwrap();
+        let parsed = round_trip(&attr);
+        assert_eq!(parsed, value);
+
+        let punching = parsed.as_collection().unwrap().get("punching").unwrap();
+        assert_eq!(
+            punching.as_collection().unwrap().get("punching-locations"),
+            Some(&IppValue::Array(vec![
+                IppValue::Integer(2540),
+                IppValue::Integer(17780)
+            ]))
+        );
+        assert_eq!(FinishingsCol::from_value(&parsed), Some(finishings));
+    }
+
+    #[test]
+    fn test_finishings_col_database() {
+        let database = vec![
+            FinishingsCol::new().finishing_template("none"),
+            FinishingsCol::new()
+                .finishing_template("staple-dual-left")
+                .stitching(Stitching {
+                    locations: vec![2540, 17780],
+                    offset: Some(635),
+                    reference_edge: Some("left".to_owned()),
+                    ..Default::default()
+                }),
+        ];
+
+        let value = IppValue::Array(database.iter().cloned().map(|f| f.build().unwrap()).collect());
+        let attr = IppAttribute::with_name(IppAttribute::FINISHINGS_COL_DATABASE, value).unwrap();
+        let parsed = FinishingsCol::parse_all(&round_trip(&attr));
+        assert_eq!(parsed, database);
+        assert_eq!(parsed[1].stitching.as_ref().unwrap().locations, vec![2540, 17780]);
+    }
+
+    #[test]
+    fn test_job_sheets_col_round_trip() {
+        let sheets = JobSheets::new(["standard", "none"]).media("iso_a4_210x297mm");
+
+        let attr = sheets.clone().into_attribute().unwrap();
+        assert_eq!(attr.name().as_str(), IppAttribute::JOB_SHEETS_COL);
+
+        let parsed = round_trip(&attr);
+        let coll = parsed.as_collection().unwrap();
+        assert_eq!(
+            coll.get("job-sheets"),
+            Some(&IppValue::Array(vec![
+                IppValue::Keyword("standard".try_into().unwrap()),
+                IppValue::Keyword("none".try_into().unwrap())
+            ]))
+        );
+        assert_eq!(JobSheets::from_value(&parsed), Some(sheets));
+    }
+
+    #[test]
+    fn test_job_sheets_keyword() {
+        let attr = JobSheets::new(["confidential"]).into_attribute().unwrap();
+        assert_eq!(attr.name().as_str(), IppAttribute::JOB_SHEETS);
+        assert_eq!(attr.value(), &IppValue::Keyword("confidential".try_into().unwrap()));
+
+        let sheets = JobSheets::from_value(attr.value()).unwrap();
+        assert_eq!(sheets.sheets(), ["confidential"]);
+        assert_eq!(sheets.banner_media(), None);
+    }
+
+    #[cfg(feature = "ipp-system-service")]
+    #[test]
+    fn test_client_info_round_trip() {
+        let infos = vec![
+            ClientInfo::new("ipp.rs", ClientType::Application).string_version("6.0.0"),
+            ClientInfo::new("Linux", ClientType::OperatingSystem)
+                .patches("6.12.1-arch1")
+                .string_version("6.12"),
+        ];
+
+        let value = ClientInfo::build_all(infos.clone()).unwrap();
+        let attr = IppAttribute::with_name(IppAttribute::CLIENT_INFO, value.clone()).unwrap();
+        let parsed = round_trip(&attr);
+        assert_eq!(parsed, value);
+
+        let coll = parsed.as_array().unwrap()[0].as_collection().unwrap();
+        assert_eq!(coll.get("client-patches"), Some(&IppValue::NoValue));
+        assert_eq!(coll.get("client-type"), Some(&IppValue::Enum(3)));
+
+        let parsed = ClientInfo::parse_all(&parsed);
+        assert_eq!(parsed, infos);
+        assert_eq!(parsed[1].client_patches(), Some("6.12.1-arch1"));
+        assert_eq!(parsed[0].client_patches(), None);
+    }
+
+    #[test]
+    fn test_media_size_a4() {
+        let size = MediaSize::from_keyword("iso_a4_210x297mm").unwrap();
+        assert_eq!(size, MediaSize::new(21000, 29700));
+        assert_eq!(size.to_keyword(), "iso_a4_210x297mm");
+
+        let value = size.build();
+        assert_eq!(MediaSize::from_value(&value), Some(size));
+    }
+
+    #[test]
+    fn test_media_size_letter() {
+        let size = MediaSize::from_keyword("na_letter_8.5x11in").unwrap();
+        assert_eq!(size, MediaSize::new(21590, 27940));
+        assert_eq!(size.to_keyword(), "na_letter_8.5x11in");
+    }
+
+    #[test]
+    fn test_media_size_custom() {
+        let size = MediaSize::new(10050, 15000);
+        assert_eq!(size.to_keyword(), "custom_100.5x150mm_100.5x150mm");
+        assert_eq!(MediaSize::from_keyword(&size.to_keyword()), Some(size));
+    }
+
+    #[test]
+    fn test_media_size_malformed_keyword() {
+        assert_eq!(MediaSize::from_keyword("letter"), None);
+        assert_eq!(MediaSize::from_keyword("iso_a4_210x297cm"), None);
+        assert_eq!(MediaSize::from_keyword("iso_a4_210mm"), None);
+        assert_eq!(MediaSize::from_keyword("iso_a4_axbmm"), None);
+        assert_eq!(MediaSize::from_keyword("iso_a4_-210x297mm"), None);
+    }
+
+    #[cfg(feature = "ipp-set-extensions")]
+    #[test]
+    fn test_job_constraints() {
+        let keyword = |v: &str| IppValue::Keyword(v.try_into().unwrap());
+        let constraint = |resolver: &str, members: Vec<(&str, IppValue)>| {
+            let mut coll = IppCollection::new();
+            coll.insert(
+                member_name("resolver-name"),
+                IppValue::NameWithoutLanguage(resolver.try_into().unwrap()),
+            );
+            for (name, value) in members {
+                coll.insert(member_name(name), value);
+            }
+            IppValue::Collection(coll)
+        };
+
+        let value = IppValue::Array(vec![
+            constraint(
+                "envelope-duplex",
+                vec![
+                    (
+                        "media",
+                        IppValue::Array(vec![keyword("na_number-10_4.125x9.5in"), keyword("iso_dl_110x220mm")]),
+                    ),
+                    (
+                        "sides",
+                        IppValue::Array(vec![keyword("two-sided-long-edge"), keyword("two-sided-short-edge")]),
+                    ),
+                ],
+            ),
+            constraint("photo-draft", vec![("print-quality", IppValue::Enum(3))]),
+        ]);
+        let attr = IppAttribute::with_name(IppAttribute::JOB_CONSTRAINTS_SUPPORTED, value).unwrap();
+        let constraints = JobConstraint::parse_supported(&round_trip(&attr));
+
+        assert_eq!(constraints.len(), 2);
+        assert_eq!(constraints[0].resolver_name(), "envelope-duplex");
+        assert_eq!(
+            constraints[0]
+                .attributes()
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["media", "sides"]
+        );
+        assert_eq!(
+            constraints[1].attributes(),
+            &[("print-quality".to_owned(), IppValue::Enum(3))]
+        );
+
+        let mut job = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+        for attr in [
+            IppAttribute::keyword(IppAttribute::MEDIA, "iso_dl_110x220mm").unwrap(),
+            IppAttribute::keyword(IppAttribute::SIDES, "one-sided").unwrap(),
+        ] {
+            job.insert(attr);
+        }
+        assert!(!constraints[0].violated_by(&job));
+
+        let attr = IppAttribute::keyword(IppAttribute::SIDES, "two-sided-long-edge").unwrap();
+        job.insert(attr);
+        assert!(constraints[0].violated_by(&job));
+        assert!(!constraints[1].violated_by(&job));
+    }
+}
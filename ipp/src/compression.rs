@@ -0,0 +1,312 @@
+//!
+//! Decoding of compressed HTTP response bodies
+//!
+//! Supports the `gzip` (RFC 1952) and `deflate` (RFC 1950 zlib stream) content codings.
+//! Bodies are decoded while they are read, and the decoded size is limited so a small compressed
+//! response can not expand into an arbitrary amount of memory.
+//!
+use std::io::{self, Read, Write};
+
+use flate2::write::{MultiGzDecoder, ZlibDecoder};
+
+/// Default limit of the decoded response body size, 64 MiB
+pub const DEFAULT_MAX_DECODED_SIZE: u64 = 64 * 1024 * 1024;
+
+const CHUNK_SIZE: usize = 8192;
+
+/// Returns true if the given content coding can be decoded by [`Decoder`].
+pub fn is_supported(coding: &str) -> bool {
+    is_identity(coding)
+        || ["gzip", "x-gzip", "deflate"]
+            .iter()
+            .any(|c| coding.trim().eq_ignore_ascii_case(c))
+}
+
+/// Returns true if the Content-Encoding value does not apply any coding to the body.
+pub fn is_identity(encoding: &str) -> bool {
+    encoding
+        .split(',')
+        .map(str::trim)
+        .all(|coding| coding.is_empty() || coding.eq_ignore_ascii_case("identity"))
+}
+
+// Decoded output of a chain of decoders, the innermost writer of the chain
+trait Sink: Write + Send + Sync {
+    fn output(&mut self) -> &mut Vec<u8>;
+
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn output(&mut self) -> &mut Vec<u8> {
+        (**self).output()
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        (**self).finish()
+    }
+}
+
+impl<S: Sink> Sink for MultiGzDecoder<S> {
+    fn output(&mut self) -> &mut Vec<u8> {
+        self.get_mut().output()
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.try_finish()?;
+        self.get_mut().finish()
+    }
+}
+
+impl<S: Sink> Sink for ZlibDecoder<S> {
+    fn output(&mut self) -> &mut Vec<u8> {
+        self.get_mut().output()
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.try_finish()?;
+        self.get_mut().finish()
+    }
+}
+
+struct LimitedOutput {
+    output: Vec<u8>,
+    remaining: u64,
+}
+
+impl Write for LimitedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.len() as u64 > self.remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "decoded response body exceeds the size limit",
+            ));
+        }
+        self.remaining -= buf.len() as u64;
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Sink for LimitedOutput {
+    fn output(&mut self) -> &mut Vec<u8> {
+        &mut self.output
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Incremental decoder of a response body with the given Content-Encoding.
+///
+/// Compressed chunks are passed to [`Decoder::decode`] as they arrive, followed by a call to
+/// [`Decoder::finish`] at the end of the body.
+pub struct Decoder {
+    sink: Box<dyn Sink>,
+}
+
+impl Decoder {
+    /// Create a decoder for the value of the Content-Encoding header.
+    /// Codings are listed in the order they were applied, so they are removed in reverse order.
+    /// Decoding fails once the decoded data exceeds `max_size` bytes.
+    pub fn new(encoding: &str, max_size: u64) -> io::Result<Self> {
+        let mut sink: Box<dyn Sink> = Box::new(LimitedOutput {
+            output: Vec::new(),
+            remaining: max_size,
+        });
+
+        for coding in encoding.split(',').map(str::trim) {
+            sink = if coding.is_empty() || coding.eq_ignore_ascii_case("identity") {
+                sink
+            } else if coding.eq_ignore_ascii_case("gzip") || coding.eq_ignore_ascii_case("x-gzip") {
+                Box::new(MultiGzDecoder::new(sink))
+            } else if coding.eq_ignore_ascii_case("deflate") {
+                Box::new(ZlibDecoder::new(sink))
+            } else {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("unsupported content encoding: {coding}"),
+                ));
+            };
+        }
+
+        Ok(Decoder { sink })
+    }
+
+    /// Decode the next chunk of the body and return the data decoded so far
+    pub fn decode(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        self.sink.write_all(chunk)?;
+        self.sink.flush()?;
+        Ok(std::mem::take(self.sink.output()))
+    }
+
+    /// Finish decoding and return the remaining data.
+    /// Fails if the body ended in the middle of the compressed data.
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        self.sink.finish()?;
+        Ok(std::mem::take(self.sink.output()))
+    }
+}
+
+/// Reader which decodes the body read from the inner reader
+pub struct DecodingReader<R> {
+    inner: R,
+    decoder: Option<Decoder>,
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> DecodingReader<R> {
+    /// Create a reader decoding the body with the given Content-Encoding, see [`Decoder::new`]
+    pub fn new(inner: R, encoding: &str, max_size: u64) -> io::Result<Self> {
+        Ok(DecodingReader {
+            inner,
+            decoder: Some(Decoder::new(encoding, max_size)?),
+            buffer: Vec::new(),
+            pos: 0,
+        })
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0; CHUNK_SIZE];
+
+        while self.pos == self.buffer.len() {
+            let Some(decoder) = self.decoder.as_mut() else {
+                return Ok(0);
+            };
+
+            let len = self.inner.read(&mut chunk)?;
+            self.buffer = if len == 0 {
+                self.decoder.take().unwrap().finish()?
+            } else {
+                decoder.decode(&chunk[..len])?
+            };
+            self.pos = 0;
+        }
+
+        let len = buf.len().min(self.buffer.len() - self.pos);
+        buf[..len].copy_from_slice(&self.buffer[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use flate2::{Compression, write::GzEncoder};
+
+    use crate::{parser::IppParser, reader::IppReader};
+
+    use super::*;
+
+    const TEXT: &[u8] = b"hello hello hello hello";
+
+    fn decode(encoding: &str, body: &[u8], max_size: u64) -> io::Result<Vec<u8>> {
+        let mut decoded = Vec::new();
+        DecodingReader::new(body, encoding, max_size)?.read_to_end(&mut decoded)?;
+        Ok(decoded)
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_deflate() {
+        // zlib.compress(TEXT, 0)
+        let stored = [
+            0x78, 0x01, 0x01, 0x17, 0x00, 0xe8, 0xff, b'h', b'e', b'l', b'l', b'o', b' ', b'h', b'e', b'l', b'l', b'o',
+            b' ', b'h', b'e', b'l', b'l', b'o', b' ', b'h', b'e', b'l', b'l', b'o', 0x68, 0x03, 0x08, 0xb1,
+        ];
+        assert_eq!(decode("deflate", &stored, DEFAULT_MAX_DECODED_SIZE).unwrap(), TEXT);
+
+        // zlib.compress(TEXT, 9), the back reference covers the repeated words
+        let fixed = [
+            0x78, 0xda, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0x01, 0x68, 0x03, 0x08, 0xb1,
+        ];
+        assert_eq!(decode("deflate", &fixed, DEFAULT_MAX_DECODED_SIZE).unwrap(), TEXT);
+    }
+
+    #[test]
+    fn test_gzip_response() {
+        let expected = include_bytes!("../tests/fixtures/get-printer-attributes.ipp");
+        let compressed = include_bytes!("../tests/fixtures/get-printer-attributes.ipp.gz");
+
+        let reader = DecodingReader::new(&compressed[..], "gzip", DEFAULT_MAX_DECODED_SIZE).unwrap();
+        let response = IppParser::new(IppReader::new(reader)).parse().unwrap();
+        assert!(response.attributes().groups().len() > 1);
+
+        // chunks of any size decode to the same data
+        let mut decoder = Decoder::new("gzip", DEFAULT_MAX_DECODED_SIZE).unwrap();
+        let mut decoded = Vec::new();
+        for chunk in compressed.chunks(7) {
+            decoded.extend(decoder.decode(chunk).unwrap());
+        }
+        decoded.extend(decoder.finish().unwrap());
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn test_decode_codings() {
+        let compressed = include_bytes!("../tests/fixtures/get-printer-attributes.ipp.gz");
+        let expected = include_bytes!("../tests/fixtures/get-printer-attributes.ipp");
+
+        assert_eq!(decode("identity", TEXT, DEFAULT_MAX_DECODED_SIZE).unwrap(), TEXT);
+        assert_eq!(
+            decode(" X-GZIP , identity", compressed, DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            expected
+        );
+        assert_eq!(
+            decode("gzip, gzip", &gzip(compressed), DEFAULT_MAX_DECODED_SIZE).unwrap(),
+            expected
+        );
+        assert_eq!(
+            decode("br", TEXT, DEFAULT_MAX_DECODED_SIZE).unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+
+        assert!(is_supported("gzip"));
+        assert!(is_supported("Deflate"));
+        assert!(!is_supported("br"));
+
+        assert!(is_identity(""));
+        assert!(is_identity("identity, Identity"));
+        assert!(!is_identity("identity, gzip"));
+    }
+
+    #[test]
+    fn test_corrupted_data() {
+        let mut compressed = include_bytes!("../tests/fixtures/get-printer-attributes.ipp.gz").to_vec();
+        let len = compressed.len();
+
+        assert!(decode("gzip", &compressed[..len - 10], DEFAULT_MAX_DECODED_SIZE).is_err());
+
+        // CRC mismatch
+        compressed[len - 8] ^= 0xff;
+        assert!(decode("gzip", &compressed, DEFAULT_MAX_DECODED_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_max_decoded_size() {
+        let zeros = vec![0; 1024 * 1024];
+        let compressed = gzip(&zeros);
+        assert!(compressed.len() < 4096);
+
+        assert_eq!(decode("gzip", &compressed, zeros.len() as u64).unwrap(), zeros);
+        assert_eq!(
+            decode("gzip", &compressed, zeros.len() as u64 - 1).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -1,13 +1,19 @@
 //!
 //! IPP error
 //!
+use alloc::string::String;
+use core::{convert::Infallible, num::TryFromIntError};
+#[cfg(feature = "std")]
 use std::io;
 
+#[cfg(feature = "std")]
 use http::uri::InvalidUri;
 
-use crate::{model::StatusCode, parser::IppParseError};
+#[cfg(feature = "std")]
+use crate::model::StatusCode;
 
 /// IPP error
+#[cfg(feature = "std")]
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, thiserror::Error)]
 pub enum IppError {
@@ -32,6 +38,26 @@ pub enum IppError {
     /// IPP status error
     StatusError(StatusCode),
 
+    #[error(
+        "IPP status error: {status}{}{}",
+        message.as_ref().map(|m| format!(": {m}")).unwrap_or_default(),
+        detailed_message.as_ref().map(|m| format!(" ({m})")).unwrap_or_default()
+    )]
+    /// IPP status error with the status-message and detailed-status-message sent by the printer
+    StatusMessageError {
+        status: StatusCode,
+        message: Option<String>,
+        detailed_message: Option<String>,
+    },
+
+    #[error("Unsupported attributes: {}", .0.join(", "))]
+    /// Job attributes not supported by the printer while ipp-attribute-fidelity was requested
+    UnsupportedAttributes(Vec<String>),
+
+    #[error("Unsupported content encoding: {0}")]
+    /// The response body is compressed, e.g. with gzip, which is not supported
+    UnsupportedContentEncoding(String),
+
     #[error("Printer not ready")]
     PrinterNotReady,
 
@@ -56,3 +82,123 @@ pub enum IppError {
     /// Client error
     ClientError(#[from] ureq::Error),
 }
+
+#[cfg(feature = "std")]
+impl From<StatusCode> for IppError {
+    fn from(status: StatusCode) -> Self {
+        IppError::StatusError(status)
+    }
+}
+
+/// Parse error enum
+#[derive(Debug, thiserror::Error)]
+pub enum IppParseError {
+    #[error("Invalid tag: {0}")]
+    InvalidTag(u8),
+
+    #[error("Invalid IPP collection")]
+    InvalidCollection,
+
+    /// occurs when the attributes end before a collection is closed with an endCollection value
+    #[error("unterminated IPP collection")]
+    UnterminatedCollection,
+
+    /// occurs when a string is too long for an IPP Value.
+    #[error("invalid string length: {len}, max: {max}")]
+    InvalidStringLength { len: usize, max: usize },
+
+    /// failure to parse int usually used when trying to convert usize -> u16 in this crate
+    #[error(transparent)]
+    InvalidIntValue(#[from] TryFromIntError),
+
+    /// occurs when the lower bound of a range is greater than the upper bound
+    #[error("invalid range: {min}-{max}")]
+    InvalidRange { min: i32, max: i32 },
+
+    /// occurs when an attribute is added twice to the same group in strict mode
+    #[error("duplicate attribute: {name}")]
+    DuplicateAttribute { name: String },
+
+    /// occurs when a value is not among the values supported by the printer
+    #[error("unsupported value for {name}: {value}")]
+    UnsupportedValue { name: String, value: String },
+
+    /// occurs when the operation code of a request header is not a known operation
+    #[error("invalid operation: {0:#06x}")]
+    InvalidOperation(u16),
+
+    /// occurs when an empty array is encoded in strict mode, as IPP cannot represent an empty set
+    #[error("empty array")]
+    EmptyArray,
+
+    /// occurs when a raw value does not fit into the 16-bit value length and cannot be sent as fragments
+    #[error("value with tag {tag:#04x} is too long: {len} bytes")]
+    ValueTooLong { tag: u8, len: usize },
+
+    /// occurs when a collection member name is not a valid keyword
+    #[error("invalid collection member name: {0}")]
+    InvalidMemberName(String),
+
+    /// occurs when a value is not a valid URI
+    #[error("invalid URI: {0}")]
+    InvalidUri(String),
+
+    /// occurs when a required attribute is missing from the message
+    #[error("missing required attribute: {0}")]
+    MissingAttribute(String),
+
+    /// occurs when the message declares an attributes-charset other than utf-8 or us-ascii
+    #[error("unsupported charset: {0}")]
+    UnsupportedCharset(String),
+
+    /// occurs when a string value is not valid UTF-8
+    #[error(transparent)]
+    InvalidUtf8(#[from] core::str::Utf8Error),
+
+    #[error(transparent)]
+    #[cfg(feature = "std")]
+    IoError(#[from] io::Error),
+
+    #[error("infallible this should never happen")]
+    Infallible(#[from] Infallible),
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipp_error_conversions() {
+        let error = IppError::from(IppParseError::InvalidTag(0x55));
+        assert!(matches!(error, IppError::ParseError(IppParseError::InvalidTag(0x55))));
+        assert_eq!(error.to_string(), "Invalid tag: 85");
+
+        let error = IppError::from(io::Error::new(io::ErrorKind::TimedOut, "timed out"));
+        assert!(matches!(&error, IppError::IoError(e) if e.kind() == io::ErrorKind::TimedOut));
+
+        let error = IppError::from(StatusCode::ClientErrorNotFound);
+        assert!(matches!(error, IppError::StatusError(StatusCode::ClientErrorNotFound)));
+
+        let error = IppError::from("not a uri".parse::<http::Uri>().unwrap_err());
+        assert!(matches!(error, IppError::InvalidUri(_)));
+
+        let error: IppError = http::Request::builder()
+            .header("bad\nheader", "value")
+            .body(())
+            .unwrap_err()
+            .into();
+        assert!(matches!(error, IppError::HttpError(_)));
+    }
+
+    #[test]
+    fn test_ipp_parse_error_conversions() {
+        let error = IppParseError::from(u16::try_from(70000usize).unwrap_err());
+        assert!(matches!(error, IppParseError::InvalidIntValue(_)));
+
+        let error = crate::value::IppKeyword::from_bytes(&[0xff]).unwrap_err();
+        assert!(matches!(error, IppParseError::InvalidUtf8(_)));
+
+        let error = IppParseError::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert!(matches!(error, IppParseError::IoError(_)));
+    }
+}
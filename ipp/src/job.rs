@@ -0,0 +1,530 @@
+//!
+//! High-level view of the job attributes
+//!
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    FromPrimitive as _,
+    attribute::{IppAttribute, IppAttributeGroup, IppAttributes},
+    model::{DelimiterTag, JobState, PrintColorMode, PrintQuality, PrintScaling, Sides},
+    parser::IppParseError,
+    value::{IppValue, is_keyword},
+};
+
+/// Summary of the job status decoded from the Get-Job-Attributes or Get-Jobs response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobStatus {
+    /// job-id
+    pub job_id: i32,
+    /// job-state
+    pub state: JobState,
+    /// job-state-reasons
+    pub state_reasons: Vec<String>,
+    /// job-name
+    pub job_name: Option<String>,
+    /// job-originating-user-name
+    pub originating_user: Option<String>,
+    /// job-printer-uri
+    pub printer_uri: Option<String>,
+    /// time-at-creation, in seconds since the printer was started (printer-up-time)
+    pub time_at_creation: Option<i32>,
+    /// time-at-processing, in seconds since the printer was started (printer-up-time)
+    pub time_at_processing: Option<i32>,
+    /// time-at-completed, in seconds since the printer was started (printer-up-time)
+    pub time_at_completed: Option<i32>,
+}
+
+impl JobStatus {
+    /// Decode job status from the first job attributes group of the response.
+    /// Returns `None` if there is no job group or it lacks valid `job-id` and `job-state` attributes.
+    pub fn from_attributes(attributes: &IppAttributes) -> Option<JobStatus> {
+        attributes
+            .groups_of(DelimiterTag::JobAttributes)
+            .next()
+            .and_then(JobStatus::from_group)
+    }
+
+    /// Decode all jobs from the Get-Jobs response, skipping the groups which cannot be decoded
+    pub fn all_from_attributes(attributes: &IppAttributes) -> Vec<JobStatus> {
+        attributes
+            .groups_of(DelimiterTag::JobAttributes)
+            .filter_map(JobStatus::from_group)
+            .collect()
+    }
+
+    /// Decode job status from a single job attributes group
+    pub fn from_group(group: &IppAttributeGroup) -> Option<JobStatus> {
+        let integer = |name| group.first_value(name).and_then(|v| v.as_integer()).copied();
+
+        Some(JobStatus {
+            job_id: integer(IppAttribute::JOB_ID)?,
+            state: group
+                .first_value(IppAttribute::JOB_STATE)
+                .and_then(|v| v.as_enum())
+                .and_then(|v| JobState::from_i32(*v))?,
+            state_reasons: group.strings(IppAttribute::JOB_STATE_REASONS),
+            job_name: group.first_value(IppAttribute::JOB_NAME).map(|v| v.to_string()),
+            originating_user: group
+                .first_value(IppAttribute::JOB_ORIGINATING_USER_NAME)
+                .map(|v| v.to_string()),
+            printer_uri: group.first_value(IppAttribute::JOB_PRINTER_URI).map(|v| v.to_string()),
+            time_at_creation: integer(IppAttribute::TIME_AT_CREATION),
+            time_at_processing: integer(IppAttribute::TIME_AT_PROCESSING),
+            time_at_completed: integer(IppAttribute::TIME_AT_COMPLETED),
+        })
+    }
+
+    /// Check if the job is in one of the terminal states: canceled, aborted or completed
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, JobState::Canceled | JobState::Aborted | JobState::Completed)
+    }
+}
+
+/// Validated value of the `number-up` job attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NumberUp(i32);
+
+impl NumberUp {
+    /// Create the number-up value, the number of pages to impose on a single side.
+    /// If `supported` is given, e.g. the `number-up-supported` printer attribute, the value must be within it.
+    pub fn new(number_up: i32, supported: Option<&IppValue>) -> Result<NumberUp, IppParseError> {
+        if number_up < 1 || supported.is_some_and(|supported| !supported.contains_integer(number_up)) {
+            return Err(IppParseError::UnsupportedValue {
+                name: IppAttribute::NUMBER_UP.to_owned(),
+                value: number_up.to_string(),
+            });
+        }
+        Ok(NumberUp(number_up))
+    }
+
+    /// Number of pages per side
+    pub fn value(&self) -> i32 {
+        self.0
+    }
+
+    /// Convert into the `number-up` job attribute
+    pub fn into_attribute(self) -> IppAttribute {
+        // unwrap is fine because the name is a short static keyword
+        IppAttribute::integer(IppAttribute::NUMBER_UP, self.0).unwrap()
+    }
+}
+
+/// Builder for the common IPP Everywhere job template attributes.
+/// Only the options which have been set are included in the resulting job attributes group.
+#[derive(Debug, Clone, Default)]
+pub struct JobOptions {
+    pub(crate) copies: Option<i32>,
+    pub(crate) sides: Option<Sides>,
+    pub(crate) media: Option<String>,
+    pub(crate) color_mode: Option<PrintColorMode>,
+    pub(crate) number_up: Option<i32>,
+    pub(crate) print_quality: Option<PrintQuality>,
+}
+
+impl JobOptions {
+    /// Create an empty set of job options
+    pub fn new() -> JobOptions {
+        JobOptions::default()
+    }
+
+    /// Number of copies, must be at least 1
+    pub fn copies(mut self, copies: i32) -> Self {
+        self.copies = Some(copies);
+        self
+    }
+
+    /// One- or two-sided printing
+    pub fn sides(mut self, sides: Sides) -> Self {
+        self.sides = Some(sides);
+        self
+    }
+
+    /// Media size name, e.g. `iso_a4_210x297mm` or `na_letter_8.5x11in`
+    pub fn media<S>(mut self, media: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.media = Some(media.as_ref().to_owned());
+        self
+    }
+
+    /// Color mode
+    pub fn color_mode(mut self, color_mode: PrintColorMode) -> Self {
+        self.color_mode = Some(color_mode);
+        self
+    }
+
+    /// Number of pages per side, must be at least 1
+    pub fn number_up(mut self, number_up: i32) -> Self {
+        self.number_up = Some(number_up);
+        self
+    }
+
+    /// Print quality
+    pub fn print_quality(mut self, print_quality: PrintQuality) -> Self {
+        self.print_quality = Some(print_quality);
+        self
+    }
+
+    /// Validate the options and build the job attributes group
+    pub fn build(self) -> Result<IppAttributeGroup, IppParseError> {
+        let mut attributes = Vec::new();
+
+        if let Some(copies) = self.copies {
+            if copies < 1 {
+                return Err(IppParseError::UnsupportedValue {
+                    name: IppAttribute::COPIES.to_owned(),
+                    value: copies.to_string(),
+                });
+            }
+            attributes.push(IppAttribute::integer(IppAttribute::COPIES, copies)?);
+        }
+        if let Some(sides) = self.sides {
+            attributes.push(IppAttribute::keyword(IppAttribute::SIDES, sides.as_str())?);
+        }
+        if let Some(media) = self.media {
+            if !is_keyword(&media) {
+                return Err(IppParseError::UnsupportedValue {
+                    name: IppAttribute::MEDIA.to_owned(),
+                    value: media,
+                });
+            }
+            attributes.push(IppAttribute::keyword(IppAttribute::MEDIA, media)?);
+        }
+        if let Some(color_mode) = self.color_mode {
+            attributes.push(IppAttribute::keyword(
+                IppAttribute::PRINT_COLOR_MODE,
+                color_mode.as_str(),
+            )?);
+        }
+        if let Some(number_up) = self.number_up {
+            attributes.push(NumberUp::new(number_up, None)?.into_attribute());
+        }
+        if let Some(print_quality) = self.print_quality {
+            attributes.push(IppAttribute::with_name(
+                IppAttribute::PRINT_QUALITY,
+                IppValue::Enum(print_quality as i32),
+            )?);
+        }
+
+        let mut group = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+        for attr in attributes {
+            group.insert(attr);
+        }
+        Ok(group)
+    }
+}
+
+/// Build the `print-scaling` job attribute
+pub fn print_scaling_attribute(scaling: PrintScaling) -> IppAttribute {
+    // unwrap is fine because both the name and the value are short static keywords
+    IppAttribute::keyword(IppAttribute::PRINT_SCALING, scaling.as_str()).unwrap()
+}
+
+/// Convert a `time-at-*` job attribute to an approximate absolute time.
+///
+/// `time_at` and `printer_up_time` are both in seconds since the printer was started, and `now` is the client
+/// clock at the moment `printer-up-time` was obtained. The result is `now - (printer_up_time - time_at)`, so it is
+/// only as accurate as the delay between the printer reporting its up time and the client reading the clock,
+/// and it assumes the printer has not been restarted since the event. Returns `None` for negative values,
+/// or if the event lies after `printer_up_time`.
+pub fn up_time_to_system_time(time_at: i32, printer_up_time: i32, now: SystemTime) -> Option<SystemTime> {
+    if time_at < 0 || time_at > printer_up_time {
+        return None;
+    }
+    now.checked_sub(Duration::from_secs(u64::try_from(printer_up_time - time_at).ok()?))
+}
+
+/// Build the `job-ids` operation attribute from a list of job IDs.
+/// The value is always encoded as `1setOf integer`, even for a single job.
+#[cfg(feature = "ipp-system-service")]
+pub fn job_ids_attribute(job_ids: &[i32]) -> IppAttribute {
+    IppAttribute::with_name(
+        IppAttribute::JOB_IDS,
+        IppValue::Array(job_ids.iter().copied().map(IppValue::Integer).collect()),
+    )
+    .unwrap() // the name is a short static keyword
+}
+
+/// Decode the `job-ids` value, which may be a single integer or an array of integers.
+/// Non-integer values are skipped.
+#[cfg(feature = "ipp-system-service")]
+pub fn parse_job_ids(value: &IppValue) -> Vec<i32> {
+    value.into_iter().filter_map(|v| v.as_integer()).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{
+        model::{IppVersion, StatusCode},
+        parser::IppParser,
+        reader::IppReader,
+        request::IppRequestResponse,
+        value::IppValue,
+    };
+
+    use super::*;
+
+    fn job_group(
+        job_id: i32,
+        state: JobState,
+        reasons: &[&str],
+        name: &str,
+        times: &[(&str, i32)],
+    ) -> IppAttributeGroup {
+        let mut group = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+        let mut attrs = vec![
+            IppAttribute::with_name(IppAttribute::JOB_ID, IppValue::Integer(job_id)).unwrap(),
+            IppAttribute::with_name(IppAttribute::JOB_STATE, IppValue::Enum(state as i32)).unwrap(),
+            IppAttribute::with_name(
+                IppAttribute::JOB_STATE_REASONS,
+                IppValue::Array(
+                    reasons
+                        .iter()
+                        .map(|r| IppValue::Keyword((*r).try_into().unwrap()))
+                        .collect(),
+                ),
+            )
+            .unwrap(),
+            IppAttribute::with_name(
+                IppAttribute::JOB_NAME,
+                IppValue::NameWithoutLanguage(name.try_into().unwrap()),
+            )
+            .unwrap(),
+            IppAttribute::with_name(
+                IppAttribute::JOB_ORIGINATING_USER_NAME,
+                IppValue::NameWithoutLanguage("alice".try_into().unwrap()),
+            )
+            .unwrap(),
+            IppAttribute::with_name(
+                IppAttribute::JOB_PRINTER_URI,
+                IppValue::Uri("ipp://printer.local/ipp/print".try_into().unwrap()),
+            )
+            .unwrap(),
+        ];
+        attrs.extend(
+            times
+                .iter()
+                .map(|(name, time)| IppAttribute::with_name(name, IppValue::Integer(*time)).unwrap()),
+        );
+        for attr in attrs {
+            group.insert(attr);
+        }
+        group
+    }
+
+    fn get_jobs_response() -> IppRequestResponse {
+        let mut resp =
+            IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, 1, IppAttributes::new())
+                .unwrap();
+        let groups = resp.attributes_mut().groups_mut();
+        groups.push(job_group(
+            101,
+            JobState::Completed,
+            &["job-completed-successfully"],
+            "report.pdf",
+            &[
+                (IppAttribute::TIME_AT_CREATION, 1000),
+                (IppAttribute::TIME_AT_PROCESSING, 1002),
+                (IppAttribute::TIME_AT_COMPLETED, 1010),
+            ],
+        ));
+        groups.push(job_group(
+            102,
+            JobState::Processing,
+            &["job-printing"],
+            "photo.jpg",
+            &[
+                (IppAttribute::TIME_AT_CREATION, 1100),
+                (IppAttribute::TIME_AT_PROCESSING, 1101),
+            ],
+        ));
+        groups.push(job_group(
+            103,
+            JobState::Pending,
+            &["none"],
+            "letter.txt",
+            &[(IppAttribute::TIME_AT_CREATION, 1200)],
+        ));
+
+        IppParser::new(IppReader::new(io::Cursor::new(resp.to_bytes().to_vec())))
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_get_jobs_response() {
+        let jobs = JobStatus::all_from_attributes(get_jobs_response().attributes());
+        assert_eq!(jobs.len(), 3);
+
+        assert_eq!(
+            jobs[0],
+            JobStatus {
+                job_id: 101,
+                state: JobState::Completed,
+                state_reasons: vec!["job-completed-successfully".to_owned()],
+                job_name: Some("report.pdf".to_owned()),
+                originating_user: Some("alice".to_owned()),
+                printer_uri: Some("ipp://printer.local/ipp/print".to_owned()),
+                time_at_creation: Some(1000),
+                time_at_processing: Some(1002),
+                time_at_completed: Some(1010),
+            }
+        );
+        assert!(jobs[0].is_finished());
+
+        assert_eq!(jobs[1].job_id, 102);
+        assert_eq!(jobs[1].state, JobState::Processing);
+        assert_eq!(jobs[1].time_at_completed, None);
+        assert!(!jobs[1].is_finished());
+
+        assert_eq!(jobs[2].job_id, 103);
+        assert_eq!(jobs[2].state_reasons, vec!["none"]);
+        assert_eq!(jobs[2].time_at_processing, None);
+    }
+
+    #[test]
+    fn test_job_status_from_attributes() {
+        let job = JobStatus::from_attributes(get_jobs_response().attributes()).unwrap();
+        assert_eq!(job.job_id, 101);
+
+        assert_eq!(JobStatus::from_attributes(&IppAttributes::new()), None);
+    }
+
+    #[test]
+    fn test_job_status_missing_state() {
+        let mut group = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+        let attr = IppAttribute::with_name(IppAttribute::JOB_ID, IppValue::Integer(1)).unwrap();
+        group.insert(attr);
+        assert_eq!(JobStatus::from_group(&group), None);
+    }
+
+    #[cfg(feature = "ipp-system-service")]
+    #[test]
+    fn test_job_ids() {
+        let attr = job_ids_attribute(&[1, 2, 300]);
+        let mut expected = vec![0x21, 0, 7];
+        expected.extend(b"job-ids");
+        expected.extend([0, 4, 0, 0, 0, 1]);
+        expected.extend([0x21, 0, 0, 0, 4, 0, 0, 0, 2]);
+        expected.extend([0x21, 0, 0, 0, 4, 0, 0, 1, 44]);
+        assert_eq!(attr.to_bytes(), expected);
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 1];
+        data.extend(attr.to_bytes());
+        data.push(3);
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let group = res
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .unwrap();
+        let value = group.attributes()[IppAttribute::JOB_IDS].value();
+        assert_eq!(value, attr.value());
+        assert_eq!(parse_job_ids(value), vec![1, 2, 300]);
+
+        assert_eq!(parse_job_ids(&IppValue::Integer(5)), vec![5]);
+    }
+
+    #[test]
+    fn test_number_up() {
+        let supported = IppValue::Array(vec![IppValue::Integer(1), IppValue::Integer(2), IppValue::Integer(4)]);
+        let number_up = NumberUp::new(4, Some(&supported)).unwrap();
+        assert_eq!(number_up.value(), 4);
+
+        let attr = number_up.into_attribute();
+        assert_eq!(attr.name().as_str(), IppAttribute::NUMBER_UP);
+        assert_eq!(attr.value(), &IppValue::Integer(4));
+
+        let range = IppValue::RangeOfInteger { min: 1, max: 16 };
+        assert!(NumberUp::new(9, Some(&range)).is_ok());
+        assert!(NumberUp::new(9, None).is_ok());
+    }
+
+    #[test]
+    fn test_number_up_unsupported() {
+        let supported = IppValue::Array(vec![IppValue::Integer(1), IppValue::Integer(2), IppValue::Integer(4)]);
+        let result = NumberUp::new(3, Some(&supported));
+        assert!(matches!(
+            result,
+            Err(IppParseError::UnsupportedValue { ref name, ref value }) if name == "number-up" && value == "3"
+        ));
+        assert!(NumberUp::new(0, None).is_err());
+    }
+
+    #[test]
+    fn test_print_scaling() {
+        let attr = print_scaling_attribute(PrintScaling::Fit);
+        assert_eq!(attr.name().as_str(), IppAttribute::PRINT_SCALING);
+        assert_eq!(attr.value(), &IppValue::Keyword("fit".try_into().unwrap()));
+
+        assert_eq!(PrintScaling::from_keyword("fill"), Some(PrintScaling::Fill));
+        assert_eq!(PrintScaling::from_keyword("stretch"), None);
+    }
+
+    #[test]
+    fn test_up_time_to_system_time() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        // job created 10 minutes ago, printer running for an hour
+        assert_eq!(
+            up_time_to_system_time(3000, 3600, now),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_699_999_400))
+        );
+        assert_eq!(up_time_to_system_time(3600, 3600, now), Some(now));
+        assert_eq!(up_time_to_system_time(-1, 3600, now), None);
+        assert_eq!(up_time_to_system_time(3601, 3600, now), None);
+    }
+
+    #[test]
+    fn test_job_options() {
+        let group = JobOptions::new()
+            .copies(2)
+            .sides(Sides::TwoSidedLongEdge)
+            .media("iso_a4_210x297mm")
+            .color_mode(PrintColorMode::Color)
+            .number_up(2)
+            .print_quality(PrintQuality::High)
+            .build()
+            .unwrap();
+
+        assert_eq!(group.tag(), DelimiterTag::JobAttributes);
+        let attrs = group.attributes();
+        assert_eq!(attrs.len(), 6);
+        assert_eq!(attrs[IppAttribute::COPIES].value(), &IppValue::Integer(2));
+        assert_eq!(
+            attrs[IppAttribute::SIDES].value(),
+            &IppValue::Keyword("two-sided-long-edge".try_into().unwrap())
+        );
+        assert_eq!(
+            attrs[IppAttribute::MEDIA].value(),
+            &IppValue::Keyword("iso_a4_210x297mm".try_into().unwrap())
+        );
+        assert_eq!(
+            attrs[IppAttribute::PRINT_COLOR_MODE].value(),
+            &IppValue::Keyword("color".try_into().unwrap())
+        );
+        assert_eq!(attrs[IppAttribute::NUMBER_UP].value(), &IppValue::Integer(2));
+        assert_eq!(attrs[IppAttribute::PRINT_QUALITY].value(), &IppValue::Enum(5));
+        for attr in attrs.values() {
+            assert!(crate::registry::check_syntax(attr.name().as_str(), attr.value()).is_empty());
+        }
+
+        assert!(JobOptions::new().build().unwrap().attributes().is_empty());
+    }
+
+    #[test]
+    fn test_job_options_invalid() {
+        assert!(matches!(
+            JobOptions::new().copies(0).build(),
+            Err(IppParseError::UnsupportedValue { ref name, .. }) if name == IppAttribute::COPIES
+        ));
+        assert!(matches!(
+            JobOptions::new().media("ISO A4").build(),
+            Err(IppParseError::UnsupportedValue { ref name, .. }) if name == IppAttribute::MEDIA
+        ));
+        assert!(JobOptions::new().number_up(0).build().is_err());
+    }
+}
@@ -6,6 +6,10 @@
 //! * using any third-party HTTP client and send the serialized request manually.
 //!
 //! This crate supports both synchronous and asynchronous operations. The following feature flags are supported:
+//! * `std` - enables the parts of the crate which depend on the standard library: I/O, parser, attributes,
+//!   operations and clients. Without it only the `no_std` + `alloc` core is built: IPP values, including
+//!   [`value::BoundedString`], value encoding and decoding, model definitions and [`IppHeader`].
+//!   Implied by all client and `async` features.
 //! * `async` - enables asynchronous APIs.
 //! * `async-client` - enables an asynchronous IPP client based on `reqwest` crate, implies `async` feature.
 //! * `async-client-rustls` - enables an asynchronous IPP client with TLS, using `rustls` backend. Implies `async-client` feature.
@@ -13,8 +17,13 @@
 //! * `client` - enables a blocking IPP client based on `ureq` crate.
 //! * `client-rustls` - enables a blocking IPP client with TLS, using `rustls` backend. Implies `client` feature.
 //! * `client-tls` - enables a blocking IPP client with TLS, using `native-tls` backend. Implies `client` feature.
+//! * `compression` - decodes gzip and deflate compressed responses in the clients.
+//! * `schemars` - implements `schemars::JsonSchema` for the serde representation of IPP values and attributes.
+//! * `ipp-job-set-operations` - enables helpers for the IPP Job and Printer Set Operations extension (RFC 3380).
+//! * `ipp-set-extensions` - enables helpers for the IPP Job and Printer Extensions - Set 3 (PWG 5100.13), e.g. `job-constraints-supported`.
+//! * `ipp-system-service` - enables helpers for the IPP System Service (PWG 5100.22), e.g. `client-info` collections.
 //!
-//! By default, the `async-client-rustls` feature is enabled. Some old printers may not support the latest TLS standards;
+//! By default, the `std` and `async-client-rustls` features are enabled. Some old printers may not support the latest TLS standards;
 //! in that case you can choose to use `async-client-tls` or `client-tls`, which will use platform-specific `native-tls`.
 //!
 //! Implementation notes:
@@ -59,29 +68,55 @@
 //! }
 //!```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::result_large_err)]
 
+extern crate alloc;
+
 use bytes::{BufMut, Bytes, BytesMut};
 use num_traits::FromPrimitive;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::model::{IppVersion, StatusCode};
+use crate::{
+    error::IppParseError,
+    model::{IppVersion, Operation, StatusCode},
+};
 
+#[cfg(feature = "std")]
 pub mod attribute;
 #[cfg(any(feature = "client", feature = "async-client"))]
 pub mod client;
+#[cfg(feature = "std")]
+pub mod collection;
+#[cfg(feature = "compression")]
+pub mod compression;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod job;
 pub mod model;
+#[cfg(feature = "std")]
 pub mod operation;
+#[cfg(feature = "std")]
 pub mod parser;
+#[cfg(feature = "std")]
 pub mod payload;
+#[cfg(feature = "std")]
+pub mod printer;
+#[cfg(feature = "std")]
 pub mod reader;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
 pub mod request;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(feature = "std")]
 pub mod util;
 pub mod value;
 
+#[cfg(feature = "std")]
 pub mod prelude {
     //!
     //! Common imports
@@ -131,6 +166,9 @@ impl IppHeader {
         }
     }
 
+    /// Size of the encoded header in bytes
+    pub const ENCODED_LEN: usize = 8;
+
     /// Write header to a given writer
     pub fn to_bytes(&self) -> Bytes {
         let mut buffer = BytesMut::new();
@@ -145,9 +183,14 @@ impl IppHeader {
     pub fn status_code(&self) -> StatusCode {
         StatusCode::from_u16(self.operation_or_status).unwrap_or(StatusCode::UnknownStatusCode)
     }
+
+    /// Decode and get IPP operation from the header of a request
+    pub fn operation(&self) -> Result<Operation, IppParseError> {
+        Operation::from_u16(self.operation_or_status).ok_or(IppParseError::InvalidOperation(self.operation_or_status))
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -157,4 +200,16 @@ mod tests {
         let buf = header.to_bytes();
         assert_eq!(buf, vec![0x02, 0x01, 0x12, 0x34, 0xaa, 0x55, 0xaa, 0x55]);
     }
+
+    #[test]
+    fn test_header_operation() {
+        let header = IppHeader::new(IppVersion::v1_1(), Operation::PrintJob as u16, 1);
+        assert_eq!(header.operation().unwrap(), Operation::PrintJob);
+
+        let header = IppHeader::new(IppVersion::v1_1(), 0x0001, 1);
+        assert!(matches!(
+            header.operation(),
+            Err(IppParseError::InvalidOperation(0x0001))
+        ));
+    }
 }
@@ -1,12 +1,16 @@
 //!
 //! Base IPP definitions and tags
 //!
-use std::fmt;
+use core::fmt;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use enum_primitive_derive::Primitive;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+#[cfg(feature = "std")]
+use crate::attribute::IppAttribute;
 
 /// IPP protocol version
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -29,6 +33,14 @@ impl IppVersion {
     pub const fn v2_2() -> Self {
         IppVersion(0x0202)
     }
+
+    /// Parse version from the "major.minor" keyword used by ipp-versions-supported, e.g. "2.0"
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        let (major, minor) = keyword.split_once('.')?;
+        Some(IppVersion(
+            u16::from(major.parse::<u8>().ok()?) << 8 | u16::from(minor.parse::<u8>().ok()?),
+        ))
+    }
 }
 
 /// IPP operation constants
@@ -72,6 +84,53 @@ pub enum Operation {
     CupsCreateLocalPrinter = 0x4028,
 }
 
+#[cfg(feature = "std")]
+impl Operation {
+    /// Return the operation attributes required by the specification for this operation,
+    /// in addition to attributes-charset and attributes-natural-language.
+    /// Job operations may use job-uri instead of printer-uri and job-id.
+    pub fn required_operation_attributes(&self) -> &'static [&'static str] {
+        match self {
+            Operation::PrintJob
+            | Operation::ValidateJob
+            | Operation::CreateJob
+            | Operation::GetJobs
+            | Operation::GetPrinterAttributes
+            | Operation::PausePrinter
+            | Operation::ResumePrinter
+            | Operation::PurgeJobs
+            | Operation::CupsDeletePrinter
+            | Operation::CupsAcceptJobs
+            | Operation::CupsRejectJobs
+            | Operation::CupsSetDefault => &[IppAttribute::PRINTER_URI],
+            Operation::PrintUri => &[IppAttribute::PRINTER_URI, IppAttribute::DOCUMENT_URI],
+            Operation::SendDocument => &[
+                IppAttribute::PRINTER_URI,
+                IppAttribute::JOB_ID,
+                IppAttribute::LAST_DOCUMENT,
+            ],
+            Operation::SendUri => &[
+                IppAttribute::PRINTER_URI,
+                IppAttribute::JOB_ID,
+                IppAttribute::LAST_DOCUMENT,
+                IppAttribute::DOCUMENT_URI,
+            ],
+            Operation::CancelJob
+            | Operation::GetJobAttributes
+            | Operation::HoldJob
+            | Operation::ReleaseJob
+            | Operation::RestartJob
+            | Operation::CupsAuthenticateJob => &[IppAttribute::PRINTER_URI, IppAttribute::JOB_ID],
+            Operation::CupsGetDocument => &[
+                IppAttribute::PRINTER_URI,
+                IppAttribute::JOB_ID,
+                IppAttribute::DOCUMENT_NUMBER,
+            ],
+            _ => &[],
+        }
+    }
+}
+
 /// printer-state constants
 #[derive(Primitive, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum PrinterState {
@@ -80,6 +139,16 @@ pub enum PrinterState {
     Stopped = 5,
 }
 
+/// client-type constants (PWG 5100.7)
+#[cfg(feature = "ipp-system-service")]
+#[derive(Primitive, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ClientType {
+    Application = 3,
+    OperatingSystem = 4,
+    Driver = 5,
+    Other = 6,
+}
+
 /// paper orientation constants
 #[derive(Primitive, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Orientation {
@@ -89,6 +158,31 @@ pub enum Orientation {
     ReversePortrait = 6,
 }
 
+impl Orientation {
+    /// Counter-clockwise rotation of the content in degrees as defined by RFC 8011:
+    /// portrait is 0, landscape 90, reverse-portrait 180 and reverse-landscape 270
+    pub fn degrees(&self) -> i32 {
+        match self {
+            Orientation::Portrait => 0,
+            Orientation::Landscape => 90,
+            Orientation::ReversePortrait => 180,
+            Orientation::ReverseLandscape => 270,
+        }
+    }
+
+    /// Get the orientation for a rotation in degrees, normalized to 0..360.
+    /// Returns `None` if the rotation is not a multiple of 90 degrees.
+    pub fn from_degrees(degrees: i32) -> Option<Orientation> {
+        match degrees.rem_euclid(360) {
+            0 => Some(Orientation::Portrait),
+            90 => Some(Orientation::Landscape),
+            180 => Some(Orientation::ReversePortrait),
+            270 => Some(Orientation::ReverseLandscape),
+            _ => None,
+        }
+    }
+}
+
 /// print-quality constants
 #[derive(Primitive, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum PrintQuality {
@@ -97,6 +191,240 @@ pub enum PrintQuality {
     High = 5,
 }
 
+/// sides keywords
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Sides {
+    OneSided,
+    TwoSidedLongEdge,
+    TwoSidedShortEdge,
+}
+
+impl Sides {
+    /// Return the keyword value of this sides mode
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sides::OneSided => "one-sided",
+            Sides::TwoSidedLongEdge => "two-sided-long-edge",
+            Sides::TwoSidedShortEdge => "two-sided-short-edge",
+        }
+    }
+
+    /// Parse sides mode from the keyword value
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "one-sided" => Some(Sides::OneSided),
+            "two-sided-long-edge" => Some(Sides::TwoSidedLongEdge),
+            "two-sided-short-edge" => Some(Sides::TwoSidedShortEdge),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Sides {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// print-color-mode keywords
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PrintColorMode {
+    Auto,
+    AutoMonochrome,
+    BiLevel,
+    Color,
+    Highlight,
+    Monochrome,
+    ProcessBiLevel,
+    ProcessMonochrome,
+}
+
+impl PrintColorMode {
+    /// Return the keyword value of this color mode
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrintColorMode::Auto => "auto",
+            PrintColorMode::AutoMonochrome => "auto-monochrome",
+            PrintColorMode::BiLevel => "bi-level",
+            PrintColorMode::Color => "color",
+            PrintColorMode::Highlight => "highlight",
+            PrintColorMode::Monochrome => "monochrome",
+            PrintColorMode::ProcessBiLevel => "process-bi-level",
+            PrintColorMode::ProcessMonochrome => "process-monochrome",
+        }
+    }
+
+    /// Parse color mode from the keyword value
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "auto" => Some(PrintColorMode::Auto),
+            "auto-monochrome" => Some(PrintColorMode::AutoMonochrome),
+            "bi-level" => Some(PrintColorMode::BiLevel),
+            "color" => Some(PrintColorMode::Color),
+            "highlight" => Some(PrintColorMode::Highlight),
+            "monochrome" => Some(PrintColorMode::Monochrome),
+            "process-bi-level" => Some(PrintColorMode::ProcessBiLevel),
+            "process-monochrome" => Some(PrintColorMode::ProcessMonochrome),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PrintColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// print-scaling keywords
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PrintScaling {
+    Auto,
+    AutoFit,
+    Fill,
+    Fit,
+    None,
+}
+
+impl PrintScaling {
+    /// Return the keyword value of this scaling mode
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrintScaling::Auto => "auto",
+            PrintScaling::AutoFit => "auto-fit",
+            PrintScaling::Fill => "fill",
+            PrintScaling::Fit => "fit",
+            PrintScaling::None => "none",
+        }
+    }
+
+    /// Parse scaling mode from the keyword value
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "auto" => Some(PrintScaling::Auto),
+            "auto-fit" => Some(PrintScaling::AutoFit),
+            "fill" => Some(PrintScaling::Fill),
+            "fit" => Some(PrintScaling::Fit),
+            "none" => Some(PrintScaling::None),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PrintScaling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// uri-security-supported keywords
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum UriSecurity {
+    None,
+    Tls,
+}
+
+impl UriSecurity {
+    /// Return the keyword value of this security mechanism
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UriSecurity::None => "none",
+            UriSecurity::Tls => "tls",
+        }
+    }
+
+    /// Parse security mechanism from the keyword value
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "none" => Some(UriSecurity::None),
+            "tls" => Some(UriSecurity::Tls),
+            _ => None,
+        }
+    }
+}
+
+impl AsRef<str> for UriSecurity {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for UriSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// uri-authentication-supported keywords
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum UriAuthentication {
+    None,
+    RequestingUserName,
+    Basic,
+    Digest,
+    Certificate,
+}
+
+impl UriAuthentication {
+    /// Return the keyword value of this authentication mechanism
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UriAuthentication::None => "none",
+            UriAuthentication::RequestingUserName => "requesting-user-name",
+            UriAuthentication::Basic => "basic",
+            UriAuthentication::Digest => "digest",
+            UriAuthentication::Certificate => "certificate",
+        }
+    }
+
+    /// Parse authentication mechanism from the keyword value
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "none" => Some(UriAuthentication::None),
+            "requesting-user-name" => Some(UriAuthentication::RequestingUserName),
+            "basic" => Some(UriAuthentication::Basic),
+            "digest" => Some(UriAuthentication::Digest),
+            "certificate" => Some(UriAuthentication::Certificate),
+            _ => None,
+        }
+    }
+}
+
+impl AsRef<str> for UriAuthentication {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for UriAuthentication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// job-password-encryption keywords
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum JobPasswordEncryption {
+    None,
+    Md5,
+}
+
+impl JobPasswordEncryption {
+    /// Return the keyword value of this encryption method
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobPasswordEncryption::None => "none",
+            JobPasswordEncryption::Md5 => "md5",
+        }
+    }
+}
+
+impl fmt::Display for JobPasswordEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// finishings constants
 #[derive(Primitive, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Finishings {
@@ -155,13 +483,56 @@ pub enum JobState {
 
 /// group delimiter tags
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Primitive, Debug, Copy, Clone, PartialEq, Hash, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Hash, Eq)]
 pub enum DelimiterTag {
-    OperationAttributes = 0x01,
-    JobAttributes = 0x02,
-    EndOfAttributes = 0x03,
-    PrinterAttributes = 0x04,
-    UnsupportedAttributes = 0x05,
+    OperationAttributes,
+    JobAttributes,
+    EndOfAttributes,
+    PrinterAttributes,
+    UnsupportedAttributes,
+    /// delimiter tag in the 0x06-0x0f range which is not known to this crate, e.g. a vendor or future extension group
+    Unknown(u8),
+}
+
+impl From<DelimiterTag> for u8 {
+    fn from(tag: DelimiterTag) -> u8 {
+        match tag {
+            DelimiterTag::OperationAttributes => 0x01,
+            DelimiterTag::JobAttributes => 0x02,
+            DelimiterTag::EndOfAttributes => 0x03,
+            DelimiterTag::PrinterAttributes => 0x04,
+            DelimiterTag::UnsupportedAttributes => 0x05,
+            DelimiterTag::Unknown(tag) => tag,
+        }
+    }
+}
+
+impl FromPrimitive for DelimiterTag {
+    fn from_i64(n: i64) -> Option<DelimiterTag> {
+        u64::try_from(n).ok().and_then(DelimiterTag::from_u64)
+    }
+
+    fn from_u64(n: u64) -> Option<DelimiterTag> {
+        match n {
+            0x01 => Some(DelimiterTag::OperationAttributes),
+            0x02 => Some(DelimiterTag::JobAttributes),
+            0x03 => Some(DelimiterTag::EndOfAttributes),
+            0x04 => Some(DelimiterTag::PrinterAttributes),
+            0x05 => Some(DelimiterTag::UnsupportedAttributes),
+            0x06..=0x0f => Some(DelimiterTag::Unknown(n as u8)),
+            _ => None,
+        }
+    }
+}
+
+impl ToPrimitive for DelimiterTag {
+    fn to_i64(&self) -> Option<i64> {
+        Some(u8::from(*self).into())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        Some(u8::from(*self).into())
+    }
 }
 
 /// IPP value tags
@@ -193,6 +564,52 @@ pub enum ValueTag {
     MemberAttrName = 0x4a,
 }
 
+impl ValueTag {
+    /// Check if a value tag is registered by IANA, including the out-of-band tags defined by RFC 3380
+    /// and the extension tag, which are not represented by this enum
+    pub fn is_registered(tag: u8) -> bool {
+        matches!(
+            tag,
+            0x10 | 0x12 | 0x13 | 0x15..=0x17 | 0x21..=0x23 | 0x30..=0x37 | 0x41 | 0x42 | 0x44..=0x4a | 0x7f
+        )
+    }
+
+    /// Syntax name of the tag as used in RFC 8010, e.g. `textWithoutLanguage`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueTag::Unsupported => "unsupported",
+            ValueTag::Unknown => "unknown",
+            ValueTag::NoValue => "no-value",
+            ValueTag::Integer => "integer",
+            ValueTag::Boolean => "boolean",
+            ValueTag::Enum => "enum",
+            ValueTag::OctetStringUnspecified => "octetString",
+            ValueTag::DateTime => "dateTime",
+            ValueTag::Resolution => "resolution",
+            ValueTag::RangeOfInteger => "rangeOfInteger",
+            ValueTag::BegCollection => "begCollection",
+            ValueTag::TextWithLanguage => "textWithLanguage",
+            ValueTag::NameWithLanguage => "nameWithLanguage",
+            ValueTag::EndCollection => "endCollection",
+            ValueTag::TextWithoutLanguage => "textWithoutLanguage",
+            ValueTag::NameWithoutLanguage => "nameWithoutLanguage",
+            ValueTag::Keyword => "keyword",
+            ValueTag::Uri => "uri",
+            ValueTag::UriScheme => "uriScheme",
+            ValueTag::Charset => "charset",
+            ValueTag::NaturalLanguage => "naturalLanguage",
+            ValueTag::MimeMediaType => "mimeMediaType",
+            ValueTag::MemberAttrName => "memberAttrName",
+        }
+    }
+}
+
+impl fmt::Display for ValueTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 /// IPP status codes
 #[derive(Primitive, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum StatusCode {
@@ -283,3 +700,61 @@ impl fmt::Display for StatusCode {
         }
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipp_version_from_keyword() {
+        assert_eq!(IppVersion::from_keyword("2.2"), Some(IppVersion::v2_2()));
+        assert_eq!(IppVersion::from_keyword("1.0"), Some(IppVersion::v1_0()));
+        assert_eq!(IppVersion::from_keyword("2"), None);
+        assert_eq!(IppVersion::from_keyword("x.y"), None);
+    }
+
+    #[test]
+    fn test_orientation_degrees() {
+        for (orientation, degrees) in [
+            (Orientation::Portrait, 0),
+            (Orientation::Landscape, 90),
+            (Orientation::ReversePortrait, 180),
+            (Orientation::ReverseLandscape, 270),
+        ] {
+            assert_eq!(orientation.degrees(), degrees);
+            assert_eq!(Orientation::from_degrees(degrees), Some(orientation));
+        }
+        assert_eq!(Orientation::from_degrees(-90), Some(Orientation::ReverseLandscape));
+        assert_eq!(Orientation::from_degrees(450), Some(Orientation::Landscape));
+        assert_eq!(Orientation::from_degrees(45), None);
+    }
+
+    #[test]
+    fn test_value_tag_name() {
+        assert_eq!(ValueTag::Integer.name(), "integer");
+        assert_eq!(ValueTag::NoValue.name(), "no-value");
+        assert_eq!(ValueTag::OctetStringUnspecified.name(), "octetString");
+        assert_eq!(ValueTag::TextWithoutLanguage.to_string(), "textWithoutLanguage");
+    }
+
+    #[test]
+    fn test_required_operation_attributes() {
+        assert_eq!(
+            Operation::PrintJob.required_operation_attributes(),
+            &[IppAttribute::PRINTER_URI]
+        );
+        assert_eq!(
+            Operation::GetJobAttributes.required_operation_attributes(),
+            &[IppAttribute::PRINTER_URI, IppAttribute::JOB_ID]
+        );
+        assert_eq!(
+            Operation::SendDocument.required_operation_attributes(),
+            &[
+                IppAttribute::PRINTER_URI,
+                IppAttribute::JOB_ID,
+                IppAttribute::LAST_DOCUMENT
+            ]
+        );
+        assert!(Operation::CupsGetPrinters.required_operation_attributes().is_empty());
+    }
+}
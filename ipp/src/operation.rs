@@ -1,11 +1,13 @@
 //!
 //! High-level IPP operation abstractions
 //!
+use bytes::Bytes;
 use http::Uri;
+use md5::{Digest, Md5};
 
 use crate::{
     attribute::IppAttribute,
-    model::{DelimiterTag, IppVersion, Operation},
+    model::{DelimiterTag, IppVersion, JobPasswordEncryption, Operation, ValueTag},
     parser::IppParseError,
     payload::IppPayload,
     request::IppRequestResponse,
@@ -39,6 +41,84 @@ fn with_document_format(document_format: Option<IppMimeMediaType>, req: &mut Ipp
     }
 }
 
+fn with_attribute_fidelity(fidelity: Option<bool>, req: &mut IppRequestResponse) {
+    if let Some(fidelity) = fidelity {
+        req.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::new(
+                IppAttribute::IPP_ATTRIBUTE_FIDELITY.try_into().unwrap(),
+                IppValue::Boolean(fidelity),
+            ),
+        );
+    }
+}
+
+// job-account-id and job-accounting-user-id job template attributes (PWG 5100.7)
+fn with_job_accounting(accounting: JobAccounting, req: &mut IppRequestResponse) {
+    let names = [
+        (IppAttribute::JOB_ACCOUNT_ID, accounting.account_id),
+        (IppAttribute::JOB_ACCOUNTING_USER_ID, accounting.accounting_user_id),
+    ];
+    for (name, value) in names {
+        if let Some(value) = value {
+            req.attributes_mut().add(
+                DelimiterTag::JobAttributes,
+                IppAttribute::new(name.try_into().unwrap(), IppValue::NameWithoutLanguage(value)),
+            );
+        }
+    }
+}
+
+// job accounting attributes shared by Print-Job and Create-Job
+#[derive(Default)]
+struct JobAccounting {
+    account_id: Option<IppName>,
+    accounting_user_id: Option<IppName>,
+}
+
+// maximum length of the job-password octetString
+const JOB_PASSWORD_MAX_LEN: usize = 255;
+
+fn with_job_password(job_password: Option<(Bytes, JobPasswordEncryption)>, req: &mut IppRequestResponse) {
+    if let Some((password, encryption)) = job_password {
+        req.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::new(
+                IppAttribute::JOB_PASSWORD.try_into().unwrap(),
+                IppValue::Other {
+                    tag: ValueTag::OctetStringUnspecified as u8,
+                    data: password,
+                },
+            ),
+        );
+        req.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::new(
+                IppAttribute::JOB_PASSWORD_ENCRYPTION.try_into().unwrap(),
+                IppValue::Keyword(encryption.as_str().try_into().unwrap()),
+            ),
+        );
+    }
+}
+
+/// Encode the job password according to the given job-password-encryption method
+pub fn encrypt_job_password<P>(password: P, encryption: JobPasswordEncryption) -> Result<Bytes, IppParseError>
+where
+    P: AsRef<[u8]>,
+{
+    let password = password.as_ref();
+    if password.len() > JOB_PASSWORD_MAX_LEN {
+        return Err(IppParseError::InvalidStringLength {
+            len: password.len(),
+            max: JOB_PASSWORD_MAX_LEN,
+        });
+    }
+    Ok(match encryption {
+        JobPasswordEncryption::None => Bytes::copy_from_slice(password),
+        JobPasswordEncryption::Md5 => Bytes::copy_from_slice(&Md5::digest(password)),
+    })
+}
+
 /// Trait which represents a single IPP operation
 pub trait IppOperation {
     /// Convert this operation to IPP request which is ready for sending
@@ -48,6 +128,46 @@ pub trait IppOperation {
     fn version(&self) -> IppVersion {
         IppVersion::v1_1()
     }
+
+    /// Attach an extra attribute to a given group of the request, e.g. a vendor or uncommon job template attribute
+    /// which has no dedicated field. An attribute with the same name set by the operation itself is replaced.
+    fn with_attribute(self, tag: DelimiterTag, attribute: IppAttribute) -> WithAttributes<Self>
+    where
+        Self: Sized,
+    {
+        WithAttributes {
+            operation: self,
+            attributes: vec![(tag, attribute)],
+        }
+    }
+}
+
+/// IPP operation with extra attributes, created by [`IppOperation::with_attribute`]
+pub struct WithAttributes<T> {
+    operation: T,
+    attributes: Vec<(DelimiterTag, IppAttribute)>,
+}
+
+impl<T> WithAttributes<T> {
+    /// Attach one more attribute to a given group of the request
+    pub fn with_attribute(mut self, tag: DelimiterTag, attribute: IppAttribute) -> Self {
+        self.attributes.push((tag, attribute));
+        self
+    }
+}
+
+impl<T: IppOperation> IppOperation for WithAttributes<T> {
+    fn into_ipp_request(self) -> IppRequestResponse {
+        let mut retval = self.operation.into_ipp_request();
+        for (tag, attribute) in self.attributes {
+            retval.attributes_mut().add(tag, attribute);
+        }
+        retval
+    }
+
+    fn version(&self) -> IppVersion {
+        self.operation.version()
+    }
 }
 
 impl<T: IppOperation> From<T> for IppRequestResponse {
@@ -63,6 +183,9 @@ pub struct PrintJob {
     user_name: Option<IppName>,
     job_name: Option<IppName>,
     document_format: Option<IppMimeMediaType>,
+    job_password: Option<(Bytes, JobPasswordEncryption)>,
+    attribute_fidelity: Option<bool>,
+    accounting: JobAccounting,
     attributes: Vec<IppAttribute>,
 }
 
@@ -93,6 +216,9 @@ impl PrintJob {
             user_name: user_name.map(|v| v.as_ref().to_string().try_into()).transpose()?,
             job_name: job_name.map(|v| v.as_ref().to_string().try_into()).transpose()?,
             document_format: document_format.map(|v| v.as_ref().to_string().try_into()).transpose()?,
+            job_password: None,
+            attribute_fidelity: None,
+            accounting: JobAccounting::default(),
             attributes: Vec::new(),
         })
     }
@@ -101,6 +227,40 @@ impl PrintJob {
     pub fn add_attribute(&mut self, attribute: IppAttribute) {
         self.attributes.push(attribute);
     }
+
+    /// Set job-password and job-password-encryption attributes, the password is encoded according to `encryption`
+    pub fn set_job_password<P>(&mut self, password: P, encryption: JobPasswordEncryption) -> Result<(), IppParseError>
+    where
+        P: AsRef<[u8]>,
+    {
+        self.job_password = Some((encrypt_job_password(password, encryption)?, encryption));
+        Ok(())
+    }
+
+    /// Set ipp-attribute-fidelity attribute. If true, the printer must reject the job when any of the requested
+    /// attributes or values is not supported. If false (the printer default), the printer ignores or substitutes
+    /// unsupported attributes, returns them in the unsupported attributes group and accepts the job.
+    pub fn set_attribute_fidelity(&mut self, fidelity: bool) {
+        self.attribute_fidelity = Some(fidelity);
+    }
+
+    /// Set job-account-id attribute, the account to charge for the job (PWG 5100.7)
+    pub fn set_job_account_id<S>(&mut self, account_id: S) -> Result<(), IppParseError>
+    where
+        S: AsRef<str>,
+    {
+        self.accounting.account_id = Some(account_id.as_ref().try_into()?);
+        Ok(())
+    }
+
+    /// Set job-accounting-user-id attribute, the user to charge for the job (PWG 5100.7)
+    pub fn set_job_accounting_user_id<S>(&mut self, user_id: S) -> Result<(), IppParseError>
+    where
+        S: AsRef<str>,
+    {
+        self.accounting.accounting_user_id = Some(user_id.as_ref().try_into()?);
+        Ok(())
+    }
 }
 
 impl IppOperation for PrintJob {
@@ -109,6 +269,9 @@ impl IppOperation for PrintJob {
 
         with_user_name(self.user_name, &mut retval);
         with_document_format(self.document_format, &mut retval);
+        with_job_password(self.job_password, &mut retval);
+        with_attribute_fidelity(self.attribute_fidelity, &mut retval);
+        with_job_accounting(self.accounting, &mut retval);
 
         if let Some(job_name) = self.job_name {
             retval.attributes_mut().add(
@@ -189,6 +352,8 @@ impl IppOperation for GetPrinterAttributes {
 pub struct CreateJob {
     printer_uri: IppString,
     job_name: Option<IppName>,
+    attribute_fidelity: Option<bool>,
+    accounting: JobAccounting,
     attributes: Vec<IppAttribute>,
 }
 
@@ -204,6 +369,8 @@ impl CreateJob {
         Ok(CreateJob {
             printer_uri: printer_uri.try_into()?,
             job_name: job_name.map(|v| v.as_ref().to_string().try_into()).transpose()?,
+            attribute_fidelity: None,
+            accounting: JobAccounting::default(),
             attributes: Vec::new(),
         })
     }
@@ -212,12 +379,38 @@ impl CreateJob {
     pub fn add_attribute(&mut self, attribute: IppAttribute) {
         self.attributes.push(attribute);
     }
+
+    /// Set ipp-attribute-fidelity attribute, see [`PrintJob::set_attribute_fidelity`]
+    pub fn set_attribute_fidelity(&mut self, fidelity: bool) {
+        self.attribute_fidelity = Some(fidelity);
+    }
+
+    /// Set job-account-id attribute, see [`PrintJob::set_job_account_id`]
+    pub fn set_job_account_id<S>(&mut self, account_id: S) -> Result<(), IppParseError>
+    where
+        S: AsRef<str>,
+    {
+        self.accounting.account_id = Some(account_id.as_ref().try_into()?);
+        Ok(())
+    }
+
+    /// Set job-accounting-user-id attribute, see [`PrintJob::set_job_accounting_user_id`]
+    pub fn set_job_accounting_user_id<S>(&mut self, user_id: S) -> Result<(), IppParseError>
+    where
+        S: AsRef<str>,
+    {
+        self.accounting.accounting_user_id = Some(user_id.as_ref().try_into()?);
+        Ok(())
+    }
 }
 
 impl IppOperation for CreateJob {
     fn into_ipp_request(self) -> IppRequestResponse {
         let mut retval = IppRequestResponse::new_internal(self.version(), Operation::CreateJob, Some(self.printer_uri));
 
+        with_attribute_fidelity(self.attribute_fidelity, &mut retval);
+        with_job_accounting(self.accounting, &mut retval);
+
         if let Some(job_name) = self.job_name {
             retval.attributes_mut().add(
                 DelimiterTag::OperationAttributes,
@@ -443,3 +636,148 @@ impl IppOperation for GetJobs {
         retval
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print_job_with_password(encryption: JobPasswordEncryption) -> IppRequestResponse {
+        let mut op = PrintJob::new(
+            "ipp://localhost/printers/test".parse().unwrap(),
+            IppPayload::empty(),
+            Some("user"),
+            None::<&str>,
+            None::<&str>,
+        )
+        .unwrap();
+        op.set_job_password("1234", encryption).unwrap();
+        op.into_ipp_request()
+    }
+
+    fn operation_attribute(req: &IppRequestResponse, name: &str) -> IppValue {
+        req.attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .and_then(|g| g.attributes().get(name))
+            .map(|a| a.value().clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_with_attribute() {
+        let op = PrintJob::new(
+            "ipp://localhost/printers/test".parse().unwrap(),
+            IppPayload::empty(),
+            Some("user"),
+            None::<&str>,
+            None::<&str>,
+        )
+        .unwrap()
+        .with_attribute(
+            DelimiterTag::JobAttributes,
+            IppAttribute::integer("job-priority", 80).unwrap(),
+        )
+        .with_attribute(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::keyword("x-vendor-mode", "fast").unwrap(),
+        );
+        let req = op.into_ipp_request();
+
+        let job = req.attributes().groups_of(DelimiterTag::JobAttributes).next().unwrap();
+        assert_eq!(job.attributes()["job-priority"].value(), &IppValue::Integer(80));
+        assert_eq!(
+            operation_attribute(&req, "x-vendor-mode"),
+            IppValue::Keyword("fast".try_into().unwrap())
+        );
+        assert_eq!(
+            operation_attribute(&req, IppAttribute::REQUESTING_USER_NAME),
+            IppValue::NameWithoutLanguage("user".try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_job_password_none() {
+        let req = print_job_with_password(JobPasswordEncryption::None);
+
+        assert_eq!(
+            operation_attribute(&req, IppAttribute::JOB_PASSWORD),
+            IppValue::Other {
+                tag: ValueTag::OctetStringUnspecified as u8,
+                data: Bytes::from_static(b"1234"),
+            }
+        );
+        assert_eq!(
+            operation_attribute(&req, IppAttribute::JOB_PASSWORD_ENCRYPTION),
+            IppValue::Keyword("none".try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_job_password_md5() {
+        let req = print_job_with_password(JobPasswordEncryption::Md5);
+
+        // md5("1234") = 81dc9bdb52d04dc20036dbd8313ed055
+        let expected = [
+            0x81, 0xdc, 0x9b, 0xdb, 0x52, 0xd0, 0x4d, 0xc2, 0x00, 0x36, 0xdb, 0xd8, 0x31, 0x3e, 0xd0, 0x55,
+        ];
+        assert_eq!(
+            operation_attribute(&req, IppAttribute::JOB_PASSWORD),
+            IppValue::Other {
+                tag: ValueTag::OctetStringUnspecified as u8,
+                data: Bytes::copy_from_slice(&expected),
+            }
+        );
+        assert_eq!(
+            operation_attribute(&req, IppAttribute::JOB_PASSWORD_ENCRYPTION),
+            IppValue::Keyword("md5".try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_job_accounting() {
+        let mut op = PrintJob::new(
+            "ipp://localhost/printers/test".parse().unwrap(),
+            IppPayload::empty(),
+            Some("user"),
+            None::<&str>,
+            None::<&str>,
+        )
+        .unwrap();
+        op.set_job_account_id("dept-42").unwrap();
+        op.set_job_accounting_user_id("jdoe").unwrap();
+        let req = op.into_ipp_request();
+
+        let job = req.attributes().groups_of(DelimiterTag::JobAttributes).next().unwrap();
+        assert_eq!(
+            job.attributes()[IppAttribute::JOB_ACCOUNT_ID].value(),
+            &IppValue::NameWithoutLanguage("dept-42".try_into().unwrap())
+        );
+        assert_eq!(
+            job.attributes()[IppAttribute::JOB_ACCOUNTING_USER_ID].value(),
+            &IppValue::NameWithoutLanguage("jdoe".try_into().unwrap())
+        );
+
+        let mut op = CreateJob::new("ipp://localhost/printers/test".parse().unwrap(), None::<&str>).unwrap();
+        assert!(matches!(
+            op.set_job_account_id("x".repeat(256)),
+            Err(IppParseError::InvalidStringLength { len: 256, max: 255 })
+        ));
+        op.set_job_account_id("x".repeat(255)).unwrap();
+        let req = op.into_ipp_request();
+        let job = req.attributes().groups_of(DelimiterTag::JobAttributes).next().unwrap();
+        assert_eq!(job.attributes().len(), 1);
+        assert_eq!(
+            job.attributes()[IppAttribute::JOB_ACCOUNT_ID].value().to_string().len(),
+            255
+        );
+    }
+
+    #[test]
+    fn test_job_password_too_long() {
+        let result = encrypt_job_password([b'x'; 256], JobPasswordEncryption::None);
+        assert!(matches!(
+            result,
+            Err(IppParseError::InvalidStringLength { len: 256, max: 255 })
+        ));
+    }
+}
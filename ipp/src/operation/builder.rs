@@ -87,6 +87,10 @@ pub struct PrintJobBuilder {
     user_name: Option<String>,
     job_title: Option<String>,
     document_format: Option<String>,
+    job_password: Option<(Vec<u8>, JobPasswordEncryption)>,
+    attribute_fidelity: Option<bool>,
+    job_account_id: Option<String>,
+    job_accounting_user_id: Option<String>,
     attributes: Vec<IppAttribute>,
 }
 
@@ -98,6 +102,10 @@ impl PrintJobBuilder {
             user_name: None,
             job_title: None,
             document_format: None,
+            job_password: None,
+            attribute_fidelity: None,
+            job_account_id: None,
+            job_accounting_user_id: None,
             attributes: Vec::new(),
         }
     }
@@ -128,6 +136,39 @@ impl PrintJobBuilder {
         self
     }
 
+    /// Specify job-password attribute for secure release printing, encoded with the given job-password-encryption
+    pub fn job_password<P>(mut self, password: P, encryption: JobPasswordEncryption) -> Self
+    where
+        P: AsRef<[u8]>,
+    {
+        self.job_password = Some((password.as_ref().to_owned(), encryption));
+        self
+    }
+
+    /// Specify ipp-attribute-fidelity attribute: if true, the printer rejects the job when any attribute is not supported
+    pub fn attribute_fidelity(mut self, fidelity: bool) -> Self {
+        self.attribute_fidelity = Some(fidelity);
+        self
+    }
+
+    /// Specify job-account-id attribute, the account to charge for the job
+    pub fn job_account_id<S>(mut self, account_id: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.job_account_id = Some(account_id.as_ref().to_owned());
+        self
+    }
+
+    /// Specify job-accounting-user-id attribute, the user to charge for the job
+    pub fn job_accounting_user_id<S>(mut self, user_id: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.job_accounting_user_id = Some(user_id.as_ref().to_owned());
+        self
+    }
+
     /// Specify custom job attribute
     pub fn attribute(mut self, attribute: IppAttribute) -> Self {
         self.attributes.push(attribute);
@@ -145,13 +186,25 @@ impl PrintJobBuilder {
 
     /// Build operation
     pub fn build(self) -> Result<impl IppOperation, IppParseError> {
-        let op = PrintJob::new(
+        let mut op = PrintJob::new(
             self.printer_uri,
             self.payload,
             self.user_name.as_ref(),
             self.job_title.as_ref(),
             self.document_format.as_ref(),
         )?;
+        if let Some((password, encryption)) = self.job_password {
+            op.set_job_password(password, encryption)?;
+        }
+        if let Some(fidelity) = self.attribute_fidelity {
+            op.set_attribute_fidelity(fidelity);
+        }
+        if let Some(account_id) = self.job_account_id {
+            op.set_job_account_id(account_id)?;
+        }
+        if let Some(user_id) = self.job_accounting_user_id {
+            op.set_job_accounting_user_id(user_id)?;
+        }
         Ok(self.attributes.into_iter().fold(op, |mut op, attr| {
             op.add_attribute(attr);
             op
@@ -203,6 +256,9 @@ impl GetPrinterAttributesBuilder {
 pub struct CreateJobBuilder {
     printer_uri: Uri,
     job_name: Option<String>,
+    attribute_fidelity: Option<bool>,
+    job_account_id: Option<String>,
+    job_accounting_user_id: Option<String>,
     attributes: Vec<IppAttribute>,
 }
 
@@ -211,6 +267,9 @@ impl CreateJobBuilder {
         CreateJobBuilder {
             printer_uri,
             job_name: None,
+            attribute_fidelity: None,
+            job_account_id: None,
+            job_accounting_user_id: None,
             attributes: Vec::new(),
         }
     }
@@ -224,6 +283,30 @@ impl CreateJobBuilder {
         self
     }
 
+    /// Specify ipp-attribute-fidelity attribute: if true, the printer rejects the job when any attribute is not supported
+    pub fn attribute_fidelity(mut self, fidelity: bool) -> Self {
+        self.attribute_fidelity = Some(fidelity);
+        self
+    }
+
+    /// Specify job-account-id attribute, the account to charge for the job
+    pub fn job_account_id<S>(mut self, account_id: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.job_account_id = Some(account_id.as_ref().to_owned());
+        self
+    }
+
+    /// Specify job-accounting-user-id attribute, the user to charge for the job
+    pub fn job_accounting_user_id<S>(mut self, user_id: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.job_accounting_user_id = Some(user_id.as_ref().to_owned());
+        self
+    }
+
     /// Specify custom job attribute
     pub fn attribute(mut self, attribute: IppAttribute) -> Self {
         self.attributes.push(attribute);
@@ -241,7 +324,16 @@ impl CreateJobBuilder {
 
     /// Build operation
     pub fn build(self) -> Result<impl IppOperation, IppParseError> {
-        let op = CreateJob::new(self.printer_uri, self.job_name.as_ref())?;
+        let mut op = CreateJob::new(self.printer_uri, self.job_name.as_ref())?;
+        if let Some(fidelity) = self.attribute_fidelity {
+            op.set_attribute_fidelity(fidelity);
+        }
+        if let Some(account_id) = self.job_account_id {
+            op.set_job_account_id(account_id)?;
+        }
+        if let Some(user_id) = self.job_accounting_user_id {
+            op.set_job_accounting_user_id(user_id)?;
+        }
         Ok(self.attributes.into_iter().fold(op, |mut op, attr| {
             op.add_attribute(attr);
             op
@@ -445,4 +537,32 @@ impl CupsBuilder {
     pub fn delete_printer(&self, printer_uri: Uri) -> Result<impl IppOperation, IppParseError> {
         CupsDeletePrinter::new(printer_uri)
     }
+
+    /// CUPS-Accept-Jobs operation
+    pub fn accept_jobs(&self, printer_uri: Uri) -> Result<impl IppOperation, IppParseError> {
+        CupsAcceptJobs::new(printer_uri)
+    }
+
+    /// CUPS-Reject-Jobs operation with an optional printer-state-message
+    pub fn reject_jobs<S>(&self, printer_uri: Uri, message: Option<S>) -> Result<impl IppOperation, IppParseError>
+    where
+        S: AsRef<str>,
+    {
+        CupsRejectJobs::new(printer_uri, message)
+    }
+
+    /// CUPS-Set-Default operation
+    pub fn set_default(&self, printer_uri: Uri) -> Result<impl IppOperation, IppParseError> {
+        CupsSetDefault::new(printer_uri)
+    }
+
+    /// CUPS-Get-Document operation
+    pub fn get_document(
+        &self,
+        printer_uri: Uri,
+        job_id: i32,
+        document_number: i32,
+    ) -> Result<impl IppOperation, IppParseError> {
+        CupsGetDocument::new(printer_uri, job_id, document_number)
+    }
 }
@@ -5,7 +5,12 @@
 use http::Uri;
 
 use crate::{
-    model::Operation, operation::IppOperation, parser::IppParseError, request::IppRequestResponse, value::IppString,
+    attribute::IppAttribute,
+    model::{DelimiterTag, Operation},
+    operation::IppOperation,
+    parser::IppParseError,
+    request::IppRequestResponse,
+    value::{IppString, IppTextValue, IppValue},
 };
 
 /// IPP operation CUPS-Get-Printers
@@ -41,3 +46,243 @@ impl IppOperation for CupsDeletePrinter {
         IppRequestResponse::new_internal(self.version(), Operation::CupsDeletePrinter, Some(self.0))
     }
 }
+
+/// IPP operation CUPS-Accept-Jobs
+pub struct CupsAcceptJobs(IppString);
+
+impl CupsAcceptJobs {
+    /// Create CUPS-Accept-Jobs operation
+    pub fn new(printer_uri: Uri) -> Result<CupsAcceptJobs, IppParseError> {
+        Ok(CupsAcceptJobs(printer_uri.try_into()?))
+    }
+}
+
+impl IppOperation for CupsAcceptJobs {
+    fn into_ipp_request(self) -> IppRequestResponse {
+        IppRequestResponse::new_internal(self.version(), Operation::CupsAcceptJobs, Some(self.0))
+    }
+}
+
+/// IPP operation CUPS-Reject-Jobs
+pub struct CupsRejectJobs {
+    printer_uri: IppString,
+    message: Option<IppTextValue>,
+}
+
+impl CupsRejectJobs {
+    /// Create CUPS-Reject-Jobs operation
+    ///
+    /// * `printer_uri` - printer URI<br/>
+    /// * `message` - optional printer-state-message explaining why jobs are rejected<br/>
+    pub fn new<S>(printer_uri: Uri, message: Option<S>) -> Result<CupsRejectJobs, IppParseError>
+    where
+        S: AsRef<str>,
+    {
+        Ok(CupsRejectJobs {
+            printer_uri: printer_uri.try_into()?,
+            message: message.map(|v| v.as_ref().to_string().try_into()).transpose()?,
+        })
+    }
+}
+
+impl IppOperation for CupsRejectJobs {
+    fn into_ipp_request(self) -> IppRequestResponse {
+        let mut retval =
+            IppRequestResponse::new_internal(self.version(), Operation::CupsRejectJobs, Some(self.printer_uri));
+        if let Some(message) = self.message {
+            retval.attributes_mut().add(
+                DelimiterTag::OperationAttributes,
+                IppAttribute::new(
+                    IppAttribute::PRINTER_STATE_MESSAGE.try_into().unwrap(),
+                    IppValue::TextWithoutLanguage(message),
+                ),
+            );
+        }
+        retval
+    }
+}
+
+/// IPP operation CUPS-Set-Default
+pub struct CupsSetDefault(IppString);
+
+impl CupsSetDefault {
+    /// Create CUPS-Set-Default operation
+    pub fn new(printer_uri: Uri) -> Result<CupsSetDefault, IppParseError> {
+        Ok(CupsSetDefault(printer_uri.try_into()?))
+    }
+}
+
+impl IppOperation for CupsSetDefault {
+    fn into_ipp_request(self) -> IppRequestResponse {
+        IppRequestResponse::new_internal(self.version(), Operation::CupsSetDefault, Some(self.0))
+    }
+}
+
+/// IPP operation CUPS-Get-Document.
+/// The document data is returned in the payload of the response, following the attributes.
+pub struct CupsGetDocument {
+    printer_uri: IppString,
+    job_id: i32,
+    document_number: i32,
+}
+
+impl CupsGetDocument {
+    /// Create CUPS-Get-Document operation
+    ///
+    /// * `printer_uri` - printer URI<br/>
+    /// * `job_id` - job ID<br/>
+    /// * `document_number` - document number within the job, starting from 1<br/>
+    pub fn new(printer_uri: Uri, job_id: i32, document_number: i32) -> Result<CupsGetDocument, IppParseError> {
+        Ok(CupsGetDocument {
+            printer_uri: printer_uri.try_into()?,
+            job_id,
+            document_number,
+        })
+    }
+}
+
+impl IppOperation for CupsGetDocument {
+    fn into_ipp_request(self) -> IppRequestResponse {
+        let mut retval =
+            IppRequestResponse::new_internal(self.version(), Operation::CupsGetDocument, Some(self.printer_uri));
+        retval.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::new(IppAttribute::JOB_ID.try_into().unwrap(), IppValue::Integer(self.job_id)),
+        );
+        retval.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::new(
+                IppAttribute::DOCUMENT_NUMBER.try_into().unwrap(),
+                IppValue::Integer(self.document_number),
+            ),
+        );
+        retval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use crate::{
+        attribute::IppAttributes,
+        model::{IppVersion, StatusCode},
+        parser::IppParser,
+        reader::IppReader,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_document_request() {
+        let op = CupsGetDocument::new("ipp://localhost/printers/test".parse().unwrap(), 12, 1).unwrap();
+        let req = op.into_ipp_request();
+        assert_eq!(req.header().operation_or_status, Operation::CupsGetDocument as u16);
+
+        let attrs = req
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .unwrap();
+        assert_eq!(
+            attrs.attributes().get(IppAttribute::JOB_ID).map(|a| a.value()),
+            Some(&IppValue::Integer(12))
+        );
+        assert_eq!(
+            attrs.attributes().get(IppAttribute::DOCUMENT_NUMBER).map(|a| a.value()),
+            Some(&IppValue::Integer(1))
+        );
+    }
+
+    #[test]
+    fn test_get_document_response_body() {
+        let document = b"%PDF-1.7\n\x00\x01\x02\xff binary document data".to_vec();
+
+        let mut resp =
+            IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, 1, IppAttributes::new())
+                .unwrap();
+        resp.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(
+                IppAttribute::DOCUMENT_FORMAT,
+                IppValue::MimeMediaType("application/pdf".try_into().unwrap()),
+            )
+            .unwrap(),
+        );
+        *resp.payload_mut() = document.clone().into();
+
+        let mut data = Vec::new();
+        resp.into_read().read_to_end(&mut data).unwrap();
+
+        let mut parsed = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let format = parsed
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .and_then(|g| g.attributes().get(IppAttribute::DOCUMENT_FORMAT))
+            .map(|a| a.value().to_string());
+        assert_eq!(format.as_deref(), Some("application/pdf"));
+
+        let mut body = Vec::new();
+        parsed.payload_mut().read_to_end(&mut body).unwrap();
+        assert_eq!(body, document);
+    }
+
+    #[test]
+    fn test_accept_jobs_request() {
+        let op = CupsAcceptJobs::new("ipp://localhost/printers/test".parse().unwrap()).unwrap();
+        let req = op.into_ipp_request();
+        assert_eq!(req.header().operation_or_status, 0x4008);
+        assert_eq!(req.header().operation_or_status, Operation::CupsAcceptJobs as u16);
+    }
+
+    #[test]
+    fn test_reject_jobs_request() {
+        let uri: http::Uri = "ipp://localhost/printers/test".parse().unwrap();
+
+        let req = CupsRejectJobs::new(uri.clone(), Some("out of toner"))
+            .unwrap()
+            .into_ipp_request();
+        assert_eq!(req.header().operation_or_status, 0x4009);
+        let attrs = req
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .unwrap();
+        assert_eq!(
+            attrs
+                .attributes()
+                .get(IppAttribute::PRINTER_STATE_MESSAGE)
+                .map(|a| a.value().to_string()),
+            Some("out of toner".to_owned())
+        );
+
+        let req = CupsRejectJobs::new(uri, None::<&str>).unwrap().into_ipp_request();
+        let attrs = req
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .unwrap();
+        assert!(!attrs.attributes().contains_key(IppAttribute::PRINTER_STATE_MESSAGE));
+    }
+
+    #[test]
+    fn test_set_default_request() {
+        let op = CupsSetDefault::new("ipp://localhost/printers/test".parse().unwrap()).unwrap();
+        let req = op.into_ipp_request();
+        assert_eq!(req.header().operation_or_status, 0x400A);
+
+        let attrs = req
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .unwrap();
+        assert_eq!(
+            attrs
+                .attributes()
+                .get(IppAttribute::PRINTER_URI)
+                .map(|a| a.value().to_string()),
+            Some("ipp://localhost/printers/test".to_owned())
+        );
+    }
+}
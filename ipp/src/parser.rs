@@ -1,54 +1,34 @@
 //!
 //! IPP stream parser
 //!
-use std::{
-    collections::BTreeMap,
-    convert::Infallible,
-    io::{self, Read},
-    num::TryFromIntError,
-};
+//! octetString values longer than 1023 bytes are returned as [`IppValue::Other`] with the octetString tag.
+//! Values longer than 65535 bytes do not fit into the 16-bit length field and are sent as fragments: an
+//! octetString value of exactly 65535 bytes followed by an octetString value without a name is its continuation,
+//! and the fragments are concatenated into one value. Any other unnamed value is an additional value of a `1setOf`.
+//!
+use std::io::{self, Read};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use log::{error, trace};
 
 #[cfg(feature = "async")]
-use {crate::reader::AsyncIppReader, futures_util::io::AsyncRead};
+use {
+    crate::reader::AsyncIppReader,
+    futures_util::io::{AsyncRead, AsyncReadExt},
+};
 
+pub use crate::error::IppParseError;
 use crate::{
     FromPrimitive as _, IppHeader,
     attribute::{IppAttribute, IppAttributeGroup, IppAttributes},
     model::{DelimiterTag, ValueTag},
     reader::IppReader,
     request::IppRequestResponse,
-    value::{IppName, IppValue},
+    value::{IppCollection, IppName, IppValue, MAX_VALUE_LENGTH},
 };
 
-/// Parse error enum
-#[derive(Debug, thiserror::Error)]
-pub enum IppParseError {
-    #[error("Invalid tag: {0}")]
-    InvalidTag(u8),
-
-    #[error("Invalid IPP collection")]
-    InvalidCollection,
-
-    /// occurs when a string is too long for an IPP Value.
-    #[error("invalid string length: {len}, max: {max}")]
-    InvalidStringLength { len: usize, max: usize },
-
-    /// failure to parse int usually used when trying to convert usize -> u16 in this crate
-    #[error(transparent)]
-    InvalidIntValue(#[from] TryFromIntError),
-
-    #[error(transparent)]
-    IoError(#[from] io::Error),
-
-    #[error("infallible this should never happen")]
-    Infallible(#[from] Infallible),
-}
-
 // create a single value from one-element list, list otherwise
-fn list_or_value(mut list: Vec<IppValue>) -> IppValue {
+pub(crate) fn list_or_value(mut list: Vec<IppValue>) -> IppValue {
     if list.len() == 1 {
         list.remove(0)
     } else {
@@ -56,11 +36,75 @@ fn list_or_value(mut list: Vec<IppValue>) -> IppValue {
     }
 }
 
+// build a collection from a flat list of member names, each followed by one or more values
+fn collection_from_members(members: Vec<IppValue>) -> Result<IppCollection, IppParseError> {
+    let mut map = IppCollection::new();
+    let mut current: Option<(IppName, Vec<IppValue>)> = None;
+
+    for value in members {
+        match value {
+            IppValue::MemberAttrName(name) => {
+                if let Some((name, values)) = current.replace((name, Vec::new())) {
+                    if values.is_empty() {
+                        error!("Missing value for collection member {name}");
+                        return Err(IppParseError::InvalidCollection);
+                    }
+                    map.insert(name, list_or_value(values));
+                }
+            }
+            value => match current {
+                Some((_, ref mut values)) => values.push(value),
+                None => {
+                    error!("Collection value without member name");
+                    return Err(IppParseError::InvalidCollection);
+                }
+            },
+        }
+    }
+
+    if let Some((name, values)) = current {
+        if values.is_empty() {
+            error!("Missing value for collection member {name}");
+            return Err(IppParseError::InvalidCollection);
+        }
+        map.insert(name, list_or_value(values));
+    }
+
+    Ok(map)
+}
+
+const BOM: char = '\u{feff}';
+
+// remove a leading UTF-8 byte order mark from text and name values
+fn strip_bom(value: IppValue) -> Result<IppValue, IppParseError> {
+    Ok(match value {
+        IppValue::TextWithoutLanguage(ref text) if text.starts_with(BOM) => {
+            IppValue::TextWithoutLanguage(text.trim_start_matches(BOM).try_into()?)
+        }
+        IppValue::NameWithoutLanguage(ref name) if name.starts_with(BOM) => {
+            IppValue::NameWithoutLanguage(name.trim_start_matches(BOM).try_into()?)
+        }
+        IppValue::TextWithLanguage { language, ref text } if text.starts_with(BOM) => IppValue::TextWithLanguage {
+            language,
+            text: text.trim_start_matches(BOM).try_into()?,
+        },
+        IppValue::NameWithLanguage { language, ref name } if name.starts_with(BOM) => IppValue::NameWithLanguage {
+            language,
+            name: name.trim_start_matches(BOM).try_into()?,
+        },
+        value => value,
+    })
+}
+
 struct ParserState {
     current_group: Option<IppAttributeGroup>,
     last_name: Option<IppName>,
     context: Vec<Vec<IppValue>>,
+    // data of the fragmented octetString value which is the last value in the context, with the length of the last fragment
+    fragments: Option<(BytesMut, usize)>,
     attributes: IppAttributes,
+    strip_bom: bool,
+    lowercase_names: bool,
 }
 
 impl ParserState {
@@ -69,43 +113,129 @@ impl ParserState {
             current_group: None,
             last_name: None,
             context: vec![vec![]],
+            fragments: None,
             attributes: IppAttributes::new(),
+            strip_bom: true,
+            lowercase_names: false,
         }
     }
 
     fn add_last_attribute(&mut self) {
-        if let Some(last_name) = self.last_name.take() {
-            if let Some(val_list) = self.context.pop()
-                && let Some(ref mut group) = self.current_group
-            {
-                let attr = IppAttribute::new(last_name.clone(), list_or_value(val_list));
-                group.attributes_mut().insert(last_name, attr);
+        self.end_fragments();
+
+        if let Some(last_name) = self.last_name.take()
+            && let Some(val_list) = self.context.last_mut()
+        {
+            // single values are popped so that the buffer is reused for the next attribute,
+            // only multi-valued attributes hand over their buffer to the array
+            let value = if val_list.len() == 1 {
+                val_list.pop().unwrap()
+            } else {
+                IppValue::Array(std::mem::take(val_list))
+            };
+            if let Some(ref mut group) = self.current_group {
+                group.insert(IppAttribute::new(last_name, value));
             }
-            self.context.push(vec![]);
         }
     }
 
+    fn in_collection(&self) -> bool {
+        self.context.len() > 1
+    }
+
+    // End of stream inside of a collection is reported as an unterminated collection
+    fn read_error<E: Into<IppParseError>>(&self, error: E) -> IppParseError {
+        match error.into() {
+            IppParseError::IoError(e) if self.in_collection() && e.kind() == io::ErrorKind::UnexpectedEof => {
+                IppParseError::UnterminatedCollection
+            }
+            error => error,
+        }
+    }
+
+    // Text values are always decoded as UTF-8, which is only correct for utf-8 and its us-ascii subset
+    fn check_charset(&self, name: &IppName, value: &IppValue) -> Result<(), IppParseError> {
+        let is_operation_group = self
+            .current_group
+            .as_ref()
+            .is_some_and(|group| group.tag() == DelimiterTag::OperationAttributes);
+
+        if is_operation_group
+            && !self.in_collection()
+            && name.as_str() == IppAttribute::ATTRIBUTES_CHARSET
+            && let IppValue::Charset(charset) = value
+            && !charset.eq_ignore_ascii_case("utf-8")
+            && !charset.eq_ignore_ascii_case("us-ascii")
+        {
+            error!("Unsupported attributes-charset: {charset}");
+            return Err(IppParseError::UnsupportedCharset(charset.to_string()));
+        }
+        Ok(())
+    }
+
     fn parse_delimiter(&mut self, tag: u8) -> Result<DelimiterTag, IppParseError> {
         trace!("Delimiter tag: {tag:0x}");
 
         let tag = DelimiterTag::from_u8(tag).ok_or(IppParseError::InvalidTag(tag))?;
 
+        if self.in_collection() {
+            error!("Delimiter tag inside of collection");
+            return Err(IppParseError::UnterminatedCollection);
+        }
+
         self.add_last_attribute();
 
         if let Some(group) = self.current_group.take() {
             self.attributes.groups_mut().push(group);
         }
 
-        self.current_group = Some(IppAttributeGroup::new(tag));
+        self.current_group = Some(IppAttributeGroup::new(tag).lowercase_names(self.lowercase_names));
 
         Ok(tag)
     }
 
+    // Large binary values are sent as a sequence of octetString values for the same attribute, every fragment but
+    // the last one has the maximum length of 65535 bytes. The data is collected in one buffer until the value ends.
+    fn append_fragment(&mut self, tag: u8, value: &Bytes) -> bool {
+        match self.fragments {
+            Some((ref mut data, ref mut last_len))
+                if *last_len == MAX_VALUE_LENGTH && tag == ValueTag::OctetStringUnspecified as u8 =>
+            {
+                trace!("Appending {} bytes to octetString value", value.len());
+                data.extend_from_slice(value);
+                *last_len = value.len();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Store the collected fragments in the value they belong to
+    fn end_fragments(&mut self) {
+        if let Some((buffer, _)) = self.fragments.take()
+            && let Some(IppValue::Other { data, .. }) = self.context.last_mut().and_then(|list| list.last_mut())
+        {
+            *data = buffer.freeze();
+        }
+    }
+
     fn parse_value(&mut self, tag: u8, name: IppName, value: Bytes) -> Result<(), IppParseError> {
-        let ipp_value = IppValue::parse(tag, value)?;
+        if name.is_empty() && self.append_fragment(tag, &value) {
+            return Ok(());
+        }
+        self.end_fragments();
+
+        let first_fragment = tag == ValueTag::OctetStringUnspecified as u8 && value.len() == MAX_VALUE_LENGTH;
+
+        let mut ipp_value = IppValue::parse(tag, value)?;
+        if self.strip_bom {
+            ipp_value = strip_bom(ipp_value)?;
+        }
 
         trace!("Value tag: {tag:0x}: {name}: {ipp_value}");
 
+        self.check_charset(&name, &ipp_value)?;
+
         if !name.is_empty() {
             // single attribute or begin of array
             self.add_last_attribute();
@@ -133,19 +263,20 @@ impl ParserState {
                     return Err(IppParseError::InvalidCollection);
                 }
             }
+            if !self.in_collection() {
+                error!("End collection without begin collection");
+                return Err(IppParseError::InvalidCollection);
+            }
             if let Some(arr) = self.context.pop()
                 && let Some(val_list) = self.context.last_mut()
             {
-                let mut map: BTreeMap<IppName, IppValue> = BTreeMap::new();
-                for idx in (0..arr.len()).step_by(2) {
-                    if let (Some(IppValue::MemberAttrName(k)), Some(v)) = (arr.get(idx), arr.get(idx + 1)) {
-                        map.insert(k.clone(), v.clone());
-                    }
-                }
-                val_list.push(IppValue::Collection(map));
+                val_list.push(IppValue::Collection(collection_from_members(arr)?));
             }
         } else if let Some(val_list) = self.context.last_mut() {
             // add attribute to the current collection
+            if first_fragment && let IppValue::Other { ref data, .. } = ipp_value {
+                self.fragments = Some((BytesMut::from(data.clone()), data.len()));
+            }
             val_list.push(ipp_value);
         }
         Ok(())
@@ -175,10 +306,23 @@ where
         }
     }
 
+    /// Strip a leading UTF-8 byte order mark from text and name values, which some printers send. Enabled by default.
+    pub fn strip_bom(mut self, strip: bool) -> Self {
+        self.state.strip_bom = strip;
+        self
+    }
+
+    /// Lowercase attribute names of the parsed groups, see [`IppAttributeGroup::lowercase_names`].
+    /// Some printers send mixed-case names although IPP names are case-insensitive. Disabled by default.
+    pub fn lowercase_names(mut self, enabled: bool) -> Self {
+        self.state.lowercase_names = enabled;
+        self
+    }
+
     async fn parse_value(&mut self, tag: u8) -> Result<(), IppParseError> {
         // value tag
-        let name: IppName = self.reader.read_name().await?;
-        let value = self.reader.read_value().await?;
+        let name: IppName = self.reader.read_name().await.map_err(|e| self.state.read_error(e))?;
+        let value = self.reader.read_value().await.map_err(|e| self.state.read_error(e))?;
 
         self.state.parse_value(tag, name, value)
     }
@@ -188,8 +332,8 @@ where
         trace!("IPP header: {header:?}");
 
         loop {
-            match self.reader.read_tag().await? {
-                tag @ 0x01..=0x05 => {
+            match self.reader.read_tag().await.map_err(|e| self.state.read_error(e))? {
+                tag @ 0x01..=0x0f => {
                     if self.state.parse_delimiter(tag)? == DelimiterTag::EndOfAttributes {
                         break;
                     }
@@ -223,6 +367,21 @@ where
             payload: self.reader.into_payload(),
         })
     }
+
+    /// Parse IPP stream and read the remaining data after the attributes into memory.
+    /// This is suitable for responses which carry a document, e.g. CUPS-Get-Document.
+    pub async fn parse_buffered(mut self) -> Result<IppRequestResponse, IppParseError> {
+        let header = self.parse_header_attributes().await?;
+
+        let mut body = Vec::new();
+        self.reader.into_inner().read_to_end(&mut body).await?;
+
+        Ok(IppRequestResponse {
+            header,
+            attributes: self.state.attributes,
+            payload: body.into(),
+        })
+    }
 }
 
 /// Synchronous IPP parser
@@ -246,10 +405,23 @@ where
         }
     }
 
+    /// Strip a leading UTF-8 byte order mark from text and name values, which some printers send. Enabled by default.
+    pub fn strip_bom(mut self, strip: bool) -> Self {
+        self.state.strip_bom = strip;
+        self
+    }
+
+    /// Lowercase attribute names of the parsed groups, see [`IppAttributeGroup::lowercase_names`].
+    /// Some printers send mixed-case names although IPP names are case-insensitive. Disabled by default.
+    pub fn lowercase_names(mut self, enabled: bool) -> Self {
+        self.state.lowercase_names = enabled;
+        self
+    }
+
     fn parse_value(&mut self, tag: u8) -> Result<(), IppParseError> {
         // value tag
-        let name: IppName = self.reader.read_name()?;
-        let value = self.reader.read_value()?;
+        let name: IppName = self.reader.read_name().map_err(|e| self.state.read_error(e))?;
+        let value = self.reader.read_value().map_err(|e| self.state.read_error(e))?;
 
         self.state.parse_value(tag, name, value)
     }
@@ -259,8 +431,8 @@ where
         trace!("IPP header: {header:?}");
 
         loop {
-            match self.reader.read_tag()? {
-                tag @ 0x01..=0x05 => {
+            match self.reader.read_tag().map_err(|e| self.state.read_error(e))? {
+                tag @ 0x01..=0x0f => {
                     if self.state.parse_delimiter(tag)? == DelimiterTag::EndOfAttributes {
                         break;
                     }
@@ -294,11 +466,26 @@ where
             payload: self.reader.into_payload(),
         })
     }
+
+    /// Parse IPP stream and read the remaining data after the attributes into memory.
+    /// This is suitable for responses which carry a document, e.g. CUPS-Get-Document.
+    pub fn parse_buffered(mut self) -> Result<IppRequestResponse, IppParseError> {
+        let header = self.parse_header_attributes()?;
+
+        let mut body = Vec::new();
+        self.reader.into_inner().read_to_end(&mut body)?;
+
+        Ok(IppRequestResponse {
+            header,
+            attributes: self.state.attributes,
+            payload: body.into(),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::prelude::IppVersion;
+    use crate::{attribute::MergePolicy, prelude::IppVersion};
 
     use super::*;
 
@@ -385,7 +572,7 @@ mod tests {
         let attr = attrs.get("coll").unwrap();
         assert_eq!(
             attr.value(),
-            &IppValue::Collection(BTreeMap::from([(
+            &IppValue::Collection(IppCollection::from_iter([(
                 "abcd".try_into().unwrap(),
                 IppValue::Keyword("key".try_into().unwrap())
             )]))
@@ -521,7 +708,7 @@ mod tests {
         let attr = attrs.get("coll").unwrap();
         assert_eq!(
             attr.value(),
-            &IppValue::Collection(BTreeMap::from([(
+            &IppValue::Collection(IppCollection::from_iter([(
                 "abcd".try_into().unwrap(),
                 IppValue::Keyword("key".try_into().expect("failed to create IPP text value"))
             )]))
@@ -593,4 +780,854 @@ mod tests {
         assert_eq!(2, res.attributes().groups()[0].attributes().len());
         assert_eq!(1, res.attributes().groups()[1].attributes().len());
     }
+
+    #[test]
+    fn test_parse_empty_group() {
+        let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01];
+        data.extend(raw_keyword("attributes-charset", "utf-8"));
+        data.push(DelimiterTag::JobAttributes.into());
+        data.push(DelimiterTag::PrinterAttributes.into());
+        data.extend(raw_integer("printer-state", 3));
+        data.push(DelimiterTag::EndOfAttributes.into());
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data.clone())))
+            .parse()
+            .unwrap();
+
+        let groups = res.attributes().groups();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[1].tag(), DelimiterTag::JobAttributes);
+        assert!(groups[1].attributes().is_empty());
+        assert_eq!(groups[2].tag(), DelimiterTag::PrinterAttributes);
+        assert_eq!(groups[2].attributes().len(), 1);
+
+        assert_eq!(res.to_bytes(), data);
+    }
+
+    fn raw_value(tag: u8, name: &str, value: &[u8]) -> Vec<u8> {
+        let mut buf = vec![tag];
+        buf.extend((name.len() as u16).to_be_bytes());
+        buf.extend(name.as_bytes());
+        buf.extend((value.len() as u16).to_be_bytes());
+        buf.extend(value);
+        buf
+    }
+
+    fn raw_integer(name: &str, value: i32) -> Vec<u8> {
+        raw_value(ValueTag::Integer as u8, name, &value.to_be_bytes())
+    }
+
+    fn raw_keyword(name: &str, value: &str) -> Vec<u8> {
+        raw_value(ValueTag::Keyword as u8, name, value.as_bytes())
+    }
+
+    fn raw_member(name: &str) -> Vec<u8> {
+        raw_value(ValueTag::MemberAttrName as u8, "", name.as_bytes())
+    }
+
+    fn raw_begin(name: &str) -> Vec<u8> {
+        raw_value(ValueTag::BegCollection as u8, name, &[])
+    }
+
+    fn raw_end() -> Vec<u8> {
+        raw_value(ValueTag::EndCollection as u8, "", &[])
+    }
+
+    fn raw_media_col(name: &str, x: i32, y: i32, source: &str, media_type: &str) -> Vec<u8> {
+        [
+            raw_begin(name),
+            raw_member("media-bottom-margin"),
+            raw_integer("", 423),
+            raw_member("media-left-margin"),
+            raw_integer("", 423),
+            raw_member("media-right-margin"),
+            raw_integer("", 423),
+            raw_member("media-size"),
+            raw_begin(""),
+            raw_member("x-dimension"),
+            raw_integer("", x),
+            raw_member("y-dimension"),
+            raw_integer("", y),
+            raw_end(),
+            raw_member("media-source"),
+            raw_keyword("", source),
+            raw_member("media-top-margin"),
+            raw_integer("", 423),
+            raw_member("media-type"),
+            raw_keyword("", media_type),
+            raw_end(),
+        ]
+        .concat()
+    }
+
+    fn raw_media_col_default() -> Vec<u8> {
+        raw_media_col("media-col-default", 21000, 29700, "main", "stationery")
+    }
+
+    fn raw_media_col_ready() -> Vec<u8> {
+        [
+            raw_media_col("media-col-ready", 21000, 29700, "main", "stationery"),
+            raw_media_col("", 10160, 15240, "photo", "photographic-glossy"),
+        ]
+        .concat()
+    }
+
+    fn raw_finishings_col_database() -> Vec<u8> {
+        [
+            raw_begin("finishings-col-database"),
+            raw_member("finishing-template"),
+            raw_keyword("", "none"),
+            raw_end(),
+            raw_begin(""),
+            raw_member("finishing-template"),
+            raw_keyword("", "staple-dual-left"),
+            raw_member("stitching"),
+            raw_begin(""),
+            raw_member("stitching-locations"),
+            raw_integer("", 2540),
+            raw_integer("", 17780),
+            raw_member("stitching-offset"),
+            raw_integer("", 635),
+            raw_member("stitching-reference-edge"),
+            raw_keyword("", "left"),
+            raw_end(),
+            raw_end(),
+        ]
+        .concat()
+    }
+
+    fn parse_printer_attributes() -> IppRequestResponse {
+        let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01];
+        data.extend(raw_value(ValueTag::Charset as u8, "attributes-charset", b"utf-8"));
+        data.extend(raw_value(
+            ValueTag::NaturalLanguage as u8,
+            "attributes-natural-language",
+            b"en",
+        ));
+        data.push(u8::from(DelimiterTag::PrinterAttributes));
+        data.extend(raw_value(
+            ValueTag::NameWithoutLanguage as u8,
+            "printer-name",
+            b"office",
+        ));
+        data.extend(raw_media_col_default());
+        data.extend(raw_media_col_ready());
+        data.extend(raw_finishings_col_database());
+        data.extend(raw_keyword("media-default", "iso_a4_210x297mm"));
+        data.push(u8::from(DelimiterTag::EndOfAttributes));
+
+        IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap()
+    }
+
+    fn printer_attribute(res: &IppRequestResponse, name: &str) -> IppAttribute {
+        res.attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .and_then(|g| g.attributes().get(name))
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_media_col_default() {
+        let res = parse_printer_attributes();
+        let attr = printer_attribute(&res, IppAttribute::MEDIA_COL_DEFAULT);
+
+        let coll = attr.value().as_collection().unwrap();
+        assert_eq!(coll.len(), 7);
+        assert_eq!(
+            coll.get("media-source"),
+            Some(&IppValue::Keyword("main".try_into().unwrap()))
+        );
+        let size = coll.get("media-size").and_then(|v| v.as_collection()).unwrap();
+        assert_eq!(size.get("x-dimension"), Some(&IppValue::Integer(21000)));
+        assert_eq!(size.get("y-dimension"), Some(&IppValue::Integer(29700)));
+
+        assert_eq!(attr.to_bytes(), raw_media_col_default());
+    }
+
+    #[test]
+    fn test_parse_media_col_ready() {
+        let res = parse_printer_attributes();
+        let attr = printer_attribute(&res, IppAttribute::MEDIA_COL_READY);
+
+        let ready = attr.value().as_array().unwrap();
+        assert_eq!(ready.len(), 2);
+        let sources = ready
+            .iter()
+            .filter_map(|v| v.as_collection())
+            .filter_map(|c| c.get("media-source"))
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(sources, vec!["main", "photo"]);
+
+        assert_eq!(attr.to_bytes(), raw_media_col_ready());
+    }
+
+    #[test]
+    fn test_parse_finishings_col_database() {
+        let res = parse_printer_attributes();
+        let attr = printer_attribute(&res, "finishings-col-database");
+
+        let database = attr.value().as_array().unwrap();
+        assert_eq!(database.len(), 2);
+        let stitching = database[1]
+            .as_collection()
+            .and_then(|c| c.get("stitching"))
+            .and_then(|v| v.as_collection())
+            .unwrap();
+        assert_eq!(
+            stitching.get("stitching-locations"),
+            Some(&IppValue::Array(vec![
+                IppValue::Integer(2540),
+                IppValue::Integer(17780)
+            ]))
+        );
+        assert_eq!(stitching.get("stitching-offset"), Some(&IppValue::Integer(635)));
+
+        assert_eq!(attr.to_bytes(), raw_finishings_col_database());
+    }
+
+    #[test]
+    fn test_parse_attributes_after_collections() {
+        let res = parse_printer_attributes();
+        let group = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap();
+        assert_eq!(group.attributes().len(), 5);
+        assert_eq!(
+            printer_attribute(&res, IppAttribute::MEDIA_DEFAULT).value(),
+            &IppValue::Keyword("iso_a4_210x297mm".try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_collection_member_without_value() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_begin("coll"));
+        data.extend(raw_member("first"));
+        data.extend(raw_member("second"));
+        data.extend(raw_integer("", 1));
+        data.extend(raw_end());
+        data.push(3);
+
+        let result = IppParser::new(IppReader::new(io::Cursor::new(data))).parse();
+        assert!(matches!(result, Err(IppParseError::InvalidCollection)));
+    }
+
+    #[test]
+    fn test_parse_truncated_collection() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_begin("coll"));
+        data.extend(raw_member("first"));
+        data.extend(raw_integer("", 1));
+
+        let result = IppParser::new(IppReader::new(io::Cursor::new(data.clone()))).parse();
+        assert!(matches!(result, Err(IppParseError::UnterminatedCollection)));
+
+        data.push(3);
+        let result = IppParser::new(IppReader::new(io::Cursor::new(data))).parse();
+        assert!(matches!(result, Err(IppParseError::UnterminatedCollection)));
+    }
+
+    #[test]
+    fn test_parse_end_collection_without_begin() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_integer("value", 1));
+        data.extend(raw_end());
+        data.push(3);
+
+        let result = IppParser::new(IppReader::new(io::Cursor::new(data))).parse();
+        assert!(matches!(result, Err(IppParseError::InvalidCollection)));
+    }
+
+    #[test]
+    fn test_parse_unknown_delimiter() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 0x0a];
+        data.extend(raw_keyword("vendor-state", "ready"));
+        data.push(4);
+        data.extend(raw_integer("queued-job-count", 2));
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let groups = res.attributes().groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].tag(), DelimiterTag::Unknown(0x0a));
+        assert_eq!(
+            groups[0].attributes()["vendor-state"].value(),
+            &IppValue::Keyword("ready".try_into().unwrap())
+        );
+        assert_eq!(groups[1].tag(), DelimiterTag::PrinterAttributes);
+
+        let mut encoded = vec![1, 1, 0, 0, 0, 0, 0, 0];
+        encoded.extend(res.attributes().to_bytes());
+        let res = IppParser::new(IppReader::new(io::Cursor::new(encoded)))
+            .parse()
+            .unwrap();
+        assert!(res.attributes().groups_of(DelimiterTag::Unknown(0x0a)).next().is_some());
+    }
+
+    #[test]
+    fn test_parse_lowercase_names() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_value(ValueTag::NameWithoutLanguage as u8, "Printer-Name", b"foo"));
+        data.extend(raw_integer("Printer-Up-Time", 1));
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data.clone())))
+            .parse()
+            .unwrap();
+        let group = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap();
+        assert!(group.get(IppAttribute::PRINTER_NAME).is_none());
+
+        let mut res = IppParser::new(IppReader::new(io::Cursor::new(data)))
+            .lowercase_names(true)
+            .parse()
+            .unwrap();
+        let group = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap();
+        assert!(group.attributes().contains_key(IppAttribute::PRINTER_UP_TIME));
+        assert_eq!(
+            group.get("PRINTER-NAME").map(|a| a.value().to_string()).as_deref(),
+            Some("foo")
+        );
+
+        let name = |s: &str| IppAttribute::with_name(s, IppValue::NameWithoutLanguage(s.try_into().unwrap())).unwrap();
+        assert!(matches!(
+            res.attributes_mut()
+                .add_strict(DelimiterTag::PrinterAttributes, name("Printer-Name")),
+            Err(IppParseError::DuplicateAttribute { .. })
+        ));
+
+        let mut other = IppAttributes::new();
+        other.add(DelimiterTag::PrinterAttributes, name("PRINTER-NAME"));
+        res.attributes_mut().merge(&other, MergePolicy::KeepExisting);
+        let group = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap();
+        assert_eq!(group.attributes().len(), 2);
+        assert_eq!(
+            group.attributes()[IppAttribute::PRINTER_NAME].value().to_string(),
+            "foo"
+        );
+    }
+
+    #[test]
+    fn test_parse_strip_bom() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_value(
+            ValueTag::NameWithoutLanguage as u8,
+            "printer-name",
+            "\u{feff}Office".as_bytes(),
+        ));
+        data.extend(raw_value(
+            ValueTag::TextWithoutLanguage as u8,
+            "printer-location",
+            "Room \u{feff}1".as_bytes(),
+        ));
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data.clone())))
+            .parse()
+            .unwrap();
+        assert_eq!(
+            printer_attribute(&res, IppAttribute::PRINTER_NAME).value(),
+            &IppValue::NameWithoutLanguage("Office".try_into().unwrap())
+        );
+        // only a leading BOM is stripped
+        assert_eq!(
+            printer_attribute(&res, IppAttribute::PRINTER_LOCATION)
+                .value()
+                .to_string(),
+            "Room \u{feff}1"
+        );
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data)))
+            .strip_bom(false)
+            .parse()
+            .unwrap();
+        assert_eq!(
+            printer_attribute(&res, IppAttribute::PRINTER_NAME).value().to_string(),
+            "\u{feff}Office"
+        );
+    }
+
+    fn parse_with_charset(charset: &str) -> Result<IppRequestResponse, IppParseError> {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 1];
+        data.extend(raw_value(
+            ValueTag::Charset as u8,
+            "attributes-charset",
+            charset.as_bytes(),
+        ));
+        data.extend(raw_value(
+            ValueTag::NaturalLanguage as u8,
+            "attributes-natural-language",
+            b"en",
+        ));
+        data.push(4);
+        data.extend(raw_value(
+            ValueTag::TextWithoutLanguage as u8,
+            "printer-info",
+            b"Printer",
+        ));
+        data.push(3);
+
+        IppParser::new(IppReader::new(io::Cursor::new(data))).parse()
+    }
+
+    #[test]
+    fn test_parse_utf8_charset() {
+        let res = parse_with_charset("utf-8").unwrap();
+        let attrs = res
+            .attributes
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap();
+        assert_eq!(attrs.attributes()["printer-info"].value().to_string(), "Printer");
+    }
+
+    #[test]
+    fn test_parse_us_ascii_charset() {
+        assert!(parse_with_charset("us-ascii").is_ok());
+        assert!(parse_with_charset("US-ASCII").is_ok());
+    }
+
+    #[test]
+    fn test_parse_unsupported_charset() {
+        let result = parse_with_charset("iso-8859-1");
+        assert!(matches!(result, Err(IppParseError::UnsupportedCharset(ref charset)) if charset == "iso-8859-1"));
+    }
+
+    #[test]
+    fn test_parse_collection_out_of_band_members() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_begin("media-col"));
+        data.extend(raw_member("media-key"));
+        data.extend(raw_keyword("", "iso_a4_210x297mm"));
+        data.extend(raw_member("media-type"));
+        data.extend(raw_value(ValueTag::NoValue as u8, "", &[]));
+        data.extend(raw_member("media-source"));
+        data.extend(raw_value(ValueTag::Unknown as u8, "", &[]));
+        data.extend(raw_member("media-color"));
+        data.extend(raw_value(ValueTag::Unsupported as u8, "", &[]));
+        data.extend(raw_end());
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data.clone())))
+            .parse()
+            .unwrap();
+        let attr = printer_attribute(&res, IppAttribute::MEDIA_COL);
+        let coll = attr.value().as_collection().unwrap();
+        assert_eq!(coll.len(), 4);
+        assert_eq!(coll.get("media-type"), Some(&IppValue::NoValue));
+        assert_eq!(coll.get("media-source"), Some(&IppValue::Unknown));
+        assert_eq!(coll.get("media-color"), Some(&IppValue::Unsupported));
+
+        assert_eq!(attr.to_bytes().to_vec(), data[9..data.len() - 1]);
+    }
+
+    fn raw_with_document(document: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01];
+        data.extend(raw_value(ValueTag::Charset as u8, "attributes-charset", b"utf-8"));
+        data.extend(raw_value(
+            ValueTag::MimeMediaType as u8,
+            "document-format",
+            b"application/pdf",
+        ));
+        data.push(u8::from(DelimiterTag::EndOfAttributes));
+        data.extend(document);
+        data
+    }
+
+    const PDF_DOCUMENT: &[u8] =
+        b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n1 0 obj\n<< /Type /Catalog >>\nendobj\n\x00\x03\x01\n%%EOF\n";
+
+    fn assert_document_format(res: &IppRequestResponse) {
+        let format = res
+            .attributes()
+            .groups_of(DelimiterTag::OperationAttributes)
+            .next()
+            .and_then(|g| g.attributes().get(IppAttribute::DOCUMENT_FORMAT))
+            .map(|a| a.value().to_string());
+        assert_eq!(format.as_deref(), Some("application/pdf"));
+    }
+
+    #[test]
+    fn test_parse_buffered() {
+        let data = raw_with_document(PDF_DOCUMENT);
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data)))
+            .parse_buffered()
+            .unwrap();
+
+        assert_document_format(&res);
+        assert_eq!(res.payload().size(), Some(PDF_DOCUMENT.len() as u64));
+        assert_eq!(res.payload().as_bytes().as_deref(), Some(PDF_DOCUMENT));
+    }
+
+    #[test]
+    fn test_parse_buffered_no_document() {
+        let data = raw_with_document(&[]);
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data)))
+            .parse_buffered()
+            .unwrap();
+
+        assert_document_format(&res);
+        assert_eq!(res.payload().as_bytes(), Some(Bytes::new()));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_parse_buffered() {
+        let data = raw_with_document(PDF_DOCUMENT);
+        let res = AsyncIppParser::new(AsyncIppReader::new(futures_util::io::Cursor::new(data)))
+            .parse_buffered()
+            .await
+            .unwrap();
+
+        assert_document_format(&res);
+        assert_eq!(res.payload().as_bytes().as_deref(), Some(PDF_DOCUMENT));
+    }
+
+    #[test]
+    fn test_parse_mixed_single_and_multi_values() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_integer("single-1", 1));
+        data.extend(raw_keyword("multi", "a"));
+        data.extend(raw_keyword("", "b"));
+        data.extend(raw_integer("single-2", 2));
+        data.extend(raw_integer("multi-2", 3));
+        data.extend(raw_integer("", 4));
+        data.extend(raw_integer("", 5));
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attrs = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap()
+            .attributes();
+        assert_eq!(attrs.len(), 4);
+        assert_eq!(attrs["single-1"].value(), &IppValue::Integer(1));
+        assert_eq!(
+            attrs["multi"].value(),
+            &IppValue::Array(vec![
+                IppValue::Keyword("a".try_into().unwrap()),
+                IppValue::Keyword("b".try_into().unwrap())
+            ])
+        );
+        assert_eq!(attrs["single-2"].value(), &IppValue::Integer(2));
+        assert_eq!(
+            attrs["multi-2"].value(),
+            &IppValue::Array(vec![IppValue::Integer(3), IppValue::Integer(4), IppValue::Integer(5)])
+        );
+    }
+
+    #[test]
+    fn test_parse_fragmented_octet_string() {
+        let first = vec![0xa5u8; MAX_VALUE_LENGTH];
+        let second = (0..MAX_VALUE_LENGTH).map(|i| i as u8).collect::<Vec<_>>();
+        let third = b"tail".to_vec();
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_value(
+            ValueTag::OctetStringUnspecified as u8,
+            "printer-icon-data",
+            &first,
+        ));
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "", &second));
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "", &third));
+        data.extend(raw_integer("printer-up-time", 1));
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attrs = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap()
+            .attributes();
+
+        let expected = [first, second, third].concat();
+        assert_eq!(
+            attrs["printer-icon-data"].value(),
+            &IppValue::Other {
+                tag: ValueTag::OctetStringUnspecified as u8,
+                data: expected.into(),
+            }
+        );
+        assert_eq!(attrs["printer-up-time"].value(), &IppValue::Integer(1));
+    }
+
+    #[test]
+    fn test_fragmented_octet_string_round_trip() {
+        let first = vec![0xa5u8; MAX_VALUE_LENGTH];
+        let second = (0..60 * 1024).map(|i| i as u8).collect::<Vec<_>>();
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_value(
+            ValueTag::OctetStringUnspecified as u8,
+            "printer-icon-data",
+            &first,
+        ));
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "", &second));
+        data.push(3);
+
+        let icon_data = |res: &IppRequestResponse| {
+            res.attributes()
+                .groups_of(DelimiterTag::PrinterAttributes)
+                .next()
+                .unwrap()
+                .attributes()["printer-icon-data"]
+                .value()
+                .clone()
+        };
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let value = icon_data(&res);
+        assert_eq!(value.encoded_len(), value.to_bytes().len());
+        assert!(value.try_to_bytes().is_ok());
+
+        let encoded = res.to_bytes();
+        let reparsed = IppParser::new(IppReader::new(io::Cursor::new(encoded.to_vec())))
+            .parse()
+            .unwrap();
+        assert_eq!(icon_data(&reparsed), value);
+    }
+
+    #[test]
+    fn test_parse_short_octet_strings_not_joined() {
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "octets", b"one"));
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "", b"two"));
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attrs = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap()
+            .attributes();
+        assert_eq!(
+            attrs["octets"].value(),
+            &IppValue::Array(vec![
+                IppValue::OctetString("one".try_into().unwrap()),
+                IppValue::OctetString("two".try_into().unwrap())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_large_octet_string_set_not_joined() {
+        // the first value is raw data but shorter than a full fragment, so the next values are additional values
+        let first = vec![0xa5u8; 2000];
+        let second = vec![0x5au8; MAX_VALUE_LENGTH];
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "octets", &first));
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "", &second));
+        data.extend(raw_value(ValueTag::OctetStringUnspecified as u8, "", b"three"));
+        data.push(3);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attrs = res
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap()
+            .attributes();
+        let other = |data: Vec<u8>| IppValue::Other {
+            tag: ValueTag::OctetStringUnspecified as u8,
+            data: data.into(),
+        };
+        // a full fragment is continued by the next unnamed value
+        assert_eq!(
+            attrs["octets"].value(),
+            &IppValue::Array(vec![other(first), other([second, b"three".to_vec()].concat())])
+        );
+    }
+
+    fn raw_range(name: &str, min: i32, max: i32) -> Vec<u8> {
+        raw_value(
+            ValueTag::RangeOfInteger as u8,
+            name,
+            &[min.to_be_bytes(), max.to_be_bytes()].concat(),
+        )
+    }
+
+    fn raw_media_size(x: Vec<u8>, y: Vec<u8>) -> Vec<u8> {
+        [
+            raw_begin(""),
+            raw_member("x-dimension"),
+            x,
+            raw_member("y-dimension"),
+            y,
+            raw_end(),
+        ]
+        .concat()
+    }
+
+    // collection attributes as sent by CUPS, which puts media-size first and does not sort the members
+    fn cups_collection_fixtures() -> Vec<(&'static str, Vec<u8>)> {
+        let media_col_default = [
+            raw_begin("media-col-default"),
+            raw_member("media-size"),
+            raw_media_size(raw_integer("", 21000), raw_integer("", 29700)),
+            raw_member("media-bottom-margin"),
+            raw_integer("", 423),
+            raw_member("media-left-margin"),
+            raw_integer("", 423),
+            raw_member("media-right-margin"),
+            raw_integer("", 423),
+            raw_member("media-top-margin"),
+            raw_integer("", 423),
+            raw_member("media-source"),
+            raw_keyword("", "auto"),
+            raw_member("media-type"),
+            raw_keyword("", "stationery"),
+            raw_end(),
+        ]
+        .concat();
+
+        let media_col_database = [
+            raw_begin("media-col-database"),
+            raw_member("media-size"),
+            raw_media_size(raw_integer("", 21590), raw_integer("", 27940)),
+            raw_member("media-bottom-margin"),
+            raw_integer("", 0),
+            raw_member("media-top-margin"),
+            raw_integer("", 0),
+            raw_end(),
+            raw_begin(""),
+            raw_member("media-size"),
+            raw_media_size(raw_range("", 7620, 21590), raw_range("", 12700, 35560)),
+            raw_end(),
+        ]
+        .concat();
+
+        let printer_xri_supported = [
+            raw_begin("printer-xri-supported"),
+            raw_member("xri-uri"),
+            raw_value(ValueTag::Uri as u8, "", b"ipps://printer.local:631/ipp/print"),
+            raw_member("xri-authentication"),
+            raw_keyword("", "basic"),
+            raw_member("xri-security"),
+            raw_keyword("", "tls"),
+            raw_end(),
+        ]
+        .concat();
+
+        let job_constraints_supported = [
+            raw_begin("job-constraints-supported"),
+            raw_member("resolver-name"),
+            raw_value(ValueTag::NameWithoutLanguage as u8, "", b"fullbleed-sides"),
+            raw_member("sides"),
+            raw_keyword("", "two-sided-long-edge"),
+            raw_keyword("", "two-sided-short-edge"),
+            raw_member("media-bottom-margin"),
+            raw_integer("", 0),
+            raw_end(),
+        ]
+        .concat();
+
+        vec![
+            (IppAttribute::MEDIA_COL_DEFAULT, media_col_default),
+            (IppAttribute::MEDIA_COL_DATABASE, media_col_database),
+            (IppAttribute::PRINTER_XRI_SUPPORTED, printer_xri_supported),
+            ("job-constraints-supported", job_constraints_supported),
+        ]
+    }
+
+    #[test]
+    fn test_cups_collections_round_trip() {
+        let fixtures = cups_collection_fixtures();
+
+        let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04];
+        for (_, raw) in &fixtures {
+            data.extend(raw);
+        }
+        data.push(u8::from(DelimiterTag::EndOfAttributes));
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        for (name, raw) in &fixtures {
+            let attr = printer_attribute(&res, name);
+            assert_eq!(&attr.to_bytes().to_vec(), raw, "round-trip of {name}");
+            assert_eq!(attr.encoded_len(), raw.len(), "encoded length of {name}");
+        }
+    }
+
+    #[test]
+    fn test_collection_member_order() {
+        let (name, raw) = cups_collection_fixtures().remove(0);
+        let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04];
+        data.extend(raw);
+        data.push(u8::from(DelimiterTag::EndOfAttributes));
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attr = printer_attribute(&res, name);
+        let keys = attr
+            .value()
+            .as_collection()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                "media-size",
+                "media-bottom-margin",
+                "media-left-margin",
+                "media-right-margin",
+                "media-top-margin",
+                "media-source",
+                "media-type"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collection_with_array_member_round_trip() {
+        let keyword = |v: &str| IppValue::Keyword(v.try_into().unwrap());
+        let media_col = IppValue::Collection(IppCollection::from_iter([
+            ("media-key".try_into().unwrap(), keyword("iso_a4_210x297mm")),
+            (
+                "media-source-properties".try_into().unwrap(),
+                IppValue::Array(vec![keyword("tray-1"), keyword("tray-2"), keyword("manual")]),
+            ),
+            ("media-type".try_into().unwrap(), keyword("stationery")),
+        ]));
+        let attr = IppAttribute::with_name(IppAttribute::MEDIA_COL, media_col.clone()).unwrap();
+
+        let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x04];
+        data.extend(attr.to_bytes());
+        data.push(u8::from(DelimiterTag::EndOfAttributes));
+        assert_eq!(attr.encoded_len(), data.len() - 10);
+
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let parsed = printer_attribute(&res, IppAttribute::MEDIA_COL);
+        assert_eq!(parsed.value(), &media_col);
+        assert_eq!(
+            parsed
+                .value()
+                .as_collection()
+                .unwrap()
+                .keys()
+                .map(|k| k.as_str())
+                .collect::<Vec<_>>(),
+            vec!["media-key", "media-source-properties", "media-type"]
+        );
+    }
 }
@@ -3,6 +3,8 @@
 //!
 use std::io::{self, Read};
 
+use bytes::Bytes;
+
 #[cfg(feature = "async")]
 use {
     futures_util::io::{AllowStdIo, AsyncRead, AsyncReadExt},
@@ -16,6 +18,7 @@ enum PayloadKind {
     #[cfg(feature = "async")]
     Async(Box<dyn AsyncRead + Send + Sync + Unpin>),
     Sync(Box<dyn Read + Send + Sync>),
+    Bytes(io::Cursor<Bytes>),
     Empty,
 }
 
@@ -52,6 +55,55 @@ impl IppPayload {
             inner: PayloadKind::Sync(Box::new(r)),
         }
     }
+
+    /// Create a payload from in-memory data. The size of such payload is known in advance.
+    pub fn from_bytes<B>(data: B) -> Self
+    where
+        B: Into<Bytes>,
+    {
+        IppPayload {
+            inner: PayloadKind::Bytes(io::Cursor::new(data.into())),
+        }
+    }
+
+    /// Return the number of remaining payload bytes if it is known in advance.
+    /// Streaming payloads created from `Read` or `AsyncRead` objects return `None`.
+    pub fn size(&self) -> Option<u64> {
+        match self.inner {
+            #[cfg(feature = "async")]
+            PayloadKind::Async(_) => None,
+            PayloadKind::Sync(_) => None,
+            PayloadKind::Bytes(ref inner) => Some((inner.get_ref().len() as u64).saturating_sub(inner.position())),
+            PayloadKind::Empty => Some(0),
+        }
+    }
+
+    /// Return the remaining payload data if it is held in memory.
+    /// Streaming payloads created from `Read` or `AsyncRead` objects return `None`.
+    pub fn as_bytes(&self) -> Option<Bytes> {
+        match self.inner {
+            #[cfg(feature = "async")]
+            PayloadKind::Async(_) => None,
+            PayloadKind::Sync(_) => None,
+            PayloadKind::Bytes(ref inner) => {
+                let position = (inner.position() as usize).min(inner.get_ref().len());
+                Some(inner.get_ref().slice(position..))
+            }
+            PayloadKind::Empty => Some(Bytes::new()),
+        }
+    }
+}
+
+impl From<Bytes> for IppPayload {
+    fn from(data: Bytes) -> Self {
+        IppPayload::from_bytes(data)
+    }
+}
+
+impl From<Vec<u8>> for IppPayload {
+    fn from(data: Vec<u8>) -> Self {
+        IppPayload::from_bytes(data)
+    }
 }
 
 impl Default for IppPayload {
@@ -68,6 +120,7 @@ impl AsyncRead for IppPayload {
         match self.inner {
             PayloadKind::Async(ref mut inner) => Pin::new(&mut *inner).poll_read(cx, buf),
             PayloadKind::Sync(ref mut inner) => Pin::new(&mut AllowStdIo::new(inner)).poll_read(cx, buf),
+            PayloadKind::Bytes(ref mut inner) => Poll::Ready(inner.read(buf)),
             PayloadKind::Empty => Poll::Ready(Ok(0)),
         }
     }
@@ -79,6 +132,7 @@ impl Read for IppPayload {
             #[cfg(feature = "async")]
             PayloadKind::Async(ref mut inner) => futures_executor::block_on(inner.read(buf)),
             PayloadKind::Sync(ref mut inner) => inner.read(buf),
+            PayloadKind::Bytes(ref mut inner) => inner.read(buf),
             PayloadKind::Empty => Ok(0),
         }
     }
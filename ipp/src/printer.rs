@@ -0,0 +1,1219 @@
+//!
+//! High-level view of the printer attributes
+//!
+use std::fmt;
+
+use http::Uri;
+use log::warn;
+
+#[cfg(feature = "ipp-set-extensions")]
+use crate::collection::JobConstraint;
+use crate::{
+    FromPrimitive as _,
+    attribute::{IppAttribute, IppAttributes, enum_or_int},
+    collection::MediaCol,
+    job::{JobOptions, NumberUp},
+    model::{
+        DelimiterTag, IppVersion, Operation, PrintColorMode, PrintQuality, PrinterState, Sides, UriAuthentication,
+        UriSecurity,
+    },
+    value::IppValue,
+};
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Summary of the printer capabilities decoded from the Get-Printer-Attributes response.
+/// Values which are missing or cannot be decoded are left empty.
+#[derive(Debug, Clone, Default)]
+pub struct PrinterCapabilities {
+    /// printer-name
+    pub printer_name: Option<String>,
+    /// printer-make-and-model
+    pub make_and_model: Option<String>,
+    /// printer-state
+    pub printer_state: Option<PrinterState>,
+    /// printer-is-accepting-jobs
+    pub accepting_jobs: bool,
+    /// printer-uri-supported
+    pub uri_supported: Vec<Uri>,
+    /// ipp-versions-supported
+    pub ipp_versions_supported: Vec<IppVersion>,
+    /// operations-supported
+    pub operations_supported: Vec<Operation>,
+    /// operations-supported codes which do not map to a known [`Operation`], e.g. vendor extensions
+    pub unknown_operations: Vec<i32>,
+    /// charset-supported
+    pub charset_supported: Vec<String>,
+    /// document-format-supported
+    pub document_formats: Vec<String>,
+    /// document-format-default
+    pub document_format_default: Option<String>,
+    /// color-supported
+    pub color_supported: bool,
+    /// sides-supported
+    pub sides_supported: Vec<Sides>,
+    /// media-supported
+    pub media_supported: Vec<String>,
+    /// media-ready
+    pub media_ready: Vec<String>,
+    /// media-col-ready
+    pub media_col_ready: Vec<MediaCol>,
+    /// media-default
+    pub media_default: Option<String>,
+    /// printer-icons, ordered from the smallest to the largest icon
+    pub icons: Vec<Uri>,
+    /// copies-supported, the range of supported copies
+    pub copies_supported: Option<(i32, i32)>,
+    /// number-up-supported, an integer, a range or a list of integers
+    pub number_up_supported: Option<IppValue>,
+    /// print-color-mode-supported
+    pub color_modes_supported: Vec<PrintColorMode>,
+    /// print-quality-supported
+    pub print_quality_supported: Vec<PrintQuality>,
+    /// job-constraints-supported
+    #[cfg(feature = "ipp-set-extensions")]
+    pub job_constraints: Vec<JobConstraint>,
+}
+
+/// Conflict between the requested job options and the printer capabilities, see [`PrinterCapabilities::validate_job`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The value is not among the supported values of the printer
+    Unsupported { name: String, value: String },
+    /// The combination of values matches one of the printer job constraints
+    #[cfg(feature = "ipp-set-extensions")]
+    Constraint { resolver_name: String },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conflict::Unsupported { name, value } => write!(f, "unsupported value for {name}: {value}"),
+            #[cfg(feature = "ipp-set-extensions")]
+            Conflict::Constraint { resolver_name } => write!(f, "conflicting job attributes: {resolver_name}"),
+        }
+    }
+}
+
+impl PrinterCapabilities {
+    /// Decode printer capabilities from the printer attributes group of the response
+    pub fn from_attributes(attributes: &IppAttributes) -> PrinterCapabilities {
+        let Some(group) = attributes.groups_of(DelimiterTag::PrinterAttributes).next() else {
+            return PrinterCapabilities::default();
+        };
+
+        let (operations_supported, unknown_operations) = group
+            .values(IppAttribute::OPERATIONS_SUPPORTED)
+            .filter_map(|v| v.as_enum())
+            .fold((Vec::new(), Vec::new()), |(mut known, mut unknown), code| {
+                match Operation::from_i32(*code) {
+                    Some(operation) => known.push(operation),
+                    None => unknown.push(*code),
+                }
+                (known, unknown)
+            });
+
+        PrinterCapabilities {
+            printer_name: group.first_value(IppAttribute::PRINTER_NAME).map(|v| v.to_string()),
+            make_and_model: group
+                .first_value(IppAttribute::PRINTER_MAKE_AND_MODEL)
+                .map(|v| v.to_string()),
+            printer_state: group
+                .first_value(IppAttribute::PRINTER_STATE)
+                .and_then(|v| v.as_enum())
+                .and_then(|v| PrinterState::from_i32(*v)),
+            accepting_jobs: group.boolean(IppAttribute::PRINTER_IS_ACCEPTING_JOBS),
+            uri_supported: group
+                .values(IppAttribute::PRINTER_URI_SUPPORTED)
+                .filter_map(|v| v.as_http_uri().ok())
+                .collect(),
+            ipp_versions_supported: group
+                .values(IppAttribute::IPP_VERSIONS_SUPPORTED)
+                .filter_map(|v| IppVersion::from_keyword(&v.to_string()))
+                .collect(),
+            operations_supported,
+            unknown_operations,
+            charset_supported: group.strings(IppAttribute::CHARSET_SUPPORTED),
+            document_formats: group.strings(IppAttribute::DOCUMENT_FORMAT_SUPPORTED),
+            document_format_default: group
+                .first_value(IppAttribute::DOCUMENT_FORMAT_DEFAULT)
+                .map(|v| v.to_string()),
+            color_supported: group.boolean(IppAttribute::COLOR_SUPPORTED),
+            sides_supported: group
+                .values(IppAttribute::SIDES_SUPPORTED)
+                .filter_map(|v| Sides::from_keyword(&v.to_string()))
+                .collect(),
+            media_supported: group.strings(IppAttribute::MEDIA_SUPPORTED),
+            media_ready: group.strings(IppAttribute::MEDIA_READY),
+            media_col_ready: group
+                .attributes()
+                .get(IppAttribute::MEDIA_COL_READY)
+                .map(|a| MediaCol::parse_all(a.value()))
+                .unwrap_or_default(),
+            media_default: group.first_value(IppAttribute::MEDIA_DEFAULT).map(|v| v.to_string()),
+            icons: group
+                .values(IppAttribute::PRINTER_ICONS)
+                .filter_map(|v| v.as_http_uri().ok())
+                .collect(),
+            copies_supported: group
+                .first_value(IppAttribute::COPIES_SUPPORTED)
+                .and_then(|v| v.as_range_of_integer())
+                .map(|(min, max)| (*min, *max)),
+            number_up_supported: group
+                .attributes()
+                .get(IppAttribute::NUMBER_UP_SUPPORTED)
+                .map(|a| a.value().clone()),
+            color_modes_supported: group
+                .values(IppAttribute::PRINT_COLOR_MODE_SUPPORTED)
+                .filter_map(|v| PrintColorMode::from_keyword(&v.to_string()))
+                .collect(),
+            print_quality_supported: group
+                .values(IppAttribute::PRINT_QUALITY_SUPPORTED)
+                .filter_map(enum_or_int)
+                .filter_map(PrintQuality::from_i32)
+                .collect(),
+            #[cfg(feature = "ipp-set-extensions")]
+            job_constraints: group
+                .attributes()
+                .get(IppAttribute::JOB_CONSTRAINTS_SUPPORTED)
+                .map(|a| JobConstraint::parse_supported(a.value()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Check the job options against the supported values and job constraints of the printer before submitting
+    /// the job. Options for which the printer does not report the supported values are not checked.
+    pub fn validate_job(&self, options: &JobOptions) -> Result<(), Vec<Conflict>> {
+        fn check<T: PartialEq>(supported: &[T], value: &T) -> bool {
+            supported.is_empty() || supported.contains(value)
+        }
+
+        let mut conflicts = Vec::new();
+        let mut unsupported = |name: &str, value: String| {
+            conflicts.push(Conflict::Unsupported {
+                name: name.to_owned(),
+                value,
+            })
+        };
+
+        if let Some(copies) = options.copies
+            && (copies < 1
+                || self
+                    .copies_supported
+                    .is_some_and(|(min, max)| copies < min || copies > max))
+        {
+            unsupported(IppAttribute::COPIES, copies.to_string());
+        }
+        if let Some(sides) = options.sides
+            && !check(&self.sides_supported, &sides)
+        {
+            unsupported(IppAttribute::SIDES, sides.to_string());
+        }
+        if let Some(media) = &options.media
+            && !check(&self.media_supported, media)
+        {
+            unsupported(IppAttribute::MEDIA, media.clone());
+        }
+        if let Some(color_mode) = options.color_mode
+            && !check(&self.color_modes_supported, &color_mode)
+        {
+            unsupported(IppAttribute::PRINT_COLOR_MODE, color_mode.to_string());
+        }
+        if let Some(number_up) = options.number_up
+            && NumberUp::new(number_up, self.number_up_supported.as_ref()).is_err()
+        {
+            unsupported(IppAttribute::NUMBER_UP, number_up.to_string());
+        }
+        if let Some(print_quality) = options.print_quality
+            && !check(&self.print_quality_supported, &print_quality)
+        {
+            unsupported(IppAttribute::PRINT_QUALITY, (print_quality as i32).to_string());
+        }
+
+        #[cfg(feature = "ipp-set-extensions")]
+        if let Ok(job) = options.clone().build() {
+            conflicts.extend(
+                self.job_constraints
+                    .iter()
+                    .filter(|constraint| constraint.violated_by(&job))
+                    .map(|constraint| Conflict::Constraint {
+                        resolver_name: constraint.resolver_name().to_owned(),
+                    }),
+            );
+        }
+
+        if conflicts.is_empty() { Ok(()) } else { Err(conflicts) }
+    }
+
+    /// Return the loaded media. The `media-col-ready` collections are preferred as they also describe the source
+    /// and type of the media; if the printer only reports `media-ready`, the keywords are converted instead.
+    pub fn media_ready(&self) -> Vec<MediaCol> {
+        if !self.media_col_ready.is_empty() {
+            return self.media_col_ready.clone();
+        }
+        self.media_ready.iter().map(|m| MediaCol::from_keyword(m)).collect()
+    }
+
+    /// Check if the printer supports a given operation
+    pub fn supports_operation(&self, operation: Operation) -> bool {
+        self.operations_supported.contains(&operation)
+    }
+
+    /// Check if the printer accepts `utf-8`, the charset used by this crate for all requests.
+    /// Printers must support it, so an empty `charset-supported` counts as supported. Logs a warning if the
+    /// printer only supports `us-ascii`, which rejects requests with non-ASCII values such as job names.
+    pub fn supports_utf8(&self) -> bool {
+        let supports = |charset: &str| self.charset_supported.iter().any(|c| c.eq_ignore_ascii_case(charset));
+        if self.charset_supported.is_empty() || supports("utf-8") {
+            return true;
+        }
+        if supports("us-ascii") {
+            warn!("Printer only supports us-ascii, requests with non-ASCII values will be rejected");
+        }
+        false
+    }
+
+    /// Check if the printer supports a given document format
+    pub fn supports_document_format(&self, document_format: &str) -> bool {
+        self.document_formats.iter().any(|f| f == document_format)
+    }
+
+    /// Pick the first of the preferred document formats which the printer supports.
+    /// Falls back to `application/octet-stream` (auto-sensing) if the printer supports it.
+    pub fn best_document_format(&self, preferred: &[&str]) -> Option<String> {
+        preferred
+            .iter()
+            .copied()
+            .find(|f| self.supports_document_format(f))
+            .or_else(|| self.supports_document_format(OCTET_STREAM).then_some(OCTET_STREAM))
+            .map(ToOwned::to_owned)
+    }
+
+    /// Return the URI of the largest printer icon.
+    /// Icons are listed from the smallest to the largest (48x48, 128x128 and optionally 512x512 pixels).
+    pub fn largest_icon(&self) -> Option<&Uri> {
+        self.icons.last()
+    }
+}
+
+/// IEEE 1284 device ID as reported by the `printer-device-id` attribute,
+/// e.g. `MFG:HP;MDL:LaserJet 400;CMD:PCL,PDF;`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceId {
+    fields: KeyValueFields,
+}
+
+impl DeviceId {
+    /// Parse a device ID string. Segments without a key are ignored, so this never fails.
+    pub fn parse(device_id: &str) -> DeviceId {
+        DeviceId {
+            fields: KeyValueFields::parse(device_id, ':'),
+        }
+    }
+
+    /// Return the value of a given key, keys are case-insensitive
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key)
+    }
+
+    fn get_any(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter().find_map(|key| self.get(key))
+    }
+
+    /// Manufacturer (MFG or MANUFACTURER)
+    pub fn mfg(&self) -> Option<&str> {
+        self.get_any(&["MFG", "MANUFACTURER"])
+    }
+
+    /// Model (MDL or MODEL)
+    pub fn model(&self) -> Option<&str> {
+        self.get_any(&["MDL", "MODEL"])
+    }
+
+    /// Supported command sets (CMD or COMMAND SET), e.g. PCL, PDF, URF
+    pub fn cmd(&self) -> Vec<&str> {
+        self.get_any(&["CMD", "COMMAND SET"])
+            .map(|cmd| cmd.split(',').map(str::trim).filter(|c| !c.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Device class (CLS or CLASS)
+    pub fn class(&self) -> Option<&str> {
+        self.get_any(&["CLS", "CLASS"])
+    }
+
+    /// Description (DES or DESCRIPTION)
+    pub fn description(&self) -> Option<&str> {
+        self.get_any(&["DES", "DESCRIPTION"])
+    }
+
+    /// Serial number (SN, SERN or SERIALNUMBER)
+    pub fn serial_number(&self) -> Option<&str> {
+        self.get_any(&["SN", "SERN", "SERIALNUMBER"])
+    }
+
+    /// Iterate over all key-value pairs in the original order
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter()
+    }
+}
+
+/// Entry of `printer-uri-supported` with the security and authentication mechanisms
+/// from the `uri-security-supported` and `uri-authentication-supported` values at the same position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrinterUri {
+    /// printer-uri-supported
+    pub uri: Uri,
+    /// uri-security-supported, `None` if missing or not a known keyword
+    pub security: Option<UriSecurity>,
+    /// uri-authentication-supported, `None` if missing or not a known keyword
+    pub authentication: Option<UriAuthentication>,
+}
+
+// Fields of a structured string such as `key=value;key=value;` with an optional human-readable description.
+// Segments without a key are ignored and keys are case-insensitive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct KeyValueFields {
+    fields: Vec<(String, String)>,
+    description: Option<String>,
+}
+
+impl KeyValueFields {
+    fn parse(s: &str, separator: char) -> Self {
+        let fields = s
+            .split(';')
+            .filter_map(|segment| segment.split_once(separator))
+            .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+        KeyValueFields {
+            fields,
+            description: None,
+        }
+    }
+
+    fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn get_integer(&self, key: &str) -> Option<i32> {
+        self.get(key).and_then(|v| v.parse().ok())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Printer alert as reported by the `printer-alert` attribute (PWG 5100.9),
+/// e.g. `code=mediaJam;index=1;severity=critical;group=mediaPath;groupindex=1;`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrinterAlert {
+    fields: KeyValueFields,
+}
+
+impl PrinterAlert {
+    /// Parse an alert string. Segments without a key are ignored, so this never fails.
+    pub fn parse(alert: &str) -> PrinterAlert {
+        PrinterAlert {
+            fields: KeyValueFields::parse(alert, '='),
+        }
+    }
+
+    /// Set the human-readable description from the matching `printer-alert-description` value
+    pub fn with_description<S>(self, description: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        PrinterAlert {
+            fields: self.fields.with_description(description.as_ref()),
+        }
+    }
+
+    /// Return the value of a given key, keys are case-insensitive
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key)
+    }
+
+    /// Alert code, e.g. `mediaJam`, `doorOpen`, `markerSupplyEmpty`
+    pub fn code(&self) -> Option<&str> {
+        self.get("code")
+    }
+
+    /// Alert index
+    pub fn index(&self) -> Option<i32> {
+        self.fields.get_integer("index")
+    }
+
+    /// Alert severity: `critical`, `warning`, `warningBinaryChangeEvent` or `other`
+    pub fn severity(&self) -> Option<&str> {
+        self.get("severity")
+    }
+
+    /// Training level required to fix the alert, e.g. `untrained`, `trained`, `fieldService`
+    pub fn training(&self) -> Option<&str> {
+        self.get("training")
+    }
+
+    /// Printer subunit group, e.g. `mediaPath`, `input`, `marker`
+    pub fn group(&self) -> Option<&str> {
+        self.get("group")
+    }
+
+    /// Index of the subunit within the group
+    pub fn group_index(&self) -> Option<i32> {
+        self.fields.get_integer("groupindex")
+    }
+
+    /// Location of the alert within the subunit
+    pub fn location(&self) -> Option<i32> {
+        self.fields.get_integer("location")
+    }
+
+    /// Time of the alert in seconds since the printer was started (printer-up-time)
+    pub fn time(&self) -> Option<i32> {
+        self.fields.get_integer("time")
+    }
+
+    /// Human-readable description from `printer-alert-description`
+    pub fn description(&self) -> Option<&str> {
+        self.fields.description.as_deref()
+    }
+
+    /// Check if this is a critical alert
+    pub fn is_critical(&self) -> bool {
+        self.severity().is_some_and(|s| s.eq_ignore_ascii_case("critical"))
+    }
+
+    /// Iterate over all key-value pairs in the original order
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter()
+    }
+}
+
+/// Printer supply as reported by the `printer-supply` attribute (PWG 5100.13),
+/// e.g. `index=1;class=supplyThatIsConsumed;type=toner;unit=percent;maxcapacity=100;level=42;colorantname=black;`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrinterSupply {
+    fields: KeyValueFields,
+}
+
+impl PrinterSupply {
+    /// Parse a supply string. Segments without a key are ignored, so this never fails.
+    pub fn parse(supply: &str) -> PrinterSupply {
+        PrinterSupply {
+            fields: KeyValueFields::parse(supply, '='),
+        }
+    }
+
+    /// Set the human-readable description from the matching `printer-supply-description` value
+    pub fn with_description<S>(self, description: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        PrinterSupply {
+            fields: self.fields.with_description(description.as_ref()),
+        }
+    }
+
+    /// Return the value of a given key, keys are case-insensitive
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.get(key)
+    }
+
+    /// Supply index
+    pub fn index(&self) -> Option<i32> {
+        self.fields.get_integer("index")
+    }
+
+    /// Supply type, e.g. `toner`, `ink`, `wasteToner`
+    pub fn supply_type(&self) -> Option<&str> {
+        self.get("type")
+    }
+
+    /// Supply class: `supplyThatIsConsumed` or `receptacleThatIsFilled`
+    pub fn class(&self) -> Option<&str> {
+        self.get("class")
+    }
+
+    /// Colorant name, e.g. `black`, `cyan`
+    pub fn color(&self) -> Option<&str> {
+        self.get("colorantname")
+    }
+
+    /// Maximum capacity in supply units, negative values have special meanings as in the Printer MIB
+    pub fn max_capacity(&self) -> Option<i32> {
+        self.fields.get_integer("maxcapacity")
+    }
+
+    /// Current level in supply units, negative values have special meanings as in the Printer MIB,
+    /// e.g. -2 for unknown and -3 for "some remaining"
+    pub fn level(&self) -> Option<i32> {
+        self.fields.get_integer("level")
+    }
+
+    /// Current level in percent of the maximum capacity, or `None` if the level or capacity is unknown
+    pub fn level_percent(&self) -> Option<u8> {
+        let level = self.level().filter(|l| *l >= 0)?;
+        let max = self.max_capacity().filter(|m| *m > 0)?;
+        Some((i64::from(level.min(max)) * 100 / i64::from(max)) as u8)
+    }
+
+    /// Human-readable description from `printer-supply-description`
+    pub fn description(&self) -> Option<&str> {
+        self.fields.description.as_deref()
+    }
+
+    /// Iterate over all key-value pairs in the original order
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{
+        attribute::IppAttributeGroup,
+        collection::MediaSize,
+        model::{IppVersion, StatusCode},
+        parser::IppParser,
+        reader::IppReader,
+        request::IppRequestResponse,
+        value::IppValue,
+    };
+
+    use super::*;
+
+    fn keywords(values: &[&str]) -> IppValue {
+        IppValue::Array(
+            values
+                .iter()
+                .map(|v| IppValue::Keyword((*v).try_into().unwrap()))
+                .collect(),
+        )
+    }
+
+    // Printer attributes group with the given attributes
+    fn printer_group<'a>(attrs: impl IntoIterator<Item = (&'a str, IppValue)>) -> IppAttributeGroup {
+        let mut group = IppAttributeGroup::new(DelimiterTag::PrinterAttributes);
+        for (name, value) in attrs {
+            group.insert(IppAttribute::with_name(name, value).unwrap());
+        }
+        group
+    }
+
+    // Capabilities decoded from attributes with a single printer attributes group
+    fn capabilities_of(group: IppAttributeGroup) -> PrinterCapabilities {
+        let mut attributes = IppAttributes::new();
+        attributes.groups_mut().push(group);
+        PrinterCapabilities::from_attributes(&attributes)
+    }
+
+    // Get-Printer-Attributes response of a typical IPP Everywhere printer
+    fn ipp_everywhere_response() -> IppRequestResponse {
+        let mut resp =
+            IppRequestResponse::new_response(IppVersion::v2_0(), StatusCode::SuccessfulOk, 1, IppAttributes::new())
+                .unwrap();
+
+        let printer_attributes = [
+            (
+                IppAttribute::PRINTER_NAME,
+                IppValue::NameWithoutLanguage("Office".try_into().unwrap()),
+            ),
+            (
+                IppAttribute::PRINTER_MAKE_AND_MODEL,
+                IppValue::TextWithoutLanguage("Example LaserJet 400".try_into().unwrap()),
+            ),
+            (IppAttribute::PRINTER_STATE, IppValue::Enum(PrinterState::Idle as i32)),
+            (IppAttribute::PRINTER_IS_ACCEPTING_JOBS, IppValue::Boolean(true)),
+            (
+                IppAttribute::PRINTER_URI_SUPPORTED,
+                IppValue::Array(vec![
+                    IppValue::Uri("ipp://printer.local:631/ipp/print".try_into().unwrap()),
+                    IppValue::Uri("ipps://printer.local:631/ipp/print".try_into().unwrap()),
+                ]),
+            ),
+            (IppAttribute::IPP_VERSIONS_SUPPORTED, keywords(&["1.1", "2.0"])),
+            (
+                IppAttribute::OPERATIONS_SUPPORTED,
+                IppValue::Array(
+                    [
+                        Operation::PrintJob,
+                        Operation::ValidateJob,
+                        Operation::CreateJob,
+                        Operation::SendDocument,
+                        Operation::CancelJob,
+                        Operation::GetJobAttributes,
+                        Operation::GetJobs,
+                        Operation::GetPrinterAttributes,
+                        Operation::CupsGetPrinters,
+                    ]
+                    .iter()
+                    .map(|op| IppValue::Enum(*op as i32))
+                    // vendor operation which is unknown to this crate
+                    .chain([IppValue::Enum(0x7001)])
+                    .collect(),
+                ),
+            ),
+            (
+                IppAttribute::DOCUMENT_FORMAT_SUPPORTED,
+                IppValue::Array(
+                    [
+                        "application/octet-stream",
+                        "image/pwg-raster",
+                        "image/urf",
+                        "application/pdf",
+                    ]
+                    .iter()
+                    .map(|v| IppValue::MimeMediaType((*v).try_into().unwrap()))
+                    .collect(),
+                ),
+            ),
+            (
+                IppAttribute::DOCUMENT_FORMAT_DEFAULT,
+                IppValue::MimeMediaType("application/octet-stream".try_into().unwrap()),
+            ),
+            (IppAttribute::COLOR_SUPPORTED, IppValue::Boolean(false)),
+            (
+                IppAttribute::SIDES_SUPPORTED,
+                keywords(&["one-sided", "two-sided-long-edge", "two-sided-short-edge"]),
+            ),
+            (
+                IppAttribute::MEDIA_SUPPORTED,
+                keywords(&["iso_a4_210x297mm", "na_letter_8.5x11in", "na_legal_8.5x14in"]),
+            ),
+            (
+                IppAttribute::MEDIA_READY,
+                IppValue::Keyword("iso_a4_210x297mm".try_into().unwrap()),
+            ),
+            (
+                IppAttribute::MEDIA_DEFAULT,
+                IppValue::Keyword("iso_a4_210x297mm".try_into().unwrap()),
+            ),
+            (
+                IppAttribute::PRINTER_ICONS,
+                IppValue::Array(vec![
+                    IppValue::Uri("https://printer.local/icon-small.png".try_into().unwrap()),
+                    IppValue::Uri("https://printer.local/icon-large.png".try_into().unwrap()),
+                ]),
+            ),
+        ];
+        for (name, value) in printer_attributes {
+            resp.attributes_mut().add(
+                DelimiterTag::PrinterAttributes,
+                IppAttribute::with_name(name, value).unwrap(),
+            );
+        }
+
+        IppParser::new(IppReader::new(io::Cursor::new(resp.to_bytes().to_vec())))
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_printer_capabilities() {
+        let caps = PrinterCapabilities::from_attributes(ipp_everywhere_response().attributes());
+
+        assert_eq!(caps.printer_name.as_deref(), Some("Office"));
+        assert_eq!(caps.make_and_model.as_deref(), Some("Example LaserJet 400"));
+        assert_eq!(caps.printer_state, Some(PrinterState::Idle));
+        assert!(caps.accepting_jobs);
+        assert_eq!(
+            caps.uri_supported,
+            vec![
+                "ipp://printer.local:631/ipp/print".parse::<Uri>().unwrap(),
+                "ipps://printer.local:631/ipp/print".parse::<Uri>().unwrap()
+            ]
+        );
+        assert_eq!(
+            caps.ipp_versions_supported,
+            vec![IppVersion::v1_1(), IppVersion::v2_0()]
+        );
+        assert_eq!(caps.operations_supported.len(), 9);
+        assert!(caps.supports_operation(Operation::CreateJob));
+        assert!(!caps.supports_operation(Operation::PrintUri));
+        assert_eq!(caps.document_formats.len(), 4);
+        assert!(caps.supports_document_format("image/pwg-raster"));
+        assert_eq!(
+            caps.document_format_default.as_deref(),
+            Some("application/octet-stream")
+        );
+        assert!(!caps.color_supported);
+        assert_eq!(
+            caps.sides_supported,
+            vec![Sides::OneSided, Sides::TwoSidedLongEdge, Sides::TwoSidedShortEdge]
+        );
+        assert_eq!(caps.media_supported.len(), 3);
+        assert_eq!(caps.media_ready, vec!["iso_a4_210x297mm"]);
+        assert_eq!(caps.media_default.as_deref(), Some("iso_a4_210x297mm"));
+        assert_eq!(
+            caps.icons,
+            vec![
+                "https://printer.local/icon-small.png".parse::<Uri>().unwrap(),
+                "https://printer.local/icon-large.png".parse::<Uri>().unwrap()
+            ]
+        );
+        assert_eq!(
+            caps.largest_icon().map(|u| u.to_string()).as_deref(),
+            Some("https://printer.local/icon-large.png")
+        );
+    }
+
+    #[test]
+    fn test_operations_supported() {
+        let codes = [
+            0x0002, 0x0004, 0x0005, 0x0006, 0x0008, 0x0009, 0x000a, 0x000b, 0x0039, 0x003b, 0x4002,
+        ];
+        let caps = capabilities_of(printer_group([(
+            IppAttribute::OPERATIONS_SUPPORTED,
+            IppValue::Array(codes.iter().copied().map(IppValue::Enum).collect()),
+        )]));
+        assert_eq!(
+            caps.operations_supported,
+            vec![
+                Operation::PrintJob,
+                Operation::ValidateJob,
+                Operation::CreateJob,
+                Operation::SendDocument,
+                Operation::CancelJob,
+                Operation::GetJobAttributes,
+                Operation::GetJobs,
+                Operation::GetPrinterAttributes,
+                Operation::CupsGetPrinters,
+            ]
+        );
+        assert_eq!(caps.unknown_operations, vec![0x0039, 0x003b]);
+        assert!(caps.supports_operation(Operation::CreateJob));
+    }
+
+    #[test]
+    fn test_charset_supported() {
+        let caps = |charsets: &[&str]| {
+            capabilities_of(printer_group([(
+                IppAttribute::CHARSET_SUPPORTED,
+                IppValue::Array(
+                    charsets
+                        .iter()
+                        .map(|c| IppValue::Charset((*c).try_into().unwrap()))
+                        .collect(),
+                ),
+            )]))
+        };
+
+        let utf8 = caps(&["us-ascii", "utf-8"]);
+        assert_eq!(utf8.charset_supported, vec!["us-ascii", "utf-8"]);
+        assert!(utf8.supports_utf8());
+
+        let ascii = caps(&["us-ascii"]);
+        assert_eq!(ascii.charset_supported, vec!["us-ascii"]);
+        assert!(!ascii.supports_utf8());
+
+        assert!(PrinterCapabilities::default().supports_utf8());
+    }
+
+    #[test]
+    fn test_best_document_format() {
+        let mut caps = PrinterCapabilities {
+            document_formats: vec!["application/pdf".to_owned(), "image/pwg-raster".to_owned()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            caps.best_document_format(&["application/pdf", "image/pwg-raster"])
+                .as_deref(),
+            Some("application/pdf")
+        );
+        assert_eq!(
+            caps.best_document_format(&["image/urf", "image/pwg-raster", "application/pdf"])
+                .as_deref(),
+            Some("image/pwg-raster")
+        );
+        assert_eq!(caps.best_document_format(&["image/jpeg"]), None);
+        assert_eq!(caps.best_document_format(&[]), None);
+
+        caps.document_formats.push("application/octet-stream".to_owned());
+        assert_eq!(
+            caps.best_document_format(&["image/jpeg"]).as_deref(),
+            Some("application/octet-stream")
+        );
+        assert_eq!(
+            caps.best_document_format(&["image/jpeg", "image/pwg-raster"])
+                .as_deref(),
+            Some("image/pwg-raster")
+        );
+    }
+
+    fn validation_capabilities() -> PrinterCapabilities {
+        let attrs = vec![
+            (
+                IppAttribute::COPIES_SUPPORTED,
+                IppValue::RangeOfInteger { min: 1, max: 99 },
+            ),
+            (
+                IppAttribute::SIDES_SUPPORTED,
+                keywords(&["one-sided", "two-sided-long-edge"]),
+            ),
+            (
+                IppAttribute::MEDIA_SUPPORTED,
+                keywords(&[
+                    "iso_a4_210x297mm",
+                    "na_letter_8.5x11in",
+                    "na_letter_8.5x11in_transparency",
+                ]),
+            ),
+            (
+                IppAttribute::PRINT_COLOR_MODE_SUPPORTED,
+                keywords(&["auto", "monochrome", "color"]),
+            ),
+            (
+                IppAttribute::NUMBER_UP_SUPPORTED,
+                IppValue::Array(vec![IppValue::Integer(1), IppValue::Integer(2), IppValue::Integer(4)]),
+            ),
+            (
+                IppAttribute::PRINT_QUALITY_SUPPORTED,
+                IppValue::Array(vec![IppValue::Enum(4), IppValue::Enum(5)]),
+            ),
+        ];
+        #[cfg(feature = "ipp-set-extensions")]
+        let attrs = {
+            let mut attrs = attrs;
+            let mut constraint = crate::value::IppCollection::new();
+            constraint.insert(
+                "resolver-name".try_into().unwrap(),
+                IppValue::NameWithoutLanguage("color-transparency".try_into().unwrap()),
+            );
+            constraint.insert(
+                "media".try_into().unwrap(),
+                IppValue::Keyword("na_letter_8.5x11in_transparency".try_into().unwrap()),
+            );
+            constraint.insert("print-color-mode".try_into().unwrap(), keywords(&["auto", "color"]));
+            attrs.push((
+                IppAttribute::JOB_CONSTRAINTS_SUPPORTED,
+                IppValue::Collection(constraint),
+            ));
+            attrs
+        };
+
+        capabilities_of(printer_group(attrs))
+    }
+
+    #[test]
+    fn test_validate_job_supported() {
+        let caps = validation_capabilities();
+        assert_eq!(caps.copies_supported, Some((1, 99)));
+        assert_eq!(
+            caps.print_quality_supported,
+            vec![PrintQuality::Normal, PrintQuality::High]
+        );
+
+        let options = JobOptions::new()
+            .copies(2)
+            .sides(Sides::TwoSidedLongEdge)
+            .media("iso_a4_210x297mm")
+            .color_mode(PrintColorMode::Color)
+            .number_up(2)
+            .print_quality(PrintQuality::High);
+        assert_eq!(caps.validate_job(&options), Ok(()));
+
+        let options = JobOptions::new()
+            .copies(100)
+            .sides(Sides::TwoSidedShortEdge)
+            .media("iso_a3_297x420mm")
+            .number_up(3)
+            .print_quality(PrintQuality::Draft);
+        let conflicts = caps.validate_job(&options).unwrap_err();
+        assert_eq!(
+            conflicts.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+            vec![
+                "unsupported value for copies: 100",
+                "unsupported value for sides: two-sided-short-edge",
+                "unsupported value for media: iso_a3_297x420mm",
+                "unsupported value for number-up: 3",
+                "unsupported value for print-quality: 3",
+            ]
+        );
+
+        // nothing is checked when the printer does not report the supported values
+        let options = JobOptions::new().copies(1000).media("custom_100x100mm");
+        assert_eq!(PrinterCapabilities::default().validate_job(&options), Ok(()));
+    }
+
+    #[cfg(feature = "ipp-set-extensions")]
+    #[test]
+    fn test_validate_job_constraints() {
+        let caps = validation_capabilities();
+        assert_eq!(caps.job_constraints.len(), 1);
+
+        let options = JobOptions::new()
+            .media("na_letter_8.5x11in_transparency")
+            .color_mode(PrintColorMode::Monochrome);
+        assert_eq!(caps.validate_job(&options), Ok(()));
+
+        let options = JobOptions::new()
+            .media("na_letter_8.5x11in_transparency")
+            .color_mode(PrintColorMode::Color);
+        assert_eq!(
+            caps.validate_job(&options),
+            Err(vec![Conflict::Constraint {
+                resolver_name: "color-transparency".to_owned()
+            }])
+        );
+    }
+
+    #[test]
+    fn test_media_ready() {
+        // keywords only
+        let caps = PrinterCapabilities::from_attributes(ipp_everywhere_response().attributes());
+        assert!(caps.media_col_ready.is_empty());
+        assert_eq!(caps.media_ready(), vec![MediaCol::from_keyword("iso_a4_210x297mm")]);
+
+        // both keywords and collections
+        let photo = MediaCol {
+            media_size: Some(MediaSize::new(10160, 15240)),
+            media_source: Some("photo".to_owned()),
+            media_type: Some("photographic-glossy".to_owned()),
+            ..Default::default()
+        };
+        let caps = capabilities_of(printer_group([
+            (
+                IppAttribute::MEDIA_READY,
+                keywords(&["iso_a4_210x297mm", "na_index-4x6_4x6in"]),
+            ),
+            (
+                IppAttribute::MEDIA_COL_READY,
+                IppValue::Array(vec![
+                    MediaCol {
+                        media_source: Some("main".to_owned()),
+                        ..MediaCol::from_keyword("iso_a4_210x297mm")
+                    }
+                    .build()
+                    .unwrap(),
+                    photo.clone().build().unwrap(),
+                ]),
+            ),
+        ]));
+        assert_eq!(caps.media_ready.len(), 2);
+        let media = caps.media_ready();
+        assert_eq!(media.len(), 2);
+        assert_eq!(media[0].media_size_name.as_deref(), Some("iso_a4_210x297mm"));
+        assert_eq!(media[0].media_source.as_deref(), Some("main"));
+        assert_eq!(media[1], photo);
+    }
+
+    #[test]
+    fn test_printer_capabilities_empty() {
+        let caps = PrinterCapabilities::from_attributes(&IppAttributes::new());
+        assert!(caps.printer_name.is_none());
+        assert!(caps.document_formats.is_empty());
+        assert!(!caps.accepting_jobs);
+    }
+
+    #[test]
+    fn test_device_id() {
+        let id = DeviceId::parse(
+            "MFG:HP;MDL:HP LaserJet Pro M404dn;CMD:PJL,PML,PCL,URF,PCLXL,POSTSCRIPT,PDF;CLS:PRINTER;\
+             DES:HP LaserJet Pro M404dn;SN:PHBBB12345;",
+        );
+        assert_eq!(id.mfg(), Some("HP"));
+        assert_eq!(id.model(), Some("HP LaserJet Pro M404dn"));
+        assert_eq!(id.cmd(), vec!["PJL", "PML", "PCL", "URF", "PCLXL", "POSTSCRIPT", "PDF"]);
+        assert_eq!(id.class(), Some("PRINTER"));
+        assert_eq!(id.description(), Some("HP LaserJet Pro M404dn"));
+        assert_eq!(id.serial_number(), Some("PHBBB12345"));
+        assert_eq!(id.get("mfg"), Some("HP"));
+        assert_eq!(id.fields().count(), 6);
+    }
+
+    #[test]
+    fn test_device_id_long_keys() {
+        let id = DeviceId::parse("MANUFACTURER:Brother;MODEL:HL-L2350DW;COMMAND SET:PJL,PCL,PCLXL,URF");
+        assert_eq!(id.mfg(), Some("Brother"));
+        assert_eq!(id.model(), Some("HL-L2350DW"));
+        assert_eq!(id.cmd(), vec!["PJL", "PCL", "PCLXL", "URF"]);
+    }
+
+    #[test]
+    fn test_device_id_malformed() {
+        let id = DeviceId::parse("garbage;;:no-key;MDL:;CMD:,,;MFG : Epson ");
+        assert_eq!(id.mfg(), Some("Epson"));
+        assert_eq!(id.model(), Some(""));
+        assert!(id.cmd().is_empty());
+        assert_eq!(id.class(), None);
+
+        assert_eq!(DeviceId::parse(""), DeviceId::default());
+    }
+
+    #[test]
+    fn test_group_device_id() {
+        assert_eq!(printer_group([]).device_id(), None);
+
+        let group = printer_group([(
+            IppAttribute::PRINTER_DEVICE_ID,
+            IppValue::TextWithoutLanguage("MFG:Canon;MDL:iR-ADV C3530;CMD:PDF;".try_into().unwrap()),
+        )]);
+        let id = group.device_id().unwrap();
+        assert_eq!(id.mfg(), Some("Canon"));
+        assert_eq!(id.cmd(), vec!["PDF"]);
+    }
+
+    #[test]
+    fn test_group_printer_uris() {
+        let mut group = printer_group([
+            (
+                IppAttribute::PRINTER_URI_SUPPORTED,
+                IppValue::Array(vec![
+                    IppValue::Uri("ipps://printer.local/ipp/print".try_into().unwrap()),
+                    IppValue::Uri("ipp://printer.local/ipp/print".try_into().unwrap()),
+                ]),
+            ),
+            (IppAttribute::URI_SECURITY_SUPPORTED, keywords(&["tls", "none"])),
+            (
+                IppAttribute::URI_AUTHENTICATION_SUPPORTED,
+                keywords(&["basic", "requesting-user-name"]),
+            ),
+        ]);
+
+        assert_eq!(
+            group.printer_uris(),
+            vec![
+                PrinterUri {
+                    uri: "ipps://printer.local/ipp/print".parse().unwrap(),
+                    security: Some(UriSecurity::Tls),
+                    authentication: Some(UriAuthentication::Basic),
+                },
+                PrinterUri {
+                    uri: "ipp://printer.local/ipp/print".parse().unwrap(),
+                    security: Some(UriSecurity::None),
+                    authentication: Some(UriAuthentication::RequestingUserName),
+                },
+            ]
+        );
+
+        group
+            .attributes_mut()
+            .remove(IppAttribute::URI_AUTHENTICATION_SUPPORTED);
+        assert!(group.printer_uris().iter().all(|u| u.authentication.is_none()));
+    }
+
+    #[test]
+    fn test_printer_alert() {
+        let alert = PrinterAlert::parse(
+            "code=mediaJam;index=3;severity=critical;training=untrained;group=mediaPath;groupindex=1;location=2;time=4711;",
+        );
+        assert_eq!(alert.code(), Some("mediaJam"));
+        assert_eq!(alert.index(), Some(3));
+        assert_eq!(alert.severity(), Some("critical"));
+        assert!(alert.is_critical());
+        assert_eq!(alert.training(), Some("untrained"));
+        assert_eq!(alert.group(), Some("mediaPath"));
+        assert_eq!(alert.group_index(), Some(1));
+        assert_eq!(alert.location(), Some(2));
+        assert_eq!(alert.time(), Some(4711));
+        assert_eq!(alert.description(), None);
+        assert_eq!(alert.fields().count(), 8);
+
+        let alert = PrinterAlert::parse("garbage;code=doorOpen;index=x;=y");
+        assert_eq!(alert.code(), Some("doorOpen"));
+        assert_eq!(alert.index(), None);
+        assert!(!alert.is_critical());
+    }
+
+    #[test]
+    fn test_group_printer_alerts() {
+        assert!(printer_group([]).printer_alerts().is_empty());
+
+        let group = printer_group([
+            (
+                IppAttribute::PRINTER_ALERT,
+                IppValue::Array(vec![
+                    IppValue::OctetString("code=mediaJam;severity=critical;".try_into().unwrap()),
+                    IppValue::OctetString("code=markerSupplyLow;severity=warning;".try_into().unwrap()),
+                ]),
+            ),
+            (
+                IppAttribute::PRINTER_ALERT_DESCRIPTION,
+                IppValue::Array(vec![
+                    IppValue::TextWithoutLanguage("Paper jam in tray 1".try_into().unwrap()),
+                    IppValue::TextWithoutLanguage("Toner low".try_into().unwrap()),
+                ]),
+            ),
+        ]);
+
+        let alerts = group.printer_alerts();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].code(), Some("mediaJam"));
+        assert_eq!(alerts[0].description(), Some("Paper jam in tray 1"));
+        assert_eq!(alerts[1].severity(), Some("warning"));
+        assert_eq!(alerts[1].description(), Some("Toner low"));
+    }
+
+    #[test]
+    fn test_printer_supply() {
+        let supply = PrinterSupply::parse(
+            "index=1;class=supplyThatIsConsumed;type=toner;unit=percent;maxcapacity=100;level=42;colorantname=black;",
+        );
+        assert_eq!(supply.index(), Some(1));
+        assert_eq!(supply.class(), Some("supplyThatIsConsumed"));
+        assert_eq!(supply.supply_type(), Some("toner"));
+        assert_eq!(supply.color(), Some("black"));
+        assert_eq!(supply.level(), Some(42));
+        assert_eq!(supply.level_percent(), Some(42));
+        assert_eq!(supply.get("unit"), Some("percent"));
+
+        let supply = PrinterSupply::parse("type=ink;maxcapacity=250;level=50;colorantname=cyan;");
+        assert_eq!(supply.level_percent(), Some(20));
+
+        // some remaining
+        let supply = PrinterSupply::parse("type=toner;maxcapacity=100;level=-3;");
+        assert_eq!(supply.level(), Some(-3));
+        assert_eq!(supply.level_percent(), None);
+        assert_eq!(
+            PrinterSupply::parse("type=toner;maxcapacity=-2;level=10;").level_percent(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_group_printer_supplies() {
+        assert!(printer_group([]).printer_supplies().is_empty());
+
+        let group = printer_group([
+            (
+                IppAttribute::PRINTER_SUPPLY,
+                IppValue::Array(vec![
+                    IppValue::OctetString(
+                        "type=toner;maxcapacity=100;level=80;colorantname=black;"
+                            .try_into()
+                            .unwrap(),
+                    ),
+                    IppValue::OctetString("type=wasteToner;maxcapacity=100;level=5;".try_into().unwrap()),
+                ]),
+            ),
+            (
+                IppAttribute::PRINTER_SUPPLY_DESCRIPTION,
+                IppValue::Array(vec![
+                    IppValue::TextWithoutLanguage("Black Toner".try_into().unwrap()),
+                    IppValue::TextWithoutLanguage("Waste Toner Box".try_into().unwrap()),
+                ]),
+            ),
+        ]);
+
+        let supplies = group.printer_supplies();
+        assert_eq!(supplies.len(), 2);
+        assert_eq!(supplies[0].color(), Some("black"));
+        assert_eq!(supplies[0].level_percent(), Some(80));
+        assert_eq!(supplies[0].description(), Some("Black Toner"));
+        assert_eq!(supplies[1].supply_type(), Some("wasteToner"));
+        assert_eq!(supplies[1].description(), Some("Waste Toner Box"));
+    }
+}
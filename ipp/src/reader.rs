@@ -1,7 +1,7 @@
 //!
 //! IPP reader
 //!
-use std::io::{self, Read};
+use std::io::{self, BufRead, BufReader, Read};
 
 use bytes::Bytes;
 
@@ -10,6 +10,11 @@ use futures_util::io::{AsyncRead, AsyncReadExt};
 
 use crate::{IppHeader, model::IppVersion, parser::IppParseError, payload::IppPayload, value::IppName};
 
+// convert the buffer into a string without copying it if it contains valid UTF-8
+fn bytes_to_string(buf: Vec<u8>) -> String {
+    String::from_utf8(buf).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+}
+
 #[cfg(feature = "async")]
 /// Asynchronous IPP reader contains a set of methods to read from IPP data stream
 pub struct AsyncIppReader<R> {
@@ -33,9 +38,9 @@ where
     }
 
     async fn read_string(&mut self, len: usize) -> io::Result<String> {
-        self.read_bytes(len)
-            .await
-            .map(|b| String::from_utf8_lossy(&b).into_owned())
+        let mut buf = vec![0; len];
+        self.inner.read_exact(&mut buf).await?;
+        Ok(bytes_to_string(buf))
     }
 
     async fn read_u16(&mut self) -> io::Result<u16> {
@@ -106,7 +111,11 @@ where
     }
 }
 
-/// Synchronous IPP reader contains a set of methods to read from IPP data stream
+/// Synchronous IPP reader contains a set of methods to read from IPP data stream.
+///
+/// The reader created with [`IppReader::new`] is not buffered: every tag, length and value is read from the inner
+/// reader separately. This is fine for in-memory data and already buffered streams, but slow for network sockets
+/// and files, use [`IppReader::with_capacity`] for them.
 pub struct IppReader<R> {
     inner: R,
 }
@@ -127,7 +136,9 @@ where
     }
 
     fn read_string(&mut self, len: usize) -> io::Result<String> {
-        self.read_bytes(len).map(|b| String::from_utf8_lossy(&b).into_owned())
+        let mut buf = vec![0; len];
+        self.inner.read_exact(&mut buf)?;
+        Ok(bytes_to_string(buf))
     }
 
     fn read_u16(&mut self) -> io::Result<u16> {
@@ -188,6 +199,115 @@ where
     }
 }
 
+impl<R> IppReader<BufReader<R>>
+where
+    R: Read + Send + Sync,
+{
+    /// Create IppReader which reads from the inner reader through a buffer of the given capacity in bytes.
+    /// The payload returned by [`IppReader::into_payload`] keeps the buffer, so no data is lost.
+    pub fn with_capacity(inner: R, capacity: usize) -> Self {
+        IppReader::new(BufReader::with_capacity(capacity, inner))
+    }
+}
+
+impl<R> IppReader<ChunkedReader<R>>
+where
+    R: Read + Send + Sync,
+{
+    /// Create IppReader from a stream with HTTP chunked transfer encoding (`Transfer-Encoding: chunked`).
+    /// Most HTTP clients decode chunked bodies themselves; this is only needed for transports
+    /// which pass the raw HTTP body through.
+    pub fn chunked(inner: R) -> Self {
+        IppReader::new(ChunkedReader::new(inner))
+    }
+}
+
+/// Reader adapter which decodes HTTP chunked transfer encoding.
+/// Chunk extensions and trailer fields are skipped.
+pub struct ChunkedReader<R> {
+    inner: BufReader<R>,
+    remaining: usize,
+    done: bool,
+}
+
+impl<R: Read> ChunkedReader<R> {
+    /// Create chunked decoder from Read instance
+    pub fn new(inner: R) -> Self {
+        ChunkedReader {
+            inner: BufReader::new(inner),
+            remaining: 0,
+            done: false,
+        }
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.inner.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected end of chunked stream",
+            ));
+        }
+        Ok(line)
+    }
+
+    fn read_chunk_size(&mut self) -> io::Result<usize> {
+        let line = self.read_line()?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        usize::from_str_radix(size, 16)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("invalid chunk size: {size:?}")))
+    }
+
+    fn read_chunk_end(&mut self) -> io::Result<()> {
+        if self.read_line()?.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "missing CRLF after chunk data",
+            ))
+        }
+    }
+
+    fn skip_trailers(&mut self) -> io::Result<()> {
+        loop {
+            let mut line = String::new();
+            if self.inner.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.done || buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            self.remaining = self.read_chunk_size()?;
+            if self.remaining == 0 {
+                self.skip_trailers()?;
+                self.done = true;
+                return Ok(0);
+            }
+        }
+
+        let len = buf.len().min(self.remaining);
+        let read = self.inner.read(&mut buf[..len])?;
+        if read == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of chunk"));
+        }
+
+        self.remaining -= read;
+        if self.remaining == 0 {
+            self.read_chunk_end()?;
+        }
+        Ok(read)
+    }
+}
+
 impl<R> From<R> for IppReader<R>
 where
     R: Read + Send + Sync,
@@ -199,8 +319,10 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
-    use crate::model::StatusCode;
+    use crate::{model::StatusCode, value::IppValue};
 
     #[test]
     fn test_read_name() {
@@ -267,4 +389,79 @@ mod tests {
         assert_eq!(header.request_id, 0x11223344);
         assert_eq!(header.status_code(), StatusCode::ClientErrorForbidden);
     }
+
+    fn chunked(data: &[u8], chunk_size: usize) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for chunk in data.chunks(chunk_size) {
+            encoded.extend(format!("{:x};ext=1\r\n", chunk.len()).as_bytes());
+            encoded.extend(chunk);
+            encoded.extend(b"\r\n");
+        }
+        encoded.extend(b"0\r\nX-Trailer: value\r\n\r\n");
+        encoded
+    }
+
+    #[test]
+    fn test_chunked_response() {
+        let data = vec![
+            2, 0, 0, 0, 0, 0, 0, 1, 4, 0x21, 0x00, 0x04, b't', b'e', b's', b't', 0x00, 0x04, 0x12, 0x34, 0x56, 0x78, 3,
+            b'f', b'o', b'o',
+        ];
+
+        let reader = IppReader::chunked(io::Cursor::new(chunked(&data, 5)));
+        let mut res = crate::parser::IppParser::new(reader).parse().unwrap();
+
+        let attr = res
+            .attributes()
+            .groups_of(crate::model::DelimiterTag::PrinterAttributes)
+            .next()
+            .and_then(|g| g.attributes().get("test"))
+            .unwrap();
+        assert_eq!(attr.value().as_integer(), Some(&0x1234_5678));
+
+        let mut payload = Vec::new();
+        Read::read_to_end(res.payload_mut(), &mut payload).unwrap();
+        assert_eq!(payload, b"foo");
+    }
+
+    #[test]
+    fn test_buffered_reader() {
+        fn parse<R: Read + Send + Sync + 'static>(reader: IppReader<R>) -> (HashMap<String, IppValue>, Vec<u8>) {
+            let mut res = crate::parser::IppParser::new(reader).parse().unwrap();
+            let mut payload = Vec::new();
+            Read::read_to_end(res.payload_mut(), &mut payload).unwrap();
+            (res.attributes().flatten(), payload)
+        }
+
+        let mut data = vec![2, 0, 0, 0, 0, 0, 0, 1, 4];
+        for i in 0..20u8 {
+            let name = format!("attr-{i}");
+            data.push(0x44);
+            data.extend((name.len() as u16).to_be_bytes());
+            data.extend(name.as_bytes());
+            data.extend([0, 3, b'a' + i, b'b', b'c']);
+        }
+        data.push(3);
+        data.extend(b"payload data");
+
+        let expected = parse(IppReader::new(io::Cursor::new(data.clone())));
+        assert_eq!(expected.0.len(), 20);
+        assert_eq!(expected.1, b"payload data");
+
+        for capacity in [1, 2, 3, 7, 64, 8192] {
+            let reader = IppReader::with_capacity(io::Cursor::new(data.clone()), capacity);
+            assert_eq!(parse(reader), expected, "capacity {capacity}");
+        }
+    }
+
+    #[test]
+    fn test_chunked_reader_errors() {
+        let mut reader = ChunkedReader::new(io::Cursor::new(b"zz\r\nabc".to_vec()));
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut reader = ChunkedReader::new(io::Cursor::new(b"5\r\nabc".to_vec()));
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
 }
@@ -0,0 +1,481 @@
+//!
+//! Attribute syntax checks based on the IANA IPP registry.
+//! Useful for testing IPP printer and server implementations built on this crate.
+//!
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    FromPrimitive as _,
+    attribute::{IppAttribute, IppAttributes},
+    model::ValueTag,
+    value::{IppValue, is_keyword},
+};
+
+const INTEGER: &[ValueTag] = &[ValueTag::Integer];
+const BOOLEAN: &[ValueTag] = &[ValueTag::Boolean];
+const ENUM: &[ValueTag] = &[ValueTag::Enum];
+const RESOLUTION: &[ValueTag] = &[ValueTag::Resolution];
+const RANGE: &[ValueTag] = &[ValueTag::RangeOfInteger];
+const COLLECTION: &[ValueTag] = &[ValueTag::BegCollection];
+const TEXT: &[ValueTag] = &[ValueTag::TextWithoutLanguage, ValueTag::TextWithLanguage];
+const NAME: &[ValueTag] = &[ValueTag::NameWithoutLanguage, ValueTag::NameWithLanguage];
+const KEYWORD: &[ValueTag] = &[ValueTag::Keyword];
+const KEYWORD_OR_NAME: &[ValueTag] = &[
+    ValueTag::Keyword,
+    ValueTag::NameWithoutLanguage,
+    ValueTag::NameWithLanguage,
+];
+const URI: &[ValueTag] = &[ValueTag::Uri];
+const CHARSET: &[ValueTag] = &[ValueTag::Charset];
+const NATURAL_LANGUAGE: &[ValueTag] = &[ValueTag::NaturalLanguage];
+const MIME_MEDIA_TYPE: &[ValueTag] = &[ValueTag::MimeMediaType];
+
+// Registered syntax of the commonly used attributes, sorted by name for binary search
+const ATTRIBUTE_SYNTAX: &[(&str, &[ValueTag])] = &[
+    (IppAttribute::ATTRIBUTES_CHARSET, CHARSET),
+    (IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE, NATURAL_LANGUAGE),
+    (IppAttribute::CHARSET_CONFIGURED, CHARSET),
+    (IppAttribute::CHARSET_SUPPORTED, CHARSET),
+    (IppAttribute::COLOR_SUPPORTED, BOOLEAN),
+    (IppAttribute::COPIES, INTEGER),
+    (IppAttribute::COPIES_DEFAULT, INTEGER),
+    (IppAttribute::COPIES_SUPPORTED, RANGE),
+    (IppAttribute::DETAILED_STATUS_MESSAGE, TEXT),
+    (IppAttribute::DOCUMENT_FORMAT, MIME_MEDIA_TYPE),
+    (IppAttribute::DOCUMENT_FORMAT_DEFAULT, MIME_MEDIA_TYPE),
+    (IppAttribute::DOCUMENT_FORMAT_DETAILS, COLLECTION),
+    (IppAttribute::DOCUMENT_FORMAT_SUPPORTED, MIME_MEDIA_TYPE),
+    (IppAttribute::DOCUMENT_NUMBER, INTEGER),
+    (IppAttribute::DOCUMENT_URI, URI),
+    (IppAttribute::FINISHINGS, ENUM),
+    (IppAttribute::FINISHINGS_COL, COLLECTION),
+    (IppAttribute::FINISHINGS_COL_DATABASE, COLLECTION),
+    (IppAttribute::FINISHINGS_COL_DEFAULT, COLLECTION),
+    (IppAttribute::FINISHINGS_COL_READY, COLLECTION),
+    (IppAttribute::FINISHINGS_DEFAULT, ENUM),
+    (IppAttribute::FINISHINGS_SUPPORTED, ENUM),
+    (IppAttribute::GENERATED_NATURAL_LANGUAGE_SUPPORTED, NATURAL_LANGUAGE),
+    (IppAttribute::IPP_VERSIONS_SUPPORTED, KEYWORD),
+    (IppAttribute::JOB_ACCOUNT_ID, NAME),
+    (IppAttribute::JOB_ACCOUNTING_USER_ID, NAME),
+    (IppAttribute::JOB_ID, INTEGER),
+    (IppAttribute::JOB_NAME, NAME),
+    (IppAttribute::JOB_ORIGINATING_USER_NAME, NAME),
+    (IppAttribute::JOB_PASSWORD_ENCRYPTION, KEYWORD_OR_NAME),
+    (IppAttribute::JOB_PRINTER_URI, URI),
+    (IppAttribute::JOB_SHEETS, KEYWORD_OR_NAME),
+    (IppAttribute::JOB_SHEETS_COL, COLLECTION),
+    (IppAttribute::JOB_STATE, ENUM),
+    (IppAttribute::JOB_STATE_REASONS, KEYWORD),
+    (IppAttribute::JOB_URI, URI),
+    (IppAttribute::LAST_DOCUMENT, BOOLEAN),
+    (IppAttribute::MEDIA_COL, COLLECTION),
+    (IppAttribute::MEDIA_COL_DATABASE, COLLECTION),
+    (IppAttribute::MEDIA_COL_DEFAULT, COLLECTION),
+    (IppAttribute::MEDIA_COL_READY, COLLECTION),
+    (IppAttribute::MEDIA_COL_SUPPORTED, KEYWORD),
+    (IppAttribute::MEDIA_DEFAULT, KEYWORD_OR_NAME),
+    (IppAttribute::MEDIA_READY, KEYWORD_OR_NAME),
+    (IppAttribute::MEDIA_SUPPORTED, KEYWORD_OR_NAME),
+    (IppAttribute::MULTIPLE_DOCUMENT_HANDLING, KEYWORD),
+    (IppAttribute::NATURAL_LANGUAGE_CONFIGURED, NATURAL_LANGUAGE),
+    (IppAttribute::OPERATIONS_SUPPORTED, ENUM),
+    (IppAttribute::ORIENTATION_REQUESTED, ENUM),
+    (IppAttribute::ORIENTATION_REQUESTED_DEFAULT, ENUM),
+    (IppAttribute::ORIENTATION_REQUESTED_SUPPORTED, ENUM),
+    (IppAttribute::OUTPUT_BIN, KEYWORD_OR_NAME),
+    (IppAttribute::OVERRIDES, COLLECTION),
+    (IppAttribute::PAGES_PER_MINUTE, INTEGER),
+    (IppAttribute::PRINT_COLOR_MODE, KEYWORD),
+    (IppAttribute::PRINT_COLOR_MODE_DEFAULT, KEYWORD),
+    (IppAttribute::PRINT_COLOR_MODE_SUPPORTED, KEYWORD),
+    (IppAttribute::PRINT_QUALITY, ENUM),
+    (IppAttribute::PRINT_QUALITY_DEFAULT, ENUM),
+    (IppAttribute::PRINT_QUALITY_SUPPORTED, ENUM),
+    (IppAttribute::PRINTER_DEVICE_ID, TEXT),
+    (IppAttribute::PRINTER_ICONS, URI),
+    (IppAttribute::PRINTER_INFO, TEXT),
+    (IppAttribute::PRINTER_IS_ACCEPTING_JOBS, BOOLEAN),
+    (IppAttribute::PRINTER_LOCATION, TEXT),
+    (IppAttribute::PRINTER_MAKE_AND_MODEL, TEXT),
+    (IppAttribute::PRINTER_MORE_INFO, URI),
+    (IppAttribute::PRINTER_NAME, NAME),
+    (IppAttribute::PRINTER_RESOLUTION, RESOLUTION),
+    (IppAttribute::PRINTER_RESOLUTION_DEFAULT, RESOLUTION),
+    (IppAttribute::PRINTER_RESOLUTION_SUPPORTED, RESOLUTION),
+    (IppAttribute::PRINTER_STATE, ENUM),
+    (IppAttribute::PRINTER_STATE_MESSAGE, TEXT),
+    (IppAttribute::PRINTER_STATE_REASONS, KEYWORD),
+    (IppAttribute::PRINTER_UP_TIME, INTEGER),
+    (IppAttribute::PRINTER_URI, URI),
+    (IppAttribute::PRINTER_URI_SUPPORTED, URI),
+    (IppAttribute::PRINTER_UUID, URI),
+    (IppAttribute::QUEUED_JOB_COUNT, INTEGER),
+    (IppAttribute::REQUESTED_ATTRIBUTES, KEYWORD),
+    (IppAttribute::REQUESTING_USER_NAME, NAME),
+    (IppAttribute::SIDES, KEYWORD),
+    (IppAttribute::SIDES_DEFAULT, KEYWORD),
+    (IppAttribute::SIDES_SUPPORTED, KEYWORD),
+    (IppAttribute::STATUS_MESSAGE, TEXT),
+];
+
+/// Return the registered value syntax of an attribute, or `None` if the attribute is not known to this crate
+pub fn attribute_syntax(name: &str) -> Option<&'static [ValueTag]> {
+    ATTRIBUTE_SYNTAX
+        .binary_search_by(|(n, _)| (*n).cmp(name))
+        .ok()
+        .map(|i| ATTRIBUTE_SYNTAX[i].1)
+}
+
+impl IppValue {
+    /// Adjust the value to the registered syntax of a given attribute.
+    /// Integer and enum values are converted into each other, and keyword, name and text values without language
+    /// are converted into the registered string syntax if the value fits it, e.g. a text value only becomes
+    /// a keyword if it is a valid keyword. Values which cannot be adjusted, and values of attributes not known
+    /// to this crate, are returned unchanged.
+    pub fn coerce_for(&self, attribute: &str) -> IppValue {
+        match attribute_syntax(attribute) {
+            Some(expected) => self.coerce_to(expected),
+            None => self.clone(),
+        }
+    }
+
+    fn coerce_to(&self, expected: &[ValueTag]) -> IppValue {
+        if expected.iter().any(|t| *t as u8 == self.to_tag()) {
+            return self.clone();
+        }
+
+        let string = match self {
+            IppValue::Array(list) => return IppValue::Array(list.iter().map(|v| v.coerce_to(expected)).collect()),
+            IppValue::Integer(v) if expected.contains(&ValueTag::Enum) => return IppValue::Enum(*v),
+            IppValue::Enum(v) if expected.contains(&ValueTag::Integer) => return IppValue::Integer(*v),
+            IppValue::Keyword(v) => v.as_str(),
+            IppValue::NameWithoutLanguage(v) => v.as_str(),
+            IppValue::TextWithoutLanguage(v) => v.as_ref(),
+            _ => return self.clone(),
+        };
+
+        let coerced = expected.iter().find_map(|tag| match tag {
+            ValueTag::Keyword if is_keyword(string) => string.try_into().ok().map(IppValue::Keyword),
+            ValueTag::NameWithoutLanguage => string.try_into().ok().map(IppValue::NameWithoutLanguage),
+            ValueTag::TextWithoutLanguage => string.try_into().ok().map(IppValue::TextWithoutLanguage),
+            _ => None,
+        });
+        coerced.unwrap_or_else(|| self.clone())
+    }
+}
+
+/// Value whose tag does not match the registered syntax of its attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxMismatch {
+    /// Attribute name
+    pub name: String,
+    /// Value tag found in the attribute
+    pub found: u8,
+    /// Registered value tags, empty if the found tag itself is not registered
+    pub expected: &'static [ValueTag],
+}
+
+impl fmt::Display for SyntaxMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match ValueTag::from_u8(self.found) {
+            Some(tag) => write!(f, "{}: unexpected {tag:?} value", self.name)?,
+            None => write!(f, "{}: unexpected value tag {:#04x}", self.name, self.found)?,
+        }
+        if !self.expected.is_empty() {
+            write!(f, ", expected {:?}", self.expected)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check if the attribute is known to this crate, see [`attribute_syntax`]
+pub fn is_known_attribute(name: &str) -> bool {
+    attribute_syntax(name).is_some()
+}
+
+/// Check that the value tags of an attribute match its registered syntax.
+/// Out-of-band values (unsupported, unknown, no-value) are accepted for any attribute.
+/// For attributes not known to this crate only the registration of the value tags is checked.
+pub fn check_syntax(name: &str, value: &IppValue) -> Vec<SyntaxMismatch> {
+    check_syntax_with(name, value, attribute_syntax(name))
+}
+
+/// Check all attributes in all groups against their registered syntax
+pub fn check_attributes(attributes: &IppAttributes) -> Vec<SyntaxMismatch> {
+    check_attributes_with(attributes, check_syntax)
+}
+
+fn check_syntax_with(name: &str, value: &IppValue, expected: Option<&'static [ValueTag]>) -> Vec<SyntaxMismatch> {
+    let values: Box<dyn Iterator<Item = &IppValue>> = match value {
+        IppValue::Array(list) => Box::new(list.iter()),
+        value => Box::new(std::iter::once(value)),
+    };
+
+    values
+        .map(|v| v.to_tag())
+        .filter(|&tag| {
+            if !ValueTag::is_registered(tag) {
+                return true;
+            }
+            let out_of_band = matches!(
+                ValueTag::from_u8(tag),
+                Some(ValueTag::Unsupported | ValueTag::Unknown | ValueTag::NoValue)
+            );
+            match expected {
+                Some(expected) => !out_of_band && !expected.iter().any(|t| *t as u8 == tag),
+                None => false,
+            }
+        })
+        .map(|found| SyntaxMismatch {
+            name: name.to_owned(),
+            found,
+            expected: if ValueTag::is_registered(found) {
+                expected.unwrap_or_default()
+            } else {
+                &[]
+            },
+        })
+        .collect()
+}
+
+fn check_attributes_with<F>(attributes: &IppAttributes, check: F) -> Vec<SyntaxMismatch>
+where
+    F: Fn(&str, &IppValue) -> Vec<SyntaxMismatch>,
+{
+    attributes
+        .groups()
+        .iter()
+        .flat_map(|g| g.attributes().values())
+        .flat_map(|a| check(a.name().as_str(), a.value()))
+        .collect()
+}
+
+/// Registry of vendor extension attributes, e.g. `brother-` or `x-` prefixed attributes.
+/// Attributes registered here are checked against their syntax in addition to the ones known to this crate,
+/// which always take precedence.
+#[derive(Debug, Clone, Default)]
+pub struct VendorExtensions {
+    attributes: HashMap<String, &'static [ValueTag]>,
+    prefixes: Vec<String>,
+}
+
+impl VendorExtensions {
+    /// Create an empty registry
+    pub fn new() -> VendorExtensions {
+        VendorExtensions::default()
+    }
+
+    /// Register a vendor attribute with its value syntax
+    pub fn register<S>(&mut self, name: S, syntax: &'static [ValueTag]) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.attributes.insert(name.into(), syntax);
+        self
+    }
+
+    /// Register a vendor prefix: all attributes starting with it are considered known, with any syntax
+    pub fn register_prefix<S>(&mut self, prefix: S) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Return the value syntax of a known or registered vendor attribute
+    pub fn attribute_syntax(&self, name: &str) -> Option<&'static [ValueTag]> {
+        attribute_syntax(name).or_else(|| self.attributes.get(name).copied())
+    }
+
+    /// Check if the attribute is known to this crate, registered, or has a registered vendor prefix
+    pub fn is_known_attribute(&self, name: &str) -> bool {
+        self.attribute_syntax(name).is_some() || self.prefixes.iter().any(|prefix| name.starts_with(prefix.as_str()))
+    }
+
+    /// Same as [`check_syntax`], also checking registered vendor attributes
+    pub fn check_syntax(&self, name: &str, value: &IppValue) -> Vec<SyntaxMismatch> {
+        check_syntax_with(name, value, self.attribute_syntax(name))
+    }
+
+    /// Same as [`check_attributes`], also checking registered vendor attributes
+    pub fn check_attributes(&self, attributes: &IppAttributes) -> Vec<SyntaxMismatch> {
+        check_attributes_with(attributes, |name, value| self.check_syntax(name, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::DelimiterTag;
+
+    use super::*;
+
+    #[test]
+    fn test_syntax_table_sorted() {
+        assert!(ATTRIBUTE_SYNTAX.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_value_tag_registered() {
+        assert!(ValueTag::is_registered(ValueTag::Keyword as u8));
+        assert!(ValueTag::is_registered(0x15));
+        assert!(!ValueTag::is_registered(0x43));
+        assert!(!ValueTag::is_registered(0x60));
+    }
+
+    #[test]
+    fn test_check_syntax_correct() {
+        assert!(check_syntax(IppAttribute::COPIES, &IppValue::Integer(2)).is_empty());
+        assert!(check_syntax(IppAttribute::SIDES, &IppValue::Keyword("one-sided".try_into().unwrap())).is_empty());
+        assert!(
+            check_syntax(
+                IppAttribute::MEDIA_SUPPORTED,
+                &IppValue::Array(vec![
+                    IppValue::Keyword("iso_a4_210x297mm".try_into().unwrap()),
+                    IppValue::NameWithoutLanguage("custom".try_into().unwrap()),
+                ])
+            )
+            .is_empty()
+        );
+        assert!(check_syntax(IppAttribute::PRINTER_INFO, &IppValue::NoValue).is_empty());
+        assert!(check_syntax("vendor-attribute", &IppValue::Integer(1)).is_empty());
+    }
+
+    #[test]
+    fn test_check_syntax_mismatch() {
+        let mismatches = check_syntax(IppAttribute::COPIES, &IppValue::Keyword("two".try_into().unwrap()));
+        assert_eq!(
+            mismatches,
+            vec![SyntaxMismatch {
+                name: IppAttribute::COPIES.to_owned(),
+                found: ValueTag::Keyword as u8,
+                expected: &[ValueTag::Integer],
+            }]
+        );
+        assert_eq!(
+            mismatches[0].to_string(),
+            "copies: unexpected Keyword value, expected [Integer]"
+        );
+
+        let mut attributes = IppAttributes::new();
+        attributes.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::with_name(
+                IppAttribute::SIDES,
+                IppValue::Array(vec![
+                    IppValue::Keyword("one-sided".try_into().unwrap()),
+                    IppValue::Integer(2),
+                ]),
+            )
+            .unwrap(),
+        );
+        attributes.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::with_name(
+                "vendor-attribute",
+                IppValue::Other {
+                    tag: 0x60,
+                    data: Default::default(),
+                },
+            )
+            .unwrap(),
+        );
+
+        let mut mismatches = check_attributes(&attributes);
+        mismatches.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].name, IppAttribute::SIDES);
+        assert_eq!(mismatches[0].found, ValueTag::Integer as u8);
+        assert_eq!(mismatches[1].to_string(), "vendor-attribute: unexpected value tag 0x60");
+    }
+
+    #[test]
+    fn test_coerce_for() {
+        assert_eq!(
+            IppValue::Integer(4).coerce_for(IppAttribute::ORIENTATION_REQUESTED),
+            IppValue::Enum(4)
+        );
+        assert_eq!(
+            IppValue::Array(vec![IppValue::Integer(3), IppValue::Enum(4)])
+                .coerce_for(IppAttribute::ORIENTATION_REQUESTED_SUPPORTED),
+            IppValue::Array(vec![IppValue::Enum(3), IppValue::Enum(4)])
+        );
+        assert_eq!(IppValue::Enum(2).coerce_for(IppAttribute::COPIES), IppValue::Integer(2));
+        assert_eq!(
+            IppValue::Integer(2).coerce_for(IppAttribute::COPIES),
+            IppValue::Integer(2)
+        );
+        assert_eq!(
+            IppValue::Integer(2).coerce_for("vendor-attribute"),
+            IppValue::Integer(2)
+        );
+
+        assert_eq!(
+            IppValue::TextWithoutLanguage("one-sided".try_into().unwrap()).coerce_for(IppAttribute::SIDES),
+            IppValue::Keyword("one-sided".try_into().unwrap())
+        );
+        let text = IppValue::TextWithoutLanguage("One Sided".try_into().unwrap());
+        assert_eq!(text.coerce_for(IppAttribute::SIDES), text);
+        assert_eq!(
+            IppValue::Keyword("report".try_into().unwrap()).coerce_for(IppAttribute::JOB_NAME),
+            IppValue::NameWithoutLanguage("report".try_into().unwrap())
+        );
+        assert_eq!(
+            IppValue::NameWithoutLanguage("Office".try_into().unwrap()).coerce_for(IppAttribute::PRINTER_INFO),
+            IppValue::TextWithoutLanguage("Office".try_into().unwrap())
+        );
+        assert_eq!(
+            IppValue::Boolean(true).coerce_for(IppAttribute::COPIES),
+            IppValue::Boolean(true)
+        );
+    }
+
+    #[test]
+    fn test_vendor_extensions() {
+        const VENDOR_SYNTAX: &[ValueTag] = &[ValueTag::Integer];
+
+        assert!(is_known_attribute(IppAttribute::COPIES));
+        assert!(!is_known_attribute("brother-toner-level"));
+
+        let mut vendor = VendorExtensions::new();
+        vendor
+            .register("brother-toner-level", VENDOR_SYNTAX)
+            .register_prefix("x-acme-");
+
+        assert!(vendor.is_known_attribute(IppAttribute::COPIES));
+        assert!(vendor.is_known_attribute("brother-toner-level"));
+        assert!(vendor.is_known_attribute("x-acme-stapler"));
+        assert!(!vendor.is_known_attribute("x-other-stapler"));
+        assert_eq!(vendor.attribute_syntax("brother-toner-level"), Some(VENDOR_SYNTAX));
+        assert_eq!(vendor.attribute_syntax("x-acme-stapler"), None);
+
+        assert!(
+            vendor
+                .check_syntax("brother-toner-level", &IppValue::Integer(80))
+                .is_empty()
+        );
+        let keyword = IppValue::Keyword("full".try_into().unwrap());
+        assert!(check_syntax("brother-toner-level", &keyword).is_empty());
+        assert_eq!(
+            vendor.check_syntax("brother-toner-level", &keyword),
+            vec![SyntaxMismatch {
+                name: "brother-toner-level".to_owned(),
+                found: ValueTag::Keyword as u8,
+                expected: VENDOR_SYNTAX,
+            }]
+        );
+
+        // the registered syntax of known attributes cannot be overridden
+        vendor.register(IppAttribute::COPIES, &[ValueTag::Keyword]);
+        assert_eq!(vendor.check_syntax(IppAttribute::COPIES, &keyword).len(), 1);
+
+        let mut attributes = IppAttributes::new();
+        attributes.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name("brother-toner-level", keyword).unwrap(),
+        );
+        assert!(check_attributes(&attributes).is_empty());
+        assert_eq!(vendor.check_attributes(&attributes).len(), 1);
+    }
+}
@@ -13,13 +13,28 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     IppHeader,
-    attribute::{IppAttribute, IppAttributes},
+    attribute::{IppAttribute, IppAttributeGroup, IppAttributes},
+    error::IppError,
     model::{DelimiterTag, IppVersion, Operation, StatusCode},
-    parser::IppParseError,
+    parser::{IppParseError, IppParser},
     payload::IppPayload,
+    reader::IppReader,
     value::*,
 };
 
+/// Status reported for a single entity of a response, see [`IppRequestResponse::entity_statuses`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityStatus {
+    /// Tag of the group describing the entity, e.g. job attributes
+    pub tag: DelimiterTag,
+    /// Position of the group among the groups with the same tag
+    pub index: usize,
+    /// `status-message` of the entity
+    pub message: Option<String>,
+    /// `detailed-status-message` of the entity
+    pub detailed_message: Option<String>,
+}
+
 /// IPP request/response struct
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct IppRequestResponse {
@@ -82,33 +97,83 @@ impl IppRequestResponse {
         }
     }
 
-    /// Create response from status and id
-    pub fn new_response(version: IppVersion, status: StatusCode, id: u32) -> Result<IppRequestResponse, IppParseError> {
+    /// Create response from status, request id and response attributes, e.g. the printer attributes group of a
+    /// Get-Printer-Attributes response. The `attributes-charset` and `attributes-natural-language` operation
+    /// attributes are added unless already present.
+    pub fn new_response(
+        version: IppVersion,
+        status: StatusCode,
+        id: u32,
+        attributes: IppAttributes,
+    ) -> Result<IppRequestResponse, IppParseError> {
         let header = IppHeader::new(version, status as u16, id);
         let mut response = IppRequestResponse {
             header,
-            attributes: IppAttributes::new(),
+            attributes,
             payload: IppPayload::empty(),
         };
 
-        response.attributes_mut().add(
-            DelimiterTag::OperationAttributes,
-            IppAttribute::new(
-                IppAttribute::ATTRIBUTES_CHARSET.try_into().unwrap(),
-                IppValue::Charset("utf-8".try_into()?),
-            ),
-        );
-        response.attributes_mut().add(
-            DelimiterTag::OperationAttributes,
-            IppAttribute::new(
-                IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE.try_into().unwrap(),
+        let groups = response.attributes_mut().groups_mut();
+        if !groups.iter().any(|g| g.tag() == DelimiterTag::OperationAttributes) {
+            groups.insert(0, IppAttributeGroup::new(DelimiterTag::OperationAttributes));
+        }
+
+        let defaults = [
+            (IppAttribute::ATTRIBUTES_CHARSET, IppValue::Charset("utf-8".try_into()?)),
+            (
+                IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
                 IppValue::NaturalLanguage("en".try_into()?),
             ),
-        );
+        ];
+        for (name, value) in defaults {
+            let exists = response
+                .attributes()
+                .groups_of(DelimiterTag::OperationAttributes)
+                .next()
+                .is_some_and(|g| g.get(name).is_some());
+            if !exists {
+                response
+                    .attributes_mut()
+                    .add(DelimiterTag::OperationAttributes, IppAttribute::with_name(name, value)?);
+            }
+        }
 
         Ok(response)
     }
 
+    /// Assemble a request or response from raw parts, without adding any default attributes.
+    /// Useful for servers, proxies and test doubles.
+    pub fn from_parts(
+        version: IppVersion,
+        operation_or_status: u16,
+        request_id: u32,
+        attributes: IppAttributes,
+        payload: IppPayload,
+    ) -> IppRequestResponse {
+        IppRequestResponse {
+            header: IppHeader::new(version, operation_or_status, request_id),
+            attributes,
+            payload,
+        }
+    }
+
+    /// Parse an incoming IPP request, as received by a server. Fails if the header does not
+    /// carry a known operation code.
+    pub fn parse_request<R, T>(reader: T) -> Result<IppRequestResponse, IppParseError>
+    where
+        R: 'static + Read + Send + Sync,
+        T: Into<IppReader<R>>,
+    {
+        let request = IppParser::new(reader).parse()?;
+        request.header.operation()?;
+        Ok(request)
+    }
+
+    /// Get IPP operation of a request, or None if the header does not carry a known operation code
+    pub fn operation(&self) -> Option<Operation> {
+        self.header.operation().ok()
+    }
+
     /// Get IPP header
     pub fn header(&self) -> &IppHeader {
         &self.header
@@ -129,6 +194,92 @@ impl IppRequestResponse {
         &mut self.attributes
     }
 
+    /// Check the response status. Returns the attributes if the status code is successful,
+    /// otherwise an error with the status code and the `status-message` and `detailed-status-message`
+    /// operation attributes, if present.
+    ///
+    /// Only the top-level status is checked: a successful response may still report problems with single jobs
+    /// or documents, see [`IppRequestResponse::entity_statuses`] and [`IppRequestResponse::is_complete_success`].
+    pub fn result(&self) -> Result<&IppAttributes, IppError> {
+        let status = self.header.status_code();
+        if status.is_success() {
+            Ok(&self.attributes)
+        } else {
+            Err(IppError::StatusMessageError {
+                status,
+                message: self.status_message(),
+                detailed_message: self.detailed_status_message(),
+            })
+        }
+    }
+
+    fn operation_text(&self, name: &str) -> Option<String> {
+        self.attributes
+            .groups_of(DelimiterTag::OperationAttributes)
+            .find_map(|group| group.first_value(name))
+            .map(|value| value.to_string())
+    }
+
+    /// Get the `status-message` operation attribute of a response
+    pub fn status_message(&self) -> Option<String> {
+        self.operation_text(IppAttribute::STATUS_MESSAGE)
+    }
+
+    /// Get the `detailed-status-message` operation attribute of a response, additional technical details
+    /// intended for administrators
+    pub fn detailed_status_message(&self) -> Option<String> {
+        self.operation_text(IppAttribute::DETAILED_STATUS_MESSAGE)
+    }
+
+    /// Return the per-entity status messages of a response, e.g. job groups of a Get-Jobs or Cancel-Jobs response
+    /// which carry their own `status-message` or `detailed-status-message`.
+    pub fn entity_statuses(&self) -> Vec<EntityStatus> {
+        let mut counts: Vec<(DelimiterTag, usize)> = Vec::new();
+        let mut statuses = Vec::new();
+        for group in self.attributes.groups() {
+            let tag = group.tag();
+            if matches!(
+                tag,
+                DelimiterTag::OperationAttributes | DelimiterTag::UnsupportedAttributes
+            ) {
+                continue;
+            }
+            let index = match counts.iter_mut().find(|(t, _)| *t == tag) {
+                Some((_, count)) => {
+                    *count += 1;
+                    *count - 1
+                }
+                None => {
+                    counts.push((tag, 1));
+                    0
+                }
+            };
+            let text = |name| group.first_value(name).map(|v| v.to_string());
+            let message = text(IppAttribute::STATUS_MESSAGE);
+            let detailed_message = text(IppAttribute::DETAILED_STATUS_MESSAGE);
+            if message.is_some() || detailed_message.is_some() {
+                statuses.push(EntityStatus {
+                    tag,
+                    index,
+                    message,
+                    detailed_message,
+                });
+            }
+        }
+        statuses
+    }
+
+    /// Check that the request fully succeeded: the status is `successful-ok`, no attributes were
+    /// unsupported and no entity in the response reports a status message of its own
+    pub fn is_complete_success(&self) -> bool {
+        self.header.status_code() == StatusCode::SuccessfulOk
+            && self
+                .attributes
+                .groups_of(DelimiterTag::UnsupportedAttributes)
+                .all(|g| g.attributes().is_empty())
+            && self.entity_statuses().is_empty()
+    }
+
     /// Get payload
     pub fn payload(&self) -> &IppPayload {
         &self.payload
@@ -139,6 +290,21 @@ impl IppRequestResponse {
         &mut self.payload
     }
 
+    /// Compute the total size of the serialized request including payload, suitable for the HTTP Content-Length
+    /// header. The attributes are not serialized for this computation.
+    ///
+    /// Returns `None` if the payload is a stream of unknown length.
+    pub fn content_length(&self) -> Option<u64> {
+        let payload_len = self.payload.size()?;
+        Some((IppHeader::ENCODED_LEN + self.attributes.encoded_len()) as u64 + payload_len)
+    }
+
+    /// Write only the 8-byte header (version, operation or status code, request id), e.g. for custom flows
+    /// which encode the attributes themselves
+    pub fn header_bytes(&self) -> Bytes {
+        self.header.to_bytes()
+    }
+
     /// Write request to byte array not including payload
     pub fn to_bytes(&self) -> Bytes {
         let mut buffer = BytesMut::new();
@@ -169,3 +335,344 @@ impl IppRequestResponse {
         self.payload
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_request() -> IppRequestResponse {
+        IppRequestResponse::new(
+            IppVersion::v1_1(),
+            Operation::PrintJob,
+            Some("ipp://localhost/printers/test".parse().unwrap()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_content_length_known_size() {
+        let mut req = new_request();
+        req.attributes_mut().add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::with_name("copies", IppValue::Integer(2)).unwrap(),
+        );
+        *req.payload_mut() = IppPayload::from(b"%PDF-1.4 test".to_vec());
+
+        let expected = req.to_bytes().len() as u64 + 13;
+        assert_eq!(req.content_length(), Some(expected));
+
+        let mut data = Vec::new();
+        req.into_read().read_to_end(&mut data).unwrap();
+        assert_eq!(data.len() as u64, expected);
+    }
+
+    #[test]
+    fn test_content_length_empty_payload() {
+        let req = new_request();
+        assert_eq!(req.content_length(), Some(req.to_bytes().len() as u64));
+    }
+
+    #[test]
+    fn test_content_length_streaming() {
+        let mut req = new_request();
+        *req.payload_mut() = IppPayload::new(io::Cursor::new(b"stream".to_vec()));
+        assert_eq!(req.content_length(), None);
+    }
+
+    #[test]
+    fn test_result_success() {
+        let resp =
+            IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, 1, IppAttributes::new())
+                .unwrap();
+        let attrs = resp.result().unwrap();
+        assert!(attrs.groups_of(DelimiterTag::OperationAttributes).next().is_some());
+    }
+
+    #[test]
+    fn test_result_error_with_message() {
+        let mut resp = IppRequestResponse::new_response(
+            IppVersion::v1_1(),
+            StatusCode::ClientErrorNotFound,
+            1,
+            IppAttributes::new(),
+        )
+        .unwrap();
+        resp.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::text(IppAttribute::STATUS_MESSAGE, "The printer or class does not exist.").unwrap(),
+        );
+
+        let error = resp.result().unwrap_err();
+        assert!(matches!(
+            error,
+            IppError::StatusMessageError {
+                status: StatusCode::ClientErrorNotFound,
+                message: Some(ref message),
+                detailed_message: None,
+            } if message == "The printer or class does not exist."
+        ));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "IPP status error: {}: The printer or class does not exist.",
+                StatusCode::ClientErrorNotFound
+            )
+        );
+    }
+
+    #[test]
+    fn test_detailed_status_message() {
+        let mut resp = IppRequestResponse::new_response(
+            IppVersion::v1_1(),
+            StatusCode::ServerErrorInternalError,
+            1,
+            IppAttributes::new(),
+        )
+        .unwrap();
+        resp.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::text(IppAttribute::STATUS_MESSAGE, "Internal error").unwrap(),
+        );
+        resp.attributes_mut().add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::text(IppAttribute::DETAILED_STATUS_MESSAGE, "filter pdftoraster failed").unwrap(),
+        );
+
+        assert_eq!(resp.status_message().as_deref(), Some("Internal error"));
+        assert_eq!(
+            resp.detailed_status_message().as_deref(),
+            Some("filter pdftoraster failed")
+        );
+        let error = resp.result().unwrap_err();
+        assert!(matches!(
+            error,
+            IppError::StatusMessageError {
+                detailed_message: Some(ref detail),
+                ..
+            } if detail == "filter pdftoraster failed"
+        ));
+        assert!(
+            error
+                .to_string()
+                .ends_with(": Internal error (filter pdftoraster failed)")
+        );
+    }
+
+    #[test]
+    fn test_partial_success() {
+        let mut attributes = IppAttributes::new();
+        for (job_id, message) in [(1, None), (2, Some("job is already completed")), (3, None)] {
+            let mut group = IppAttributeGroup::new(DelimiterTag::JobAttributes);
+            group.insert(IppAttribute::integer(IppAttribute::JOB_ID, job_id).unwrap());
+            if let Some(message) = message {
+                group.insert(IppAttribute::text(IppAttribute::STATUS_MESSAGE, message).unwrap());
+            }
+            attributes.groups_mut().push(group);
+        }
+        let resp =
+            IppRequestResponse::new_response(IppVersion::v2_0(), StatusCode::SuccessfulOk, 1, attributes).unwrap();
+        let parsed = IppParser::new(IppReader::new(io::Cursor::new(resp.to_bytes().to_vec())))
+            .parse()
+            .unwrap();
+
+        assert!(parsed.result().is_ok());
+        assert!(!parsed.is_complete_success());
+        assert_eq!(
+            parsed.entity_statuses(),
+            vec![EntityStatus {
+                tag: DelimiterTag::JobAttributes,
+                index: 1,
+                message: Some("job is already completed".to_owned()),
+                detailed_message: None,
+            }]
+        );
+
+        let ok =
+            IppRequestResponse::new_response(IppVersion::v2_0(), StatusCode::SuccessfulOk, 1, IppAttributes::new())
+                .unwrap();
+        assert!(ok.is_complete_success());
+
+        let mut substituted = IppRequestResponse::new_response(
+            IppVersion::v2_0(),
+            StatusCode::SuccessfulOkIgnoredOrSubstitutedAttributes,
+            1,
+            IppAttributes::new(),
+        )
+        .unwrap();
+        assert!(!substituted.is_complete_success());
+        *substituted.header_mut() = IppHeader::new(IppVersion::v2_0(), StatusCode::SuccessfulOk as u16, 1);
+        substituted.attributes_mut().add(
+            DelimiterTag::UnsupportedAttributes,
+            IppAttribute::keyword("finishings-col", "unsupported").unwrap(),
+        );
+        assert!(!substituted.is_complete_success());
+    }
+
+    #[test]
+    fn test_header_bytes() {
+        let mut req = new_request();
+        req.header_mut().request_id = 0x0102_0304;
+
+        let header = req.header_bytes();
+        assert_eq!(header.as_ref(), &[0x01, 0x01, 0x00, 0x02, 0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(header.len(), IppHeader::ENCODED_LEN);
+        assert!(req.to_bytes().starts_with(&header));
+    }
+
+    #[test]
+    fn test_new_response_with_attributes() {
+        let mut attributes = IppAttributes::new();
+        attributes.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name(
+                IppAttribute::PRINTER_NAME,
+                IppValue::NameWithoutLanguage("office".try_into().unwrap()),
+            )
+            .unwrap(),
+        );
+        attributes.add(
+            DelimiterTag::PrinterAttributes,
+            IppAttribute::with_name(IppAttribute::PRINTER_STATE, IppValue::Enum(3)).unwrap(),
+        );
+
+        let response =
+            IppRequestResponse::new_response(IppVersion::v2_0(), StatusCode::SuccessfulOk, 42, attributes).unwrap();
+        let bytes = response.to_bytes();
+        assert_eq!(bytes[..8], [0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 42]);
+
+        let parsed = IppParser::new(IppReader::new(io::Cursor::new(bytes.to_vec())))
+            .parse()
+            .unwrap();
+        assert_eq!(parsed.header().status_code(), StatusCode::SuccessfulOk);
+        assert_eq!(parsed.header().request_id, 42);
+        assert!(parsed.result().is_ok());
+
+        let groups = parsed.attributes().groups();
+        assert_eq!(groups[0].tag(), DelimiterTag::OperationAttributes);
+        assert_eq!(
+            groups[0].attributes()[IppAttribute::ATTRIBUTES_CHARSET].value(),
+            &IppValue::Charset("utf-8".try_into().unwrap())
+        );
+        let printer = parsed
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap();
+        assert_eq!(
+            printer.attributes()[IppAttribute::PRINTER_NAME].value().to_string(),
+            "office"
+        );
+        assert_eq!(
+            printer.attributes()[IppAttribute::PRINTER_STATE].value(),
+            &IppValue::Enum(3)
+        );
+
+        // existing operation attributes are kept
+        let mut attributes = IppAttributes::new();
+        attributes.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(
+                IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE,
+                IppValue::NaturalLanguage("de".try_into().unwrap()),
+            )
+            .unwrap(),
+        );
+        let response =
+            IppRequestResponse::new_response(IppVersion::v1_1(), StatusCode::SuccessfulOk, 1, attributes).unwrap();
+        assert_eq!(response.attributes().groups().len(), 1);
+        let operation = &response.attributes().groups()[0];
+        assert_eq!(operation.attributes().len(), 2);
+        assert_eq!(
+            operation.attributes()[IppAttribute::ATTRIBUTES_NATURAL_LANGUAGE].value(),
+            &IppValue::NaturalLanguage("de".try_into().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_from_parts_round_trip() {
+        let mut attributes = IppAttributes::new();
+        attributes.add(
+            DelimiterTag::OperationAttributes,
+            IppAttribute::with_name(
+                IppAttribute::ATTRIBUTES_CHARSET,
+                IppValue::Charset("utf-8".try_into().unwrap()),
+            )
+            .unwrap(),
+        );
+        attributes.add(
+            DelimiterTag::JobAttributes,
+            IppAttribute::integer(IppAttribute::JOB_ID, 42).unwrap(),
+        );
+
+        let msg = IppRequestResponse::from_parts(
+            IppVersion::v2_0(),
+            Operation::SendDocument as u16,
+            7,
+            attributes,
+            IppPayload::from(b"payload".to_vec()),
+        );
+
+        let mut data = Vec::new();
+        msg.into_read().read_to_end(&mut data).unwrap();
+
+        let parsed = crate::parser::IppParser::new(crate::reader::IppReader::new(io::Cursor::new(data)))
+            .parse()
+            .unwrap();
+        assert_eq!(parsed.header().version, IppVersion::v2_0());
+        assert_eq!(parsed.header().operation_or_status, Operation::SendDocument as u16);
+        assert_eq!(parsed.header().request_id, 7);
+
+        let groups = parsed.attributes().groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].attributes().len(), 1);
+        assert_eq!(
+            groups[1].attributes()[IppAttribute::JOB_ID].value(),
+            &IppValue::Integer(42)
+        );
+
+        let mut payload = Vec::new();
+        Read::read_to_end(&mut parsed.into_payload(), &mut payload).unwrap();
+        assert_eq!(payload, b"payload");
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let mut data = vec![0x01, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x05, 0x01];
+        data.extend(
+            IppAttribute::with_name(
+                IppAttribute::ATTRIBUTES_CHARSET,
+                IppValue::Charset("utf-8".try_into().unwrap()),
+            )
+            .unwrap()
+            .to_bytes(),
+        );
+        data.extend(
+            IppAttribute::with_name(
+                IppAttribute::PRINTER_URI,
+                IppValue::Uri("ipp://localhost/printers/test".try_into().unwrap()),
+            )
+            .unwrap()
+            .to_bytes(),
+        );
+        data.push(0x03);
+        data.extend(b"%!PS");
+
+        let req = IppRequestResponse::parse_request(io::Cursor::new(data.clone())).unwrap();
+        assert_eq!(req.operation(), Some(Operation::PrintJob));
+        assert_eq!(req.header().request_id, 5);
+        assert!(
+            req.attributes()
+                .groups_of(DelimiterTag::OperationAttributes)
+                .next()
+                .unwrap()
+                .attributes()
+                .contains_key(IppAttribute::PRINTER_URI)
+        );
+
+        data[3] = 0x01;
+        assert!(matches!(
+            IppRequestResponse::parse_request(io::Cursor::new(data)),
+            Err(IppParseError::InvalidOperation(0x0001))
+        ));
+    }
+}
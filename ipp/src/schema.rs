@@ -0,0 +1,290 @@
+//!
+//! `schemars::JsonSchema` implementations for the serde representation of IPP values and attributes
+//!
+//! The schemas describe the JSON produced by the `serde` feature, so they can be used by OpenAPI tools
+//! such as `utoipa` or `aide` for gateways exposing IPP attributes as JSON.
+//!
+use std::{borrow::Cow, collections::BTreeMap};
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+
+use crate::{
+    attribute::IppAttribute,
+    value::{IppCollection, IppTextValue, IppValue},
+};
+
+// BoundedString limits are in bytes, maxLength counts characters, so it is an upper bound for ASCII text only
+fn string(max: usize) -> Schema {
+    json_schema!({ "type": "string", "maxLength": max })
+}
+
+fn integer(min: i64, max: i64) -> Schema {
+    json_schema!({ "type": "integer", "minimum": min, "maximum": max })
+}
+
+fn int32() -> Schema {
+    integer(i32::MIN.into(), i32::MAX.into())
+}
+
+fn uint8() -> Schema {
+    integer(0, u8::MAX.into())
+}
+
+// Raw `Bytes` data is serialized as a base64 string in human-readable formats
+fn base64() -> Schema {
+    json_schema!({ "type": "string", "contentEncoding": "base64" })
+}
+
+fn object<'a>(properties: impl IntoIterator<Item = (&'a str, Schema)>) -> Schema {
+    let properties = properties.into_iter().collect::<BTreeMap<_, _>>();
+    let required = properties.keys().collect::<Vec<_>>();
+
+    json_schema!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+// Externally tagged enum variant: `{"Variant": <content>}`
+fn variant(name: &str, content: Schema) -> Schema {
+    object([(name, content)])
+}
+
+// Unit enum variant: `"Variant"`
+fn unit_variant(name: &str) -> Schema {
+    json_schema!({ "const": name })
+}
+
+impl JsonSchema for IppTextValue {
+    fn schema_name() -> Cow<'static, str> {
+        "IppTextValue".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "ipp::value::IppTextValue".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Text value, tagged with its length tier",
+            "oneOf": [
+                variant("Short", string(127)),
+                variant("Medium", string(255)),
+                variant("Long", string(1023)),
+            ],
+        })
+    }
+}
+
+impl JsonSchema for IppCollection {
+    fn schema_name() -> Cow<'static, str> {
+        "IppCollection".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "ipp::value::IppCollection".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let member = json_schema!({
+            "type": "array",
+            "prefixItems": [string(255), generator.subschema_for::<IppValue>()],
+            "items": false,
+            "minItems": 2,
+        });
+        let mut schema = object([("members", json_schema!({ "type": "array", "items": member }))]);
+        schema.insert(
+            "description".into(),
+            "Collection members as [name, value] pairs in wire order".into(),
+        );
+        schema
+    }
+}
+
+impl JsonSchema for IppValue {
+    fn schema_name() -> Cow<'static, str> {
+        "IppValue".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "ipp::value::IppValue".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let text = generator.subschema_for::<IppTextValue>();
+        let value = generator.subschema_for::<IppValue>();
+        let collection = generator.subschema_for::<IppCollection>();
+
+        json_schema!({
+            "description": "IPP attribute value",
+            "oneOf": [
+                variant("Integer", int32()),
+                variant("Enum", int32()),
+                variant("OctetString", text.clone()),
+                variant("TextWithoutLanguage", text.clone()),
+                variant("NameWithoutLanguage", string(255)),
+                variant("TextWithLanguage", object([("language", string(63)), ("text", text)])),
+                variant("NameWithLanguage", object([("language", string(63)), ("name", string(255))])),
+                variant("Charset", string(63)),
+                variant("NaturalLanguage", string(63)),
+                variant("Uri", string(1023)),
+                variant("UriScheme", string(1023)),
+                variant("RangeOfInteger", object([("min", int32()), ("max", int32())])),
+                variant("Boolean", json_schema!({ "type": "boolean" })),
+                variant("Keyword", string(255)),
+                variant("Array", json_schema!({ "type": "array", "items": value })),
+                variant("Collection", collection),
+                variant("MimeMediaType", string(255)),
+                variant(
+                    "DateTime",
+                    object([
+                        ("year", integer(0, u16::MAX.into())),
+                        ("month", uint8()),
+                        ("day", uint8()),
+                        ("hour", uint8()),
+                        ("minutes", uint8()),
+                        ("seconds", uint8()),
+                        ("deci_seconds", uint8()),
+                        ("utc_dir", json_schema!({ "type": "string", "minLength": 1, "maxLength": 1 })),
+                        ("utc_hours", uint8()),
+                        ("utc_mins", uint8()),
+                    ])
+                ),
+                variant("MemberAttrName", string(255)),
+                variant(
+                    "Resolution",
+                    object([
+                        ("cross_feed", int32()),
+                        ("feed", int32()),
+                        ("units", integer(i8::MIN.into(), i8::MAX.into())),
+                    ])
+                ),
+                unit_variant("Unsupported"),
+                unit_variant("Unknown"),
+                unit_variant("NoValue"),
+                variant("Other", object([("tag", uint8()), ("data", base64())])),
+            ],
+        })
+    }
+}
+
+impl JsonSchema for IppAttribute {
+    fn schema_name() -> Cow<'static, str> {
+        "IppAttribute".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "ipp::attribute::IppAttribute".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        object([("name", string(255)), ("value", generator.subschema_for::<IppValue>())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use schemars::schema_for;
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    // Variant names accepted by the root IppValue schema
+    fn variant_names(schema: &Schema) -> Vec<String> {
+        schema.as_value()["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| match v.get("const") {
+                Some(name) => name.as_str().unwrap().to_owned(),
+                None => v["required"][0].as_str().unwrap().to_owned(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schema_for_ipp_value() {
+        let schema = schema_for!(IppValue);
+        let root = schema.as_value();
+        assert_eq!(root["$schema"], "https://json-schema.org/draft/2020-12/schema");
+        assert_eq!(root["title"], "IppValue");
+
+        let names = variant_names(&schema);
+        for name in [
+            "Integer",
+            "Enum",
+            "OctetString",
+            "TextWithoutLanguage",
+            "NameWithoutLanguage",
+            "TextWithLanguage",
+            "NameWithLanguage",
+            "Charset",
+            "NaturalLanguage",
+            "Uri",
+            "UriScheme",
+            "RangeOfInteger",
+            "Boolean",
+            "Keyword",
+            "Array",
+            "Collection",
+            "MimeMediaType",
+            "DateTime",
+            "MemberAttrName",
+            "Resolution",
+            "Unsupported",
+            "Unknown",
+            "NoValue",
+            "Other",
+        ] {
+            assert!(names.iter().any(|n| n == name), "missing variant {name}");
+        }
+
+        let other = &root["oneOf"][23]["properties"]["Other"];
+        assert_eq!(other["properties"]["data"]["contentEncoding"], "base64");
+
+        let text = &root["$defs"]["IppTextValue"]["oneOf"];
+        assert_eq!(text[0]["properties"]["Short"]["maxLength"], 127);
+        assert_eq!(text[2]["properties"]["Long"]["maxLength"], 1023);
+
+        // the recursive reference points to the root schema
+        let array = &root["oneOf"][14]["properties"]["Array"];
+        assert_eq!(array["items"]["$ref"], "#");
+        assert!(root["$defs"]["IppCollection"].is_object());
+    }
+
+    #[test]
+    fn test_schema_matches_serde_output() {
+        let schema = schema_for!(IppValue);
+        let names = variant_names(&schema);
+
+        let values = [
+            IppValue::Integer(1),
+            IppValue::TextWithoutLanguage(IppTextValue::new("text").unwrap()),
+            IppValue::RangeOfInteger { min: 1, max: 5 },
+            IppValue::Array(vec![IppValue::Boolean(true)]),
+            IppValue::NoValue,
+            IppValue::Other {
+                tag: 0x7f,
+                data: Bytes::from_static(b"raw"),
+            },
+        ];
+        for value in values {
+            let json = serde_json::to_value(&value).unwrap();
+            let name = match &json {
+                Value::String(name) => name.clone(),
+                Value::Object(map) => map.keys().next().unwrap().clone(),
+                other => panic!("unexpected json {other}"),
+            };
+            assert!(names.contains(&name), "{name} is not in the schema");
+        }
+
+        let schema = schema_for!(IppAttribute);
+        let root = schema.as_value();
+        assert_eq!(root["required"], json!(["name", "value"]));
+        assert_eq!(root["properties"]["value"]["$ref"], "#/$defs/IppValue");
+        assert!(root["$defs"]["IppValue"]["oneOf"].is_array());
+    }
+}
@@ -2,16 +2,29 @@
 //! IPP value
 //!
 #![allow(unused_assignments)]
-use std::{borrow::Cow, collections::BTreeMap, fmt, ops::Deref, str::FromStr};
+use alloc::{borrow::Cow, collections::BTreeMap, format, string::String, vec::Vec};
+// EnumAsInner expands to `std::hint` paths, which are provided by core without std
+#[cfg(not(feature = "std"))]
+use core as std;
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    str::FromStr,
+};
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use enum_as_inner::EnumAsInner;
 
+#[cfg(feature = "std")]
 use http::Uri;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{FromPrimitive as _, model::ValueTag, parser::IppParseError};
+use crate::{FromPrimitive as _, error::IppParseError, model::ValueTag};
+
+// Largest value which fits into the 16-bit value length field
+pub(crate) const MAX_VALUE_LENGTH: usize = u16::MAX as usize;
 
 /// A UTF-8 string whose length is bounded by a compile-time maximum (in bytes).
 ///
@@ -27,7 +40,6 @@ use crate::{FromPrimitive as _, model::ValueTag, parser::IppParseError};
 /// # Errors
 /// Returns [`IppParseError::InvalidStringLength`] if the input exceeds `MAX`.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BoundedString<const MAX: usize = 1023> {
     inner: String,
 }
@@ -54,6 +66,20 @@ impl<const MAX: usize> BoundedString<MAX> {
         Ok(Self { inner: s })
     }
 
+    /// Create a bounded string from raw bytes.
+    /// Returns an error if the data is not valid UTF-8 or its length exceeds `MAX`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, IppParseError> {
+        Self::new(core::str::from_utf8(data)?)
+    }
+
+    /// Create a bounded string from raw bytes, replacing invalid UTF-8 sequences with `U+FFFD`.
+    /// If the result is longer than `MAX` it is truncated on a character boundary.
+    pub fn from_bytes_lossy(data: &[u8]) -> Self {
+        let mut inner = String::from_utf8_lossy(data).into_owned();
+        inner.truncate(inner.floor_char_boundary(MAX));
+        Self { inner }
+    }
+
     pub const fn max() -> usize {
         MAX
     }
@@ -74,11 +100,23 @@ impl<const MAX: usize> BoundedString<MAX> {
         self.inner.is_empty()
     }
 
+    /// Convert the string to ASCII lower case in place, the length is unchanged
+    pub fn make_ascii_lowercase(&mut self) {
+        self.inner.make_ascii_lowercase();
+    }
+
     /// Widen the max size of the bounded string.
     /// Infallible because all strings of length <= MAX are valid for any larger MAX2.
-    /// if attempting to expand to a smaller `MAX2` the assertion will fail causing a panic.
+    /// Expanding to a smaller `MAX2` is rejected at compile time, use [`BoundedString::shrink`] instead:
+    ///
+    /// ```compile_fail
+    /// use ipp::value::{IppKeyword, IppShortString};
+    ///
+    /// let keyword: IppKeyword = "none".try_into().unwrap();
+    /// let short: IppShortString = keyword.expand();
+    /// ```
     pub fn expand<const MAX2: usize>(self) -> BoundedString<MAX2> {
-        assert!(MAX2 >= MAX);
+        const { assert!(MAX2 >= MAX, "cannot expand a bounded string to a smaller size") };
         BoundedString::<MAX2> { inner: self.inner }
     }
 
@@ -101,7 +139,7 @@ impl<const MAX: usize> From<BoundedString<MAX>> for String {
     }
 }
 
-impl<const MAX: usize> std::borrow::Borrow<str> for BoundedString<MAX> {
+impl<const MAX: usize> core::borrow::Borrow<str> for BoundedString<MAX> {
     fn borrow(&self) -> &str {
         &self.inner
     }
@@ -155,6 +193,23 @@ impl<const MAX: usize> fmt::Display for BoundedString<MAX> {
     }
 }
 
+// Serialized as a plain string, the length bound is checked when deserializing
+#[cfg(feature = "serde")]
+impl<const MAX: usize> Serialize for BoundedString<MAX> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.inner)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const MAX: usize> Deserialize<'de> for BoundedString<MAX> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::new(s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "std")]
 impl<const MAX: usize> TryFrom<Uri> for BoundedString<MAX> {
     type Error = IppParseError;
     fn try_from(u: Uri) -> Result<Self, Self::Error> {
@@ -170,7 +225,8 @@ impl<const MAX: usize> TryFrom<Uri> for BoundedString<MAX> {
 /// - 256–1023 bytes
 ///
 /// This enum selects the smallest valid representation automatically.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Equality and hashing only consider the text, so equal strings stored in different tiers are the same value.
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IppTextValue {
     Short(IppShortString),
@@ -198,6 +254,20 @@ impl IppTextValue {
     }
 }
 
+impl PartialEq for IppTextValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl Eq for IppTextValue {}
+
+impl Hash for IppTextValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state);
+    }
+}
+
 impl From<IppShortString> for IppTextValue {
     fn from(value: IppShortString) -> Self {
         Self::Short(value)
@@ -261,6 +331,115 @@ impl fmt::Display for IppTextValue {
     }
 }
 
+/// Members of an IPP collection value.
+///
+/// Unlike a map, the members keep the order in which they were inserted or received from the wire,
+/// so that a parsed collection is serialized back byte-for-byte.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IppCollection {
+    members: Vec<(IppName, IppValue)>,
+}
+
+impl IppCollection {
+    /// Create an empty collection
+    pub fn new() -> IppCollection {
+        IppCollection::default()
+    }
+
+    /// Return the number of members
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Check if the collection has no members
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Return the value of a member with a given name
+    pub fn get(&self, name: &str) -> Option<&IppValue> {
+        self.members.iter().find(|(k, _)| k.as_str() == name).map(|(_, v)| v)
+    }
+
+    /// Return the mutable value of a member with a given name
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut IppValue> {
+        self.members
+            .iter_mut()
+            .find(|(k, _)| k.as_str() == name)
+            .map(|(_, v)| v)
+    }
+
+    /// Check if the collection has a member with a given name
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Add a member to the end of the collection.
+    /// If a member with the same name exists its value is replaced in place and the old value is returned.
+    pub fn insert(&mut self, name: IppName, value: IppValue) -> Option<IppValue> {
+        match self.get_mut(&name) {
+            Some(existing) => Some(core::mem::replace(existing, value)),
+            None => {
+                self.members.push((name, value));
+                None
+            }
+        }
+    }
+
+    /// Remove a member with a given name, preserving the order of the remaining members
+    pub fn remove(&mut self, name: &str) -> Option<IppValue> {
+        let index = self.members.iter().position(|(k, _)| k.as_str() == name)?;
+        Some(self.members.remove(index).1)
+    }
+
+    /// Iterate over the members in order
+    pub fn iter(&self) -> impl Iterator<Item = (&IppName, &IppValue)> {
+        self.members.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Iterate over the member names in order
+    pub fn keys(&self) -> impl Iterator<Item = &IppName> {
+        self.members.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over the member values in order
+    pub fn values(&self) -> impl Iterator<Item = &IppValue> {
+        self.members.iter().map(|(_, v)| v)
+    }
+}
+
+impl From<BTreeMap<IppName, IppValue>> for IppCollection {
+    fn from(map: BTreeMap<IppName, IppValue>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl FromIterator<(IppName, IppValue)> for IppCollection {
+    fn from_iter<T: IntoIterator<Item = (IppName, IppValue)>>(iter: T) -> Self {
+        let mut collection = IppCollection::new();
+        collection.extend(iter);
+        collection
+    }
+}
+
+impl Extend<(IppName, IppValue)> for IppCollection {
+    fn extend<T: IntoIterator<Item = (IppName, IppValue)>>(&mut self, iter: T) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+impl IntoIterator for IppCollection {
+    type Item = (IppName, IppValue);
+    type IntoIter = alloc::vec::IntoIter<(IppName, IppValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.members.into_iter()
+    }
+}
+
 #[inline]
 fn get_len_string(data: &mut Bytes) -> String {
     let len = data.get_u16() as usize;
@@ -269,6 +448,13 @@ fn get_len_string(data: &mut Bytes) -> String {
     s
 }
 
+// keyword syntax as defined in RFC 8011 section 5.1.4
+pub(crate) fn is_keyword(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'))
+}
+
 /// IPP attribute values as defined in [RFC 8010](https://tools.ietf.org/html/rfc8010)
 /// the length for TextWithoutLanguage, TextWithLanguage, and OctetString values is heavily attribute dependant
 /// usual values are 127, 255, and 1023 however as these are attribute dependent, a [`IppTextValue`] is used to allow the calling routine to assert expected text length.
@@ -298,8 +484,16 @@ pub enum IppValue {
     },
     Boolean(bool),
     Keyword(IppKeyword),
+    #[cfg_attr(
+        all(feature = "serde", feature = "std"),
+        serde(deserialize_with = "nesting::deserialize")
+    )]
     Array(Vec<IppValue>),
-    Collection(BTreeMap<IppName, IppValue>),
+    #[cfg_attr(
+        all(feature = "serde", feature = "std"),
+        serde(deserialize_with = "nesting::deserialize")
+    )]
+    Collection(IppCollection),
     MimeMediaType(IppMimeMediaType),
     DateTime {
         year: u16,
@@ -319,13 +513,97 @@ pub enum IppValue {
         feed: i32,
         units: i8,
     },
+    /// Out-of-band `unsupported` value, e.g. in the unsupported attributes group of a response
+    Unsupported,
+    /// Out-of-band `unknown` value, used when the value of a supported attribute is unknown
+    Unknown,
+    /// Out-of-band `no-value` value, used when a supported attribute has no value
     NoValue,
     Other {
         tag: u8,
+        #[cfg_attr(feature = "serde", serde(with = "base64_bytes"))]
         data: Bytes,
     },
 }
 
+// Serialize raw data as a base64 string in human-readable formats such as JSON, and as bytes otherwise
+#[cfg(feature = "serde")]
+mod base64_bytes {
+    use alloc::string::String;
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use bytes::Bytes;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(data: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(data))
+        } else {
+            serializer.serialize_bytes(data)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+        if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            STANDARD.decode(encoded).map(Bytes::from).map_err(D::Error::custom)
+        } else {
+            Bytes::deserialize(deserializer)
+        }
+    }
+}
+
+/// Default maximum nesting depth of arrays and collections when deserializing with serde
+#[cfg(all(feature = "serde", feature = "std"))]
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 32;
+
+/// Set the maximum nesting depth of arrays and collections accepted when deserializing an [`IppValue`]
+/// with serde, e.g. from untrusted JSON. Deeper input is rejected with an error instead of exhausting the stack.
+/// The limit applies to all threads, the default is [`DEFAULT_MAX_NESTING_DEPTH`].
+#[cfg(all(feature = "serde", feature = "std"))]
+pub fn set_max_nesting_depth(depth: usize) {
+    nesting::MAX_DEPTH.store(depth, core::sync::atomic::Ordering::Relaxed);
+}
+
+// Track the nesting depth of arrays and collections while deserializing, per thread
+#[cfg(all(feature = "serde", feature = "std"))]
+mod nesting {
+    use core::{
+        cell::Cell,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use serde::{Deserialize, Deserializer, de::Error};
+
+    pub static MAX_DEPTH: AtomicUsize = AtomicUsize::new(super::DEFAULT_MAX_NESTING_DEPTH);
+
+    ::std::thread_local! {
+        static DEPTH: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            DEPTH.with(|depth| depth.set(depth.get() - 1));
+        }
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let max = MAX_DEPTH.load(Ordering::Relaxed);
+        let depth = DEPTH.with(|depth| depth.get());
+        if depth >= max {
+            return Err(D::Error::custom(format_args!("nesting depth exceeds {max}")));
+        }
+        DEPTH.with(|d| d.set(depth + 1));
+        let _guard = Guard;
+        T::deserialize(deserializer)
+    }
+}
+
 impl IppValue {
     /// Convert to binary tag
     pub fn to_tag(&self) -> u8 {
@@ -345,16 +623,205 @@ impl IppValue {
             IppValue::Uri(_) => ValueTag::Uri as u8,
             IppValue::UriScheme(_) => ValueTag::UriScheme as u8,
             IppValue::MimeMediaType(_) => ValueTag::MimeMediaType as u8,
-            IppValue::Array(ref array) => array.first().map(|v| v.to_tag()).unwrap_or(ValueTag::Unknown as u8),
+            // an empty set cannot be encoded, it is sent as an out-of-band no-value instead
+            // the syntax of a set is given by its values, out-of-band values are skipped
+            IppValue::Array(ref array) => array
+                .iter()
+                .find(|v| !v.is_out_of_band())
+                .or(array.first())
+                .map(|v| v.to_tag())
+                .unwrap_or(ValueTag::NoValue as u8),
             IppValue::Collection(_) => ValueTag::BegCollection as u8,
             IppValue::DateTime { .. } => ValueTag::DateTime as u8,
             IppValue::MemberAttrName(_) => ValueTag::MemberAttrName as u8,
             IppValue::Resolution { .. } => ValueTag::Resolution as u8,
             IppValue::Other { tag, .. } => tag,
+            IppValue::Unsupported => ValueTag::Unsupported as u8,
+            IppValue::Unknown => ValueTag::Unknown as u8,
             IppValue::NoValue => ValueTag::NoValue as u8,
         }
     }
 
+    #[cfg(feature = "std")]
+    /// Create a `uri` value from [`http::Uri`]. Returns an error if the URI is longer than 1023 bytes.
+    pub fn uri_from(uri: Uri) -> Result<IppValue, IppParseError> {
+        Ok(IppValue::Uri(uri.try_into()?))
+    }
+
+    #[cfg(feature = "std")]
+    /// Convert a `uri` value to [`http::Uri`]. The scheme and port are kept as is,
+    /// use [`crate::util::normalize_printer_uri`] to get the HTTP URI of an `ipp` or `ipps` printer URI.
+    /// Returns [`IppParseError::InvalidUri`] if this is not a `uri` value or it cannot be parsed.
+    pub fn as_http_uri(&self) -> Result<Uri, IppParseError> {
+        match self {
+            IppValue::Uri(uri) => uri.parse().map_err(|_| IppParseError::InvalidUri(uri.to_string())),
+            other => Err(IppParseError::InvalidUri(other.to_string())),
+        }
+    }
+
+    /// Check if an integer is matched by this value, e.g. whether a requested `copies` value
+    /// is allowed by `copies-supported`.
+    /// Integer and enum values match if equal, ranges match if the integer is within the bounds,
+    /// and arrays match if any of the elements does.
+    pub fn contains_integer(&self, n: i32) -> bool {
+        match *self {
+            IppValue::Integer(i) | IppValue::Enum(i) => i == n,
+            IppValue::RangeOfInteger { min, max } => (min..=max).contains(&n),
+            IppValue::Array(ref list) => list.iter().any(|v| v.contains_integer(n)),
+            _ => false,
+        }
+    }
+
+    /// Create a `rangeOfInteger` value from a single range or `1setOf rangeOfInteger` from multiple ranges,
+    /// e.g. for `page-ranges`. Every range must have the lower bound not greater than the upper bound.
+    /// Returns [`IppParseError::EmptyArray`] if no ranges are given.
+    pub fn from_ranges(ranges: &[(i32, i32)]) -> Result<IppValue, IppParseError> {
+        if ranges.is_empty() {
+            return Err(IppParseError::EmptyArray);
+        }
+        let mut values = ranges
+            .iter()
+            .map(|&(min, max)| {
+                if min <= max {
+                    Ok(IppValue::RangeOfInteger { min, max })
+                } else {
+                    Err(IppParseError::InvalidRange { min, max })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if values.len() == 1 {
+            Ok(values.remove(0))
+        } else {
+            Ok(IppValue::Array(values))
+        }
+    }
+
+    /// Return the ranges of a `rangeOfInteger` or `1setOf rangeOfInteger` value.
+    /// Returns `None` if the value holds anything else or a range with the lower bound greater than the upper bound.
+    pub fn as_ranges(&self) -> Option<Vec<(i32, i32)>> {
+        self.into_iter()
+            .map(|value| match *value {
+                IppValue::RangeOfInteger { min, max } if min <= max => Some((min, max)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Check if this value is one of the out-of-band values `unsupported`, `unknown` or `no-value`
+    pub fn is_out_of_band(&self) -> bool {
+        matches!(self, IppValue::Unsupported | IppValue::Unknown | IppValue::NoValue)
+    }
+
+    // Tag written in front of the encoded value: every value of a set is preceded by its own tag,
+    // so for arrays this is the tag of the first value rather than the syntax of the set
+    pub(crate) fn wire_tag(&self) -> u8 {
+        match *self {
+            IppValue::Array(ref array) => array.first().map_or(ValueTag::NoValue as u8, |v| v.wire_tag()),
+            _ => self.to_tag(),
+        }
+    }
+
+    /// Check if this value holds multiple values, i.e. it is an array
+    pub fn is_multivalue(&self) -> bool {
+        self.is_array()
+    }
+
+    /// Return the number of values: the number of elements for arrays, the number of members for collections
+    /// and 1 for all other values
+    pub fn value_count(&self) -> usize {
+        match *self {
+            IppValue::Array(ref array) => array.len(),
+            IppValue::Collection(ref coll) => coll.len(),
+            _ => 1,
+        }
+    }
+
+    /// Return the value of a collection member, or `None` if this value is not a collection
+    pub fn collection_get(&self, member: &str) -> Option<&IppValue> {
+        self.as_collection()?.get(member)
+    }
+
+    /// Add or replace a collection member, returning the old value. Does nothing for other values.
+    pub fn collection_insert(&mut self, member: IppName, value: IppValue) -> Option<IppValue> {
+        self.as_collection_mut()?.insert(member, value)
+    }
+
+    /// Return the value of a nested collection member given by a slash-separated path of member names,
+    /// e.g. `media-size/x-dimension` for the `media-col` value.
+    /// Returns `None` if a member is missing or a value on the path is not a collection.
+    pub fn get_path(&self, path: &str) -> Option<&IppValue> {
+        path.split('/')
+            .try_fold(self, |value, member| value.collection_get(member))
+    }
+
+    /// Alias of [`IppValue::get_path`], kept for code written against the collection member helpers
+    pub fn collection_path(&self, path: &str) -> Option<&IppValue> {
+        self.get_path(path)
+    }
+
+    /// Return an adapter which displays this value with control characters escaped, e.g. `\n` instead of
+    /// a line break. Use it when printing values received from a printer to a terminal or a log.
+    pub fn escaped(&self) -> EscapedValue<'_> {
+        EscapedValue(self)
+    }
+
+    /// Return an adapter which displays this value in the format used by `ipptool`: multiple values are
+    /// separated by commas without brackets, collections are enclosed in braces, e.g. `one-sided,two-sided-long-edge`.
+    /// Useful for comparing the output with the reference tooling.
+    pub fn ipptool_display(&self) -> IpptoolDisplay<'_> {
+        IpptoolDisplay(self)
+    }
+
+    /// Format a `dateTime` value as an RFC 3339 timestamp with deci-second precision, e.g. `2020-02-13T12:34:22.1+01:30`.
+    /// Returns `None` for other value types.
+    pub fn to_rfc3339(&self) -> Option<String> {
+        match *self {
+            IppValue::DateTime {
+                year,
+                month,
+                day,
+                hour,
+                minutes,
+                seconds,
+                deci_seconds,
+                utc_dir,
+                utc_hours,
+                utc_mins,
+            } => Some(format!(
+                "{year:04}-{month:02}-{day:02}T{hour:02}:{minutes:02}:{seconds:02}.{deci_seconds}{}{utc_hours:02}:{utc_mins:02}",
+                if utc_dir == '-' { '-' } else { '+' }
+            )),
+            _ => None,
+        }
+    }
+
+    /// Create a transformed copy of this value.
+    /// The closure is called for this value first: when it returns a replacement, the replacement is used as is,
+    /// otherwise the value is kept and the closure is applied to every element of an array
+    /// or member of a collection, recursively.
+    pub fn map<F>(&self, mut f: F) -> IppValue
+    where
+        F: FnMut(&IppValue) -> Option<IppValue>,
+    {
+        self.map_with(&mut f)
+    }
+
+    fn map_with<F>(&self, f: &mut F) -> IppValue
+    where
+        F: FnMut(&IppValue) -> Option<IppValue>,
+    {
+        if let Some(value) = f(self) {
+            return value;
+        }
+        match self {
+            IppValue::Array(list) => IppValue::Array(list.iter().map(|v| v.map_with(f)).collect()),
+            IppValue::Collection(coll) => {
+                IppValue::Collection(coll.iter().map(|(k, v)| (k.clone(), v.map_with(f))).collect())
+            }
+            other => other.clone(),
+        }
+    }
+
     /// Parse value from a byte array which does not include the value length field
     pub fn parse(value_tag: u8, mut data: Bytes) -> Result<IppValue, IppParseError> {
         let ipp_tag = match ValueTag::from_u8(value_tag) {
@@ -367,6 +834,10 @@ impl IppValue {
         let value = match ipp_tag {
             ValueTag::Integer => IppValue::Integer(data.get_i32()),
             ValueTag::Enum => IppValue::Enum(data.get_i32()),
+            // octetString values which do not fit into a text value are kept as raw data
+            ValueTag::OctetStringUnspecified if data.len() > IppString::max() => {
+                IppValue::Other { tag: value_tag, data }
+            }
             ValueTag::OctetStringUnspecified => IppValue::OctetString(String::from_utf8_lossy(&data).try_into()?),
             ValueTag::TextWithoutLanguage => IppValue::TextWithoutLanguage(String::from_utf8_lossy(&data).try_into()?),
             ValueTag::NameWithoutLanguage => IppValue::NameWithoutLanguage(String::from_utf8_lossy(&data).try_into()?),
@@ -407,12 +878,100 @@ impl IppValue {
                 feed: data.get_i32(),
                 units: data.get_i8(),
             },
+            ValueTag::Unsupported => IppValue::Unsupported,
+            ValueTag::Unknown => IppValue::Unknown,
             ValueTag::NoValue => IppValue::NoValue,
             _ => IppValue::Other { tag: value_tag, data },
         };
         Ok(value)
     }
 
+    /// Human-readable name of the value tag, e.g. `keyword` or `textWithoutLanguage`.
+    /// Arrays are named after their first value which is not out-of-band, unregistered tags are named `extension`.
+    pub fn tag_name(&self) -> &'static str {
+        ValueTag::from_u8(self.to_tag()).map_or("extension", |tag| tag.name())
+    }
+
+    /// Return the number of bytes produced by [`IppValue::to_bytes`] without serializing the value
+    pub fn encoded_len(&self) -> usize {
+        match *self {
+            IppValue::Integer(_) | IppValue::Enum(_) => 2 + 4,
+            IppValue::RangeOfInteger { .. } => 2 + 8,
+            IppValue::Boolean(_) => 2 + 1,
+            IppValue::Keyword(ref s) | IppValue::NameWithoutLanguage(ref s) => 2 + s.len(),
+            IppValue::OctetString(ref s) | IppValue::TextWithoutLanguage(ref s) => 2 + s.len(),
+            IppValue::Charset(ref s) | IppValue::NaturalLanguage(ref s) => 2 + s.len(),
+            IppValue::Uri(ref s) | IppValue::UriScheme(ref s) => 2 + s.len(),
+            IppValue::MimeMediaType(ref s) | IppValue::MemberAttrName(ref s) => 2 + s.len(),
+            IppValue::TextWithLanguage { ref language, ref text } => 2 + 4 + language.len() + text.len(),
+            IppValue::NameWithLanguage { ref language, ref name } => 2 + 4 + language.len() + name.len(),
+            IppValue::Array(ref list) if list.is_empty() => 2,
+            IppValue::Array(ref list) => {
+                // every value except the first one is preceded by a tag and an empty name
+                list.iter().map(|v| v.encoded_len()).sum::<usize>() + list.len().saturating_sub(1) * 3
+            }
+            IppValue::Collection(ref list) => {
+                let members = list
+                    .iter()
+                    .map(|(k, v)| (3 + 2 + k.len()) + (3 + v.encoded_len()))
+                    .sum::<usize>();
+                // begin collection value length, members, end collection tag with empty name and value
+                2 + members + 5
+            }
+            IppValue::DateTime { .. } => 2 + 11,
+            IppValue::Resolution { .. } => 2 + 9,
+            IppValue::Unsupported | IppValue::Unknown | IppValue::NoValue => 2,
+            IppValue::Other { ref data, .. } => {
+                // every fragment after the first one is preceded by a tag and an empty name
+                let fragments = data.len().div_ceil(MAX_VALUE_LENGTH).max(1);
+                data.len() + fragments * 2 + (fragments - 1) * 3
+            }
+        }
+    }
+
+    /// Check that the names of all collection members, including nested collections and collections in arrays,
+    /// are valid keywords: a lowercase letter followed by lowercase letters, digits, '-', '_' or '.'
+    pub fn validate_member_names(&self) -> Result<(), IppParseError> {
+        match *self {
+            IppValue::Array(ref list) => list.iter().try_for_each(|v| v.validate_member_names()),
+            IppValue::Collection(ref coll) => coll.iter().try_for_each(|(name, value)| {
+                if !is_keyword(name) {
+                    return Err(IppParseError::InvalidMemberName(String::from(name.as_str())));
+                }
+                value.validate_member_names()
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that the value can be encoded as is: collection member names are valid keywords, there are
+    /// no empty arrays, which [`IppValue::to_bytes`] would silently send as no-value, and raw values longer than
+    /// 65535 bytes have the octetString tag, the only one for which the parser joins the fragments back
+    pub fn validate(&self) -> Result<(), IppParseError> {
+        match *self {
+            IppValue::Other { tag, ref data }
+                if data.len() > MAX_VALUE_LENGTH && tag != ValueTag::OctetStringUnspecified as u8 =>
+            {
+                Err(IppParseError::ValueTooLong { tag, len: data.len() })
+            }
+            IppValue::Array(ref list) if list.is_empty() => Err(IppParseError::EmptyArray),
+            IppValue::Array(ref list) => list.iter().try_for_each(|v| v.validate()),
+            IppValue::Collection(ref coll) => coll.iter().try_for_each(|(name, value)| {
+                if !is_keyword(name) {
+                    return Err(IppParseError::InvalidMemberName(String::from(name.as_str())));
+                }
+                value.validate()
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Write value to byte array like [`IppValue::to_bytes`] after checking it with [`IppValue::validate`]
+    pub fn try_to_bytes(&self) -> Result<Bytes, IppParseError> {
+        self.validate()?;
+        Ok(self.to_bytes())
+    }
+
     /// Write value to byte array, including leading value length field, excluding value tag
     pub fn to_bytes(&self) -> Bytes {
         let mut buffer = BytesMut::new();
@@ -470,13 +1029,18 @@ impl IppValue {
                 buffer.put_u16(name.len() as u16);
                 buffer.put_slice(name.as_bytes());
             }
+            IppValue::Array(ref list) if list.is_empty() => {
+                // encoded as no-value, see to_tag
+                buffer.put_u16(0);
+            }
             IppValue::Array(ref list) => {
                 for (i, item) in list.iter().enumerate() {
-                    buffer.put(item.to_bytes());
-                    if i < list.len() - 1 {
-                        buffer.put_u8(self.to_tag());
+                    if i > 0 {
+                        // additional value: own tag and an empty name
+                        buffer.put_u8(item.wire_tag());
                         buffer.put_u16(0);
                     }
+                    buffer.put(item.to_bytes());
                 }
             }
             IppValue::Collection(ref list) => {
@@ -493,7 +1057,7 @@ impl IppValue {
                     buffer.put(atr_name.to_bytes());
 
                     // item tag
-                    buffer.put_u8(item.1.to_tag());
+                    buffer.put_u8(item.1.wire_tag());
                     // name size is zero, this is a collection
                     buffer.put_u16(0);
 
@@ -537,7 +1101,19 @@ impl IppValue {
                 buffer.put_i32(feed);
                 buffer.put_u8(units as u8);
             }
-            IppValue::NoValue => buffer.put_u16(0),
+            IppValue::Unsupported | IppValue::Unknown | IppValue::NoValue => buffer.put_u16(0),
+            IppValue::Other { tag, ref data } if data.len() > MAX_VALUE_LENGTH => {
+                // the value does not fit into the 16-bit length field, it is split into fragments of the same tag
+                // without a name, the reverse of how the parser joins octetString fragments
+                for (i, fragment) in data.chunks(MAX_VALUE_LENGTH).enumerate() {
+                    if i > 0 {
+                        buffer.put_u8(tag);
+                        buffer.put_u16(0);
+                    }
+                    buffer.put_u16(fragment.len() as u16);
+                    buffer.put_slice(fragment);
+                }
+            }
             IppValue::Other { ref data, .. } => {
                 buffer.put_u16(data.len() as u16);
                 buffer.put_slice(data);
@@ -590,7 +1166,7 @@ impl fmt::Display for IppValue {
                 ..
             } => write!(
                 f,
-                "{year}-{month}-{day},{hour}:{minutes}:{seconds}.{deci_seconds},{utc_dir}{utc_hours}utc"
+                "{year}-{month:02}-{day:02},{hour:02}:{minutes:02}:{seconds:02}.{deci_seconds},{utc_dir}{utc_hours}utc"
             ),
             IppValue::Resolution {
                 cross_feed,
@@ -600,12 +1176,89 @@ impl fmt::Display for IppValue {
                 write!(f, "{cross_feed}x{feed}{}", if units == 3 { "in" } else { "cm" })
             }
 
-            IppValue::NoValue => Ok(()),
+            IppValue::Unsupported | IppValue::Unknown | IppValue::NoValue => Ok(()),
             IppValue::Other { tag, ref data } => write!(f, "{tag:0x}: {data:?}"),
         }
     }
 }
 
+/// Display adapter for [`IppValue`] which escapes control characters in text, name and octetString values,
+/// created by [`IppValue::escaped`]
+pub struct EscapedValue<'a>(&'a IppValue);
+
+fn write_escaped(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        if c.is_control() {
+            write!(f, "{}", c.escape_default())?;
+        } else {
+            fmt::Write::write_char(f, c)?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for EscapedValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            IppValue::NameWithoutLanguage(s) => write_escaped(f, s),
+            IppValue::OctetString(s) | IppValue::TextWithoutLanguage(s) => write_escaped(f, s),
+            IppValue::TextWithLanguage { language, text } => {
+                write!(f, "{language}:")?;
+                write_escaped(f, text)
+            }
+            IppValue::NameWithLanguage { language, name } => {
+                write!(f, "{language}:")?;
+                write_escaped(f, name)
+            }
+            IppValue::Array(array) => {
+                let s: Vec<String> = array.iter().map(|v| format!("{}", v.escaped())).collect();
+                write!(f, "[{}]", s.join(", "))
+            }
+            IppValue::Collection(coll) => {
+                let s: Vec<String> = coll.iter().map(|(k, v)| format!("{k}={}", v.escaped())).collect();
+                write!(f, "<{}>", s.join(", "))
+            }
+            other => write!(f, "{other}"),
+        }
+    }
+}
+
+/// Display adapter for [`IppValue`] which formats values like the `ipptool` utility from CUPS,
+/// created by [`IppValue::ipptool_display`]
+pub struct IpptoolDisplay<'a>(&'a IppValue);
+
+impl fmt::Display for IpptoolDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            IppValue::RangeOfInteger { min, max } => write!(f, "{min}-{max}"),
+            IppValue::Resolution {
+                cross_feed,
+                feed,
+                units,
+            } => {
+                let units = if units == 3 { "dpi" } else { "dpcm" };
+                if cross_feed == feed {
+                    write!(f, "{cross_feed}{units}")
+                } else {
+                    write!(f, "{cross_feed}x{feed}{units}")
+                }
+            }
+            IppValue::Array(ref array) => {
+                let s: Vec<String> = array.iter().map(|v| format!("{}", v.ipptool_display())).collect();
+                write!(f, "{}", s.join(","))
+            }
+            IppValue::Collection(ref coll) => {
+                let s: Vec<String> = coll
+                    .iter()
+                    .map(|(k, v)| format!("{k}={}", v.ipptool_display()))
+                    .collect();
+                write!(f, "{{{}}}", s.join(" "))
+            }
+            ref other => write!(f, "{other}"),
+        }
+    }
+}
+
 impl FromStr for IppValue {
     type Err = IppParseError;
 
@@ -652,10 +1305,10 @@ impl<'a> Iterator for IppValueIterator<'a> {
                     None
                 }
             }
-            IppValue::Collection(map) => {
-                if let Some(entry) = map.iter().nth(self.index) {
+            IppValue::Collection(coll) => {
+                if let Some(value) = coll.values().nth(self.index) {
                     self.index += 1;
-                    Some(entry.1)
+                    Some(value)
                 } else {
                     None
                 }
@@ -672,9 +1325,8 @@ impl<'a> Iterator for IppValueIterator<'a> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    use std::collections::BTreeMap;
     use std::io;
 
     use crate::attribute::IppAttribute;
@@ -759,6 +1411,8 @@ mod tests {
             feed: 600,
             units: 2,
         });
+        value_check(IppValue::Unsupported);
+        value_check(IppValue::Unknown);
         value_check(IppValue::NoValue);
         value_check(IppValue::Other {
             tag: 123,
@@ -766,6 +1420,55 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_value_encoded_len() {
+        let values = [
+            IppValue::Integer(1234),
+            IppValue::RangeOfInteger { min: 1, max: 99 },
+            IppValue::Boolean(true),
+            IppValue::Keyword("keyword".try_into().unwrap()),
+            IppValue::TextWithLanguage {
+                language: "en".try_into().unwrap(),
+                text: "text-with".try_into().unwrap(),
+            },
+            IppValue::Array(vec![IppValue::Integer(1), IppValue::Integer(2), IppValue::Integer(3)]),
+            IppValue::Array(vec![]),
+            IppValue::Collection(IppCollection::from_iter([
+                ("abcd".try_into().unwrap(), IppValue::Integer(1)),
+                (
+                    "list".try_into().unwrap(),
+                    IppValue::Array(vec![IppValue::Integer(1), IppValue::Integer(2)]),
+                ),
+            ])),
+            IppValue::DateTime {
+                year: 2020,
+                month: 2,
+                day: 13,
+                hour: 12,
+                minutes: 34,
+                seconds: 22,
+                deci_seconds: 1,
+                utc_dir: '+',
+                utc_hours: 1,
+                utc_mins: 30,
+            },
+            IppValue::Resolution {
+                cross_feed: 600,
+                feed: 600,
+                units: 3,
+            },
+            IppValue::NoValue,
+            IppValue::Other {
+                tag: 123,
+                data: "foo".into(),
+            },
+        ];
+
+        for value in values {
+            assert_eq!(value.encoded_len(), value.to_bytes().len(), "{value:?}");
+        }
+    }
+
     #[test]
     fn test_value_iterator_single() {
         let val = IppValue::Integer(1234);
@@ -822,11 +1525,130 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_empty_array_round_trip() {
+        let attr = IppAttribute::with_name("job-state-reasons", IppValue::Array(vec![])).unwrap();
+        let buf = attr.to_bytes();
+        assert_eq!(
+            buf,
+            [&[ValueTag::NoValue as u8, 0, 17][..], b"job-state-reasons", &[0, 0]].concat()
+        );
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(buf);
+        data.push(3);
+        let result = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attrs = result
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap()
+            .attributes();
+        assert_eq!(attrs["job-state-reasons"].value(), &IppValue::NoValue);
+    }
+
+    #[test]
+    fn test_mixed_out_of_band_array_round_trip() {
+        let keyword = |s: &str| IppValue::Keyword(s.try_into().unwrap());
+        let value = IppValue::Array(vec![IppValue::Unsupported, keyword("a4"), keyword("letter")]);
+        assert_eq!(value.to_tag(), ValueTag::Keyword as u8);
+
+        let attr = IppAttribute::with_name("media", value.clone()).unwrap();
+        let buf = attr.to_bytes();
+        assert_eq!(
+            buf,
+            [
+                &[ValueTag::Unsupported as u8, 0, 5][..],
+                b"media",
+                &[0, 0, ValueTag::Keyword as u8, 0, 0, 0, 2],
+                b"a4",
+                &[ValueTag::Keyword as u8, 0, 0, 0, 6],
+                b"letter",
+            ]
+            .concat()
+        );
+        assert_eq!(buf.len(), attr.encoded_len());
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(buf);
+        data.push(3);
+        let result = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attrs = result
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap()
+            .attributes();
+        assert_eq!(attrs["media"].value(), &value);
+    }
+
+    #[test]
+    fn test_tag_name() {
+        assert_eq!(IppValue::Integer(1).tag_name(), "integer");
+        assert_eq!(IppValue::Enum(3).tag_name(), "enum");
+        assert_eq!(IppValue::Keyword("one-sided".try_into().unwrap()).tag_name(), "keyword");
+        assert_eq!(
+            IppValue::TextWithoutLanguage("text".try_into().unwrap()).tag_name(),
+            "textWithoutLanguage"
+        );
+        assert_eq!(IppValue::Collection(IppCollection::new()).tag_name(), "begCollection");
+        assert_eq!(IppValue::Array(vec![IppValue::Boolean(true)]).tag_name(), "boolean");
+        assert_eq!(IppValue::Array(vec![]).tag_name(), "no-value");
+        assert_eq!(
+            IppValue::Other {
+                tag: 0x7f,
+                data: Bytes::new()
+            }
+            .tag_name(),
+            "extension"
+        );
+    }
+
+    #[test]
+    fn test_empty_array_strict() {
+        let empty = IppValue::Array(vec![]);
+        assert_eq!(empty.to_bytes(), vec![0, 0]);
+        assert!(matches!(empty.try_to_bytes(), Err(IppParseError::EmptyArray)));
+
+        let nested = IppValue::Collection(IppCollection::from_iter([(
+            "media-source-properties".try_into().unwrap(),
+            empty,
+        )]));
+        assert!(matches!(nested.validate(), Err(IppParseError::EmptyArray)));
+        assert!(nested.validate_member_names().is_ok());
+
+        let attr = IppAttribute::with_name("media-col", nested).unwrap();
+        assert!(matches!(attr.try_to_bytes(), Err(IppParseError::EmptyArray)));
+    }
+
+    #[test]
+    fn test_empty_collection_round_trip() {
+        let value = IppValue::Collection(IppCollection::new());
+        let attr = IppAttribute::with_name("media-col", value.clone()).unwrap();
+        let buf = attr.to_bytes();
+        assert_eq!(
+            buf,
+            [&[0x34, 0, 9][..], b"media-col", &[0, 0, 0x37, 0, 0, 0, 0]].concat()
+        );
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 4];
+        data.extend(buf);
+        data.push(3);
+        let result = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let attrs = result
+            .attributes()
+            .groups_of(DelimiterTag::PrinterAttributes)
+            .next()
+            .unwrap()
+            .attributes();
+        assert_eq!(attrs["media-col"].value(), &value);
+    }
+
     #[test]
     fn test_collection() {
         let attr = IppAttribute::new(
             "coll".try_into().unwrap(),
-            IppValue::Collection(BTreeMap::from([(
+            IppValue::Collection(IppCollection::from_iter([(
                 "abcd".try_into().unwrap(),
                 IppValue::Integer(0x2222_2222),
             )])),
@@ -858,10 +1680,574 @@ mod tests {
         let attr = attrs.get("coll").unwrap();
         assert_eq!(
             attr.value(),
-            &IppValue::Collection(BTreeMap::from([(
+            &IppValue::Collection(IppCollection::from_iter([(
                 "abcd".try_into().unwrap(),
                 IppValue::Integer(0x2222_2222)
             )]))
         );
     }
+
+    #[test]
+    fn test_bounded_string_from_bytes() {
+        let s = IppShortString::from_bytes(b"iso_a4_210x297mm").unwrap();
+        assert_eq!(s.as_str(), "iso_a4_210x297mm");
+
+        let result = BoundedString::<4>::from_bytes(b"hello");
+        assert!(matches!(
+            result,
+            Err(IppParseError::InvalidStringLength { len: 5, max: 4 })
+        ));
+
+        let result = IppShortString::from_bytes(b"abc\xff");
+        assert!(matches!(result, Err(IppParseError::InvalidUtf8(_))));
+    }
+
+    #[test]
+    fn test_bounded_string_from_bytes_lossy() {
+        assert_eq!(BoundedString::<4>::from_bytes_lossy(b"hello").as_str(), "hell");
+        assert_eq!(IppShortString::from_bytes_lossy(b"abc\xff").as_str(), "abc\u{fffd}");
+
+        // "é" is two bytes long and must not be split
+        let s = BoundedString::<4>::from_bytes_lossy("abcé".as_bytes());
+        assert_eq!(s.as_str(), "abc");
+
+        // replacement character is three bytes long
+        let s = BoundedString::<5>::from_bytes_lossy(b"abc\xffd");
+        assert_eq!(s.as_str(), "abc");
+    }
+
+    #[test]
+    fn test_bounded_string_expand() {
+        let keyword: IppKeyword = "none".try_into().unwrap();
+        let s: IppString = keyword.expand();
+        assert_eq!(s.as_str(), "none");
+        assert_eq!(s.shrink::<4>().unwrap().as_str(), "none");
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let uri: Uri = "ipps://printer.example.com:631/ipp/print".parse().unwrap();
+        let value = IppValue::uri_from(uri.clone()).unwrap();
+        assert_eq!(
+            value,
+            IppValue::Uri("ipps://printer.example.com:631/ipp/print".try_into().unwrap())
+        );
+        assert_eq!(value.as_http_uri().unwrap(), uri);
+    }
+
+    #[test]
+    fn test_uri_too_long() {
+        let uri: Uri = format!("ipp://printer.example.com/{}", "x".repeat(1024))
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            IppValue::uri_from(uri),
+            Err(IppParseError::InvalidStringLength { max: 1023, .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_uri() {
+        let value = IppValue::Uri("ipp://printer example".try_into().unwrap());
+        assert!(matches!(value.as_http_uri(), Err(IppParseError::InvalidUri(ref s)) if s == "ipp://printer example"));
+        assert!(matches!(
+            IppValue::Integer(1).as_http_uri(),
+            Err(IppParseError::InvalidUri(_))
+        ));
+    }
+
+    #[test]
+    fn test_collection_insert_keeps_order() {
+        let mut coll = IppCollection::new();
+        coll.insert("b".try_into().unwrap(), IppValue::Integer(1));
+        coll.insert("a".try_into().unwrap(), IppValue::Integer(2));
+        assert_eq!(
+            coll.insert("b".try_into().unwrap(), IppValue::Integer(3)),
+            Some(IppValue::Integer(1))
+        );
+        assert_eq!(coll.keys().map(|k| k.as_str()).collect::<Vec<_>>(), vec!["b", "a"]);
+        assert_eq!(coll.get("b"), Some(&IppValue::Integer(3)));
+
+        assert_eq!(coll.remove("b"), Some(IppValue::Integer(3)));
+        assert_eq!(coll.len(), 1);
+        assert!(!coll.contains_key("b"));
+    }
+
+    #[test]
+    fn test_contains_integer() {
+        assert!(IppValue::Integer(1).contains_integer(1));
+        assert!(!IppValue::Integer(1).contains_integer(2));
+        assert!(IppValue::Enum(4).contains_integer(4));
+
+        let range = IppValue::RangeOfInteger { min: 1, max: 99 };
+        assert!(range.contains_integer(1));
+        assert!(range.contains_integer(99));
+        assert!(!range.contains_integer(0));
+        assert!(!range.contains_integer(100));
+
+        let set = IppValue::Array(vec![
+            IppValue::Integer(1),
+            IppValue::RangeOfInteger { min: 10, max: 20 },
+        ]);
+        assert!(set.contains_integer(1));
+        assert!(set.contains_integer(15));
+        assert!(!set.contains_integer(5));
+
+        assert!(!IppValue::Keyword("1".try_into().unwrap()).contains_integer(1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_other_base64() {
+        let value = IppValue::Other {
+            tag: ValueTag::OctetStringUnspecified as u8,
+            data: Bytes::from_static(&[0x00, 0xff, 0x10, 0x80]),
+        };
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, serde_json::json!({"Other": {"tag": 0x30, "data": "AP8QgA=="}}));
+
+        let decoded: IppValue = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, value);
+
+        let invalid = serde_json::json!({"Other": {"tag": 0x30, "data": "not base64!"}});
+        assert!(serde_json::from_value::<IppValue>(invalid).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_bounded_string_serde() {
+        let keyword: IppKeyword = "one-sided".try_into().unwrap();
+        let json = serde_json::to_value(&keyword).unwrap();
+        assert_eq!(json, serde_json::json!("one-sided"));
+        assert_eq!(serde_json::from_value::<IppKeyword>(json).unwrap(), keyword);
+
+        let err = serde_json::from_value::<IppKeyword>(serde_json::json!("x".repeat(256))).unwrap_err();
+        assert_eq!(err.to_string(), "invalid string length: 256, max: 255");
+
+        let value = serde_json::json!({"Keyword": "x".repeat(256)});
+        assert!(serde_json::from_value::<IppValue>(value).is_err());
+    }
+
+    // JSON shape of values and attributes as published by gateways, described by the `schemars` feature
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json_shape() {
+        let value = IppValue::TextWithLanguage {
+            language: "en".try_into().unwrap(),
+            text: IppTextValue::new("hello").unwrap(),
+        };
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"TextWithLanguage": {"language": "en", "text": {"Short": "hello"}}})
+        );
+        assert_eq!(serde_json::from_value::<IppValue>(json).unwrap(), value);
+
+        let attr = crate::attribute::IppAttribute::with_name("copies", IppValue::Integer(2)).unwrap();
+        let json = serde_json::to_value(&attr).unwrap();
+        assert_eq!(json, serde_json::json!({"name": "copies", "value": {"Integer": 2}}));
+    }
+
+    #[test]
+    fn test_long_other_value() {
+        let data = Bytes::from(vec![7u8; 2 * 65535 + 10]);
+        let value = IppValue::Other {
+            tag: ValueTag::OctetStringUnspecified as u8,
+            data: data.clone(),
+        };
+
+        let bytes = value.to_bytes();
+        assert_eq!(value.encoded_len(), bytes.len());
+        assert_eq!(&bytes[..2], &[0xff, 0xff]);
+        // second fragment: tag, empty name, length
+        assert_eq!(&bytes[2 + 65535..2 + 65535 + 5], &[0x30, 0, 0, 0xff, 0xff]);
+        assert_eq!(&bytes[bytes.len() - 12..bytes.len() - 10], &[0, 10]);
+
+        let value = IppValue::Other { tag: 0x7f, data };
+        assert!(matches!(
+            value.try_to_bytes(),
+            Err(IppParseError::ValueTooLong { tag: 0x7f, len }) if len == 2 * 65535 + 10
+        ));
+
+        let empty = IppValue::Other {
+            tag: 0x7f,
+            data: Bytes::new(),
+        };
+        assert_eq!(empty.encoded_len(), empty.to_bytes().len());
+    }
+
+    #[cfg(all(feature = "serde", feature = "std"))]
+    #[test]
+    fn test_serde_nesting_depth() {
+        let nested = |depth| {
+            (0..depth).fold(
+                serde_json::json!({"Integer": 1}),
+                |inner, _| serde_json::json!({"Array": [inner]}),
+            )
+        };
+
+        assert!(serde_json::from_value::<IppValue>(nested(DEFAULT_MAX_NESTING_DEPTH)).is_ok());
+
+        let err = serde_json::from_value::<IppValue>(nested(DEFAULT_MAX_NESTING_DEPTH + 1)).unwrap_err();
+        assert_eq!(err.to_string(), "nesting depth exceeds 32");
+
+        let collection = serde_json::json!({"Collection": {"members": [["a", nested(DEFAULT_MAX_NESTING_DEPTH)]]}});
+        let err = serde_json::from_value::<IppValue>(collection).unwrap_err();
+        assert_eq!(err.to_string(), "nesting depth exceeds 32");
+
+        // the depth is reset after an error
+        assert!(serde_json::from_value::<IppValue>(nested(2)).is_ok());
+    }
+
+    #[test]
+    fn test_map_rewrites_uris() {
+        let uri = |s: &str| IppValue::Uri(s.try_into().unwrap());
+        let value = IppValue::Array(vec![
+            uri("ipp://internal:631/printers/a"),
+            IppValue::Collection(IppCollection::from_iter([
+                ("xri-uri".try_into().unwrap(), uri("ipps://internal/printers/b")),
+                (
+                    "xri-security".try_into().unwrap(),
+                    IppValue::Keyword("tls".try_into().unwrap()),
+                ),
+                (
+                    "nested".try_into().unwrap(),
+                    IppValue::Array(vec![uri("ipp://internal/printers/c"), IppValue::Integer(5)]),
+                ),
+            ])),
+        ]);
+
+        let mut visited = 0;
+        let mapped = value.map(|v| {
+            visited += 1;
+            v.as_uri().map(|u| uri(&u.replace("internal", "printer.example.com")))
+        });
+
+        assert_eq!(visited, 8);
+        assert_eq!(
+            mapped,
+            IppValue::Array(vec![
+                uri("ipp://printer.example.com:631/printers/a"),
+                IppValue::Collection(IppCollection::from_iter([
+                    (
+                        "xri-uri".try_into().unwrap(),
+                        uri("ipps://printer.example.com/printers/b")
+                    ),
+                    (
+                        "xri-security".try_into().unwrap(),
+                        IppValue::Keyword("tls".try_into().unwrap())
+                    ),
+                    (
+                        "nested".try_into().unwrap(),
+                        IppValue::Array(vec![uri("ipp://printer.example.com/printers/c"), IppValue::Integer(5)]),
+                    ),
+                ])),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_escaped_display() {
+        let value = IppValue::TextWithoutLanguage("Paper jam\n\x1b[31mtray 2".try_into().unwrap());
+        assert_eq!(value.escaped().to_string(), "Paper jam\\n\\u{1b}[31mtray 2");
+        assert_eq!(value.to_string(), "Paper jam\n\x1b[31mtray 2");
+
+        let value = IppValue::Array(vec![
+            IppValue::NameWithLanguage {
+                language: "en".try_into().unwrap(),
+                name: "a\tb".try_into().unwrap(),
+            },
+            IppValue::Collection(IppCollection::from_iter([(
+                "message".try_into().unwrap(),
+                IppValue::TextWithoutLanguage("line\r\n".try_into().unwrap()),
+            )])),
+            IppValue::Integer(5),
+        ]);
+        assert_eq!(value.escaped().to_string(), "[en:a\\tb, <message=line\\r\\n>, 5]");
+    }
+
+    #[test]
+    fn test_ipptool_display() {
+        let value = IppValue::Array(vec![
+            IppValue::Keyword("one-sided".try_into().unwrap()),
+            IppValue::Keyword("two-sided-long-edge".try_into().unwrap()),
+            IppValue::Keyword("two-sided-short-edge".try_into().unwrap()),
+        ]);
+        assert_eq!(
+            value.to_string(),
+            "[one-sided, two-sided-long-edge, two-sided-short-edge]"
+        );
+        assert_eq!(
+            value.ipptool_display().to_string(),
+            "one-sided,two-sided-long-edge,two-sided-short-edge"
+        );
+
+        let value = IppValue::Array(vec![IppValue::Enum(3), IppValue::Enum(4), IppValue::Enum(5)]);
+        assert_eq!(value.ipptool_display().to_string(), "3,4,5");
+
+        let value = IppValue::Collection(IppCollection::from_iter([
+            (
+                "media-size".try_into().unwrap(),
+                IppValue::Collection(IppCollection::from_iter([
+                    ("x-dimension".try_into().unwrap(), IppValue::Integer(21000)),
+                    ("y-dimension".try_into().unwrap(), IppValue::Integer(29700)),
+                ])),
+            ),
+            (
+                "media-source".try_into().unwrap(),
+                IppValue::Keyword("tray-1".try_into().unwrap()),
+            ),
+        ]));
+        assert_eq!(
+            value.ipptool_display().to_string(),
+            "{media-size={x-dimension=21000 y-dimension=29700} media-source=tray-1}"
+        );
+
+        assert_eq!(
+            IppValue::RangeOfInteger { min: 1, max: 99 }
+                .ipptool_display()
+                .to_string(),
+            "1-99"
+        );
+        let resolution = |cross_feed, feed| IppValue::Resolution {
+            cross_feed,
+            feed,
+            units: 3,
+        };
+        assert_eq!(resolution(600, 600).ipptool_display().to_string(), "600dpi");
+        assert_eq!(resolution(600, 300).ipptool_display().to_string(), "600x300dpi");
+    }
+
+    #[test]
+    fn test_date_time_to_rfc3339() {
+        let date_time = |utc_dir, utc_hours, utc_mins| IppValue::DateTime {
+            year: 2020,
+            month: 2,
+            day: 13,
+            hour: 12,
+            minutes: 34,
+            seconds: 22,
+            deci_seconds: 1,
+            utc_dir,
+            utc_hours,
+            utc_mins,
+        };
+        assert_eq!(
+            date_time('+', 1, 30).to_rfc3339().as_deref(),
+            Some("2020-02-13T12:34:22.1+01:30")
+        );
+        assert_eq!(
+            date_time('-', 11, 0).to_rfc3339().as_deref(),
+            Some("2020-02-13T12:34:22.1-11:00")
+        );
+
+        let padded = IppValue::DateTime {
+            year: 987,
+            month: 1,
+            day: 2,
+            hour: 3,
+            minutes: 4,
+            seconds: 5,
+            deci_seconds: 0,
+            utc_dir: '+',
+            utc_hours: 0,
+            utc_mins: 0,
+        };
+        assert_eq!(padded.to_rfc3339().as_deref(), Some("0987-01-02T03:04:05.0+00:00"));
+
+        assert_eq!(IppValue::Integer(1).to_rfc3339(), None);
+    }
+
+    #[test]
+    fn test_date_time_display_padding() {
+        let value = IppValue::DateTime {
+            year: 2020,
+            month: 2,
+            day: 3,
+            hour: 4,
+            minutes: 5,
+            seconds: 6,
+            deci_seconds: 1,
+            utc_dir: '+',
+            utc_hours: 1,
+            utc_mins: 0,
+        };
+        assert_eq!(value.to_string(), "2020-02-03,04:05:06.1,+1utc");
+    }
+
+    #[test]
+    fn test_single_range() {
+        let value = IppValue::from_ranges(&[(1, 99)]).unwrap();
+        assert_eq!(value, IppValue::RangeOfInteger { min: 1, max: 99 });
+        assert_eq!(value.to_bytes(), vec![0, 8, 0, 0, 0, 1, 0, 0, 0, 99]);
+        assert_eq!(value.as_ranges(), Some(vec![(1, 99)]));
+    }
+
+    #[test]
+    fn test_multiple_ranges() {
+        let attr = IppAttribute::with_name("page-ranges", IppValue::from_ranges(&[(1, 5), (8, 8)]).unwrap()).unwrap();
+        let buf = attr.to_bytes().to_vec();
+        let mut expected = vec![0x33, 0, 11];
+        expected.extend(b"page-ranges");
+        expected.extend([0, 8, 0, 0, 0, 1, 0, 0, 0, 5]);
+        expected.extend([0x33, 0, 0, 0, 8, 0, 0, 0, 8, 0, 0, 0, 8]);
+        assert_eq!(buf, expected);
+
+        let mut data = vec![1, 1, 0, 0, 0, 0, 0, 0, 2];
+        data.extend(buf);
+        data.push(3);
+        let res = IppParser::new(IppReader::new(io::Cursor::new(data))).parse().unwrap();
+        let group = res.attributes().groups_of(DelimiterTag::JobAttributes).next().unwrap();
+        let value = group.attributes()["page-ranges"].value();
+        assert_eq!(value.as_ranges(), Some(vec![(1, 5), (8, 8)]));
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        let result = IppValue::from_ranges(&[(1, 5), (9, 8)]);
+        assert!(matches!(result, Err(IppParseError::InvalidRange { min: 9, max: 8 })));
+        assert!(matches!(IppValue::from_ranges(&[]), Err(IppParseError::EmptyArray)));
+
+        assert_eq!(IppValue::RangeOfInteger { min: 9, max: 8 }.as_ranges(), None);
+        assert_eq!(
+            IppValue::Array(vec![IppValue::RangeOfInteger { min: 1, max: 2 }, IppValue::Integer(3)]).as_ranges(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_collection_get_insert() {
+        let name = |s: &str| IppName::try_from(s).unwrap();
+        let media_size = IppValue::Collection(IppCollection::from_iter([
+            (name("x-dimension"), IppValue::Integer(21000)),
+            (name("y-dimension"), IppValue::Integer(29700)),
+        ]));
+        let mut media_col = IppValue::Collection(IppCollection::from_iter([(name("media-size"), media_size)]));
+
+        assert_eq!(
+            media_col.collection_insert(name("media-type"), IppValue::Keyword("stationery".try_into().unwrap())),
+            None
+        );
+        assert_eq!(
+            media_col.collection_get("media-type"),
+            Some(&IppValue::Keyword("stationery".try_into().unwrap()))
+        );
+        assert!(media_col.collection_get("media-color").is_none());
+
+        assert_eq!(
+            media_col.collection_path("media-size/y-dimension"),
+            Some(&IppValue::Integer(29700))
+        );
+
+        let mut integer = IppValue::Integer(1);
+        assert!(integer.collection_get("x-dimension").is_none());
+        assert!(
+            integer
+                .collection_insert(name("x-dimension"), IppValue::Integer(2))
+                .is_none()
+        );
+        assert_eq!(integer, IppValue::Integer(1));
+    }
+
+    #[test]
+    fn test_get_path() {
+        let name = |s: &str| IppName::try_from(s).unwrap();
+        let media_col = IppValue::Collection(IppCollection::from_iter([
+            (
+                name("media-size"),
+                IppValue::Collection(IppCollection::from_iter([
+                    (name("x-dimension"), IppValue::Integer(21000)),
+                    (name("y-dimension"), IppValue::Integer(29700)),
+                ])),
+            ),
+            (name("media-source"), IppValue::Keyword("tray-1".try_into().unwrap())),
+        ]));
+
+        assert_eq!(
+            media_col.get_path("media-size/x-dimension"),
+            Some(&IppValue::Integer(21000))
+        );
+        assert_eq!(
+            media_col.get_path("media-source"),
+            Some(&IppValue::Keyword("tray-1".try_into().unwrap()))
+        );
+
+        assert!(media_col.get_path("media-type").is_none());
+        assert!(media_col.get_path("media-size/z-dimension").is_none());
+        assert!(media_col.get_path("media-source/x-dimension").is_none());
+        assert!(media_col.get_path("media-size/x-dimension/value").is_none());
+        assert!(media_col.get_path("").is_none());
+        assert!(IppValue::Integer(1).get_path("x-dimension").is_none());
+    }
+
+    #[test]
+    fn test_value_count() {
+        let scalar = IppValue::Integer(1);
+        assert!(!scalar.is_multivalue());
+        assert_eq!(scalar.value_count(), 1);
+
+        let array = IppValue::Array(vec![IppValue::Integer(1), IppValue::Integer(2), IppValue::Integer(3)]);
+        assert!(array.is_multivalue());
+        assert_eq!(array.value_count(), 3);
+
+        let collection = IppValue::Collection(IppCollection::from_iter([
+            ("x-dimension".try_into().unwrap(), IppValue::Integer(21000)),
+            ("y-dimension".try_into().unwrap(), IppValue::Integer(29700)),
+        ]));
+        assert!(!collection.is_multivalue());
+        assert_eq!(collection.value_count(), 2);
+    }
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_text_value_tiers_equal() {
+        let short = IppTextValue::Short("printer".try_into().unwrap());
+        let long = IppTextValue::Long("printer".try_into().unwrap());
+        assert_eq!(short, long);
+        assert_eq!(hash_of(&short), hash_of(&long));
+        assert_ne!(short, IppTextValue::Long("printers".try_into().unwrap()));
+
+        let short = IppValue::TextWithoutLanguage(short);
+        let long = IppValue::TextWithoutLanguage(long);
+        assert_eq!(short, long);
+        assert_eq!(hash_of(&short), hash_of(&long));
+
+        let set = std::collections::HashSet::from([short]);
+        assert!(set.contains(&long));
+    }
+
+    #[test]
+    fn test_collection_member_names() {
+        let collection = |name: &str| {
+            IppValue::Collection(IppCollection::from_iter([(
+                name.try_into().unwrap(),
+                IppValue::Integer(21000),
+            )]))
+        };
+
+        let valid = collection("x-dimension");
+        assert!(valid.validate_member_names().is_ok());
+        assert_eq!(valid.try_to_bytes().unwrap(), valid.to_bytes());
+
+        let invalid = collection("X Dimension");
+        assert!(matches!(
+            invalid.try_to_bytes(),
+            Err(IppParseError::InvalidMemberName(ref name)) if name == "X Dimension"
+        ));
+
+        let nested = IppValue::Array(vec![IppValue::Collection(IppCollection::from_iter([(
+            "media-size".try_into().unwrap(),
+            collection("1st"),
+        )]))]);
+        assert!(matches!(
+            nested.validate_member_names(),
+            Err(IppParseError::InvalidMemberName(ref name)) if name == "1st"
+        ));
+    }
 }
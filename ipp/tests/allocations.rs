@@ -0,0 +1,81 @@
+//!
+//! Allocation count regression test for the parser.
+//! Lives in its own test binary because it installs a counting global allocator.
+//!
+#![cfg(feature = "std")]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use ipp::{
+    model::{DelimiterTag, ValueTag},
+    parser::IppParser,
+    reader::IppReader,
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Get-Jobs style response with a given number of single-valued integer attributes in each of the job groups
+fn get_jobs_response(jobs: usize, attributes: usize) -> Vec<u8> {
+    let mut job = vec![u8::from(DelimiterTag::JobAttributes)];
+    for i in 0..attributes {
+        let name = format!("attr-{i:04}");
+        job.push(ValueTag::Integer as u8);
+        job.extend((name.len() as u16).to_be_bytes());
+        job.extend(name.as_bytes());
+        job.extend(4u16.to_be_bytes());
+        job.extend((i as i32).to_be_bytes());
+    }
+
+    let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+    data.extend(job.repeat(jobs));
+    data.push(u8::from(DelimiterTag::EndOfAttributes));
+    data
+}
+
+fn count_allocations(data: Vec<u8>) -> usize {
+    let cursor = io::Cursor::new(data);
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let res = IppParser::new(IppReader::new(cursor)).parse().unwrap();
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    drop(res);
+    after - before
+}
+
+#[test]
+fn test_single_value_allocations() {
+    let small = count_allocations(get_jobs_response(10, 20));
+    let large = count_allocations(get_jobs_response(10, 40));
+
+    // marginal allocations for each additional attribute: attribute name, value buffer, group key
+    // and amortized hash map growth. Single values must not allocate a value list.
+    let per_attribute = (large - small) as f64 / 200.0;
+    assert!(
+        per_attribute < 4.0,
+        "too many allocations per attribute: {per_attribute:.2}"
+    );
+}
@@ -0,0 +1,210 @@
+//!
+//! Wire-level regression tests against the IPP response dumps stored in `tests/fixtures`.
+//! The dumps in `tests/fixtures` are synthetic, they are assembled to follow the layout of CUPS responses.
+//! Real captures are stored in `tests/fixtures/captures` and are all checked by `test_captures`.
+//! Every dump must parse without errors and every decoded attribute must re-encode to the exact bytes
+//! it was decoded from within its group. Attribute order within a group is not significant and is not checked.
+//!
+#![cfg(feature = "std")]
+
+use std::{
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use ipp::{
+    attribute::IppAttribute,
+    model::{DelimiterTag, PrinterState, StatusCode},
+    parser::IppParser,
+    reader::IppReader,
+    request::IppRequestResponse,
+    value::IppValue,
+};
+
+fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn read(path: &Path) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+}
+
+fn fixture(name: &str) -> Vec<u8> {
+    read(&fixtures_dir().join(name))
+}
+
+fn parse(data: &[u8]) -> IppRequestResponse {
+    IppParser::new(IppReader::new(io::Cursor::new(data.to_vec())))
+        .parse_buffered()
+        .unwrap()
+}
+
+// Group tag and byte ranges of the encoded attributes of one group, keyed by attribute name
+struct WireGroup {
+    tag: u8,
+    attributes: Vec<(String, Range<usize>)>,
+}
+
+// Split the message into groups and every group into the encoded attributes.
+// A value with a name starts a new attribute, additional values and collection members have empty names.
+fn wire_groups(data: &[u8]) -> Vec<WireGroup> {
+    let u16_at = |pos: usize| u16::from_be_bytes([data[pos], data[pos + 1]]) as usize;
+
+    let mut groups: Vec<WireGroup> = Vec::new();
+    let mut pos = 8;
+    loop {
+        let tag = data[pos];
+        if tag == u8::from(DelimiterTag::EndOfAttributes) {
+            return groups;
+        }
+        if tag < 0x10 {
+            groups.push(WireGroup {
+                tag,
+                attributes: Vec::new(),
+            });
+            pos += 1;
+            continue;
+        }
+
+        let name_len = u16_at(pos + 1);
+        let name = String::from_utf8(data[pos + 3..pos + 3 + name_len].to_vec()).unwrap();
+        let end = pos + 3 + name_len + 2 + u16_at(pos + 3 + name_len);
+
+        let attributes = &mut groups.last_mut().expect("value outside of a group").attributes;
+        if name.is_empty() {
+            attributes.last_mut().expect("value without a name").1.end = end;
+        } else {
+            attributes.push((name, pos..end));
+        }
+        pos = end;
+    }
+}
+
+// Check that the response re-encodes to the same bytes, apart from the order of attributes in each group
+fn assert_round_trip(name: &str, data: &[u8]) -> IppRequestResponse {
+    let response = parse(data);
+
+    let encoded = response.to_bytes();
+    assert_eq!(encoded.len(), data.len(), "{name}: encoded length differs");
+    assert_eq!(encoded[..8], data[..8], "{name}: header differs");
+
+    let wire = wire_groups(data);
+    assert_eq!(
+        wire.len(),
+        response.attributes().groups().len(),
+        "{name}: group count differs"
+    );
+    for (group, wire) in response.attributes().groups().iter().zip(&wire) {
+        assert_eq!(u8::from(group.tag()), wire.tag, "{name}: group tag differs");
+        assert_eq!(
+            group.attributes().len(),
+            wire.attributes.len(),
+            "{name}: attribute count differs"
+        );
+        for (attr_name, range) in &wire.attributes {
+            let attr = &group.attributes()[attr_name.as_str()];
+            assert_eq!(
+                attr.to_bytes()[..],
+                data[range.clone()],
+                "{name}: attribute {attr_name} re-encodes differently: {:?}",
+                attr.value()
+            );
+        }
+    }
+
+    let reparsed = parse(&encoded);
+    assert_eq!(
+        reparsed.attributes().groups().len(),
+        response.attributes().groups().len()
+    );
+    for (a, b) in reparsed
+        .attributes()
+        .groups()
+        .iter()
+        .zip(response.attributes().groups())
+    {
+        assert_eq!(a.tag(), b.tag());
+        assert_eq!(a.attributes().len(), b.attributes().len());
+        for (name, attr) in b.attributes() {
+            assert_eq!(a.attributes()[name].value(), attr.value(), "attribute {name}");
+        }
+    }
+
+    response
+}
+
+#[test]
+fn test_cups_get_printers() {
+    let response = assert_round_trip("cups-get-printers.ipp", &fixture("cups-get-printers.ipp"));
+    assert_eq!(response.header().status_code(), StatusCode::SuccessfulOk);
+
+    let printers = response
+        .attributes()
+        .groups_of(DelimiterTag::PrinterAttributes)
+        .collect::<Vec<_>>();
+    assert_eq!(printers.len(), 3);
+
+    let names = printers
+        .iter()
+        .map(|g| g.attributes()[IppAttribute::PRINTER_NAME].value().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Office", "PDF", "Photo"]);
+
+    let state = printers[2].attributes()[IppAttribute::PRINTER_STATE].value();
+    assert_eq!(state, &IppValue::Enum(PrinterState::Stopped as i32));
+}
+
+#[test]
+fn test_get_printer_attributes() {
+    let response = assert_round_trip("get-printer-attributes.ipp", &fixture("get-printer-attributes.ipp"));
+    assert_eq!(response.header().status_code(), StatusCode::SuccessfulOk);
+
+    let printer = response
+        .attributes()
+        .groups_of(DelimiterTag::PrinterAttributes)
+        .next()
+        .unwrap()
+        .attributes();
+
+    let database = printer[IppAttribute::MEDIA_COL_DATABASE].value().as_array().unwrap();
+    assert_eq!(database.len(), 3);
+    assert!(database.iter().all(IppValue::is_collection));
+
+    let resolutions = printer["printer-resolution-supported"].value().as_array().unwrap();
+    assert_eq!(resolutions.len(), 3);
+
+    assert_eq!(printer["printer-geo-location"].value(), &IppValue::NoValue);
+    assert_eq!(printer["printer-firmware-name"].value(), &IppValue::Unknown);
+    assert!(matches!(
+        printer["printer-current-time"].value(),
+        IppValue::DateTime { .. }
+    ));
+    assert!(matches!(
+        printer["printer-organization"].value(),
+        IppValue::TextWithLanguage { .. }
+    ));
+}
+
+// Captures are checked without any knowledge of their contents, adding a file is enough to cover it
+#[test]
+fn test_captures() {
+    let Ok(entries) = fs::read_dir(fixtures_dir().join("captures")) else {
+        return;
+    };
+
+    let mut paths = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ipp"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    for path in paths {
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let response = assert_round_trip(&name, &read(&path));
+        assert!(
+            response.header().status_code().is_success(),
+            "{name}: unsuccessful response"
+        );
+    }
+}